@@ -0,0 +1,20 @@
+use sudoku::bench::{benchmark, hard_puzzles};
+use sudoku::SolverKind;
+
+fn main() {
+    let puzzles = hard_puzzles();
+    let kinds = [
+        SolverKind::Backtracking,
+        SolverKind::Propagation,
+        SolverKind::Dlx,
+    ];
+    for t in benchmark(&puzzles, &kinds, 10) {
+        println!(
+            "{:<14} {:>5} puzzles  mean {:>10.2?}  slowest {:>10.2?}",
+            format!("{:?}", t.kind),
+            t.puzzles,
+            t.mean(),
+            t.slowest
+        );
+    }
+}
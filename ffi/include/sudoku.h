@@ -0,0 +1,97 @@
+#ifndef SUDOKU_H
+#define SUDOKU_H
+
+/* Generated from src/lib.rs by cbindgen. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+typedef enum SudokuStatus {
+  SUDOKU_STATUS_OK = 0,
+  SUDOKU_STATUS_NULL_POINTER = 1,
+  SUDOKU_STATUS_INVALID_UTF8 = 2,
+  SUDOKU_STATUS_PARSE_ERROR = 3,
+  SUDOKU_STATUS_INVALID_PUZZLE = 4,
+  SUDOKU_STATUS_NO_SOLUTION = 5,
+  SUDOKU_STATUS_BUFFER_TOO_SMALL = 6,
+  SUDOKU_STATUS_INVALID_ARGUMENT = 7,
+} SudokuStatus;
+
+typedef struct SudokuBoard SudokuBoard;
+
+/**
+ * A static, NUL terminated description of a `SudokuStatus` code. Codes
+ * outside the enum get a generic message.
+ */
+const char *sudoku_status_message(uint32_t status);
+
+/**
+ * Parses any format `sudoku::formats::parse` understands.
+ *
+ * # Safety
+ *
+ * `input` must be null or a NUL terminated string, and `out` null or
+ * writable.
+ */
+SudokuStatus sudoku_parse(const char *input, SudokuBoard **out);
+
+/**
+ * Writes a new, solved board to `out`. `board` is left unchanged.
+ *
+ * # Safety
+ *
+ * `board` must be null or a live board from this library, and `out` null
+ * or writable.
+ */
+SudokuStatus sudoku_solve(const SudokuBoard *board, SudokuBoard **out);
+
+/**
+ * `difficulty` is 0 (easy) to 3 (expert). The same seed always gives the
+ * same puzzle.
+ *
+ * # Safety
+ *
+ * `out` must be null or writable.
+ */
+SudokuStatus sudoku_generate(uint64_t seed, uint32_t difficulty, SudokuBoard **out);
+
+/**
+ * The difficulty as `sudoku_generate` numbers it, or -1 if `board` is null.
+ *
+ * # Safety
+ *
+ * `board` must be null or a live board from this library.
+ */
+int32_t sudoku_grade(const SudokuBoard *board);
+
+/**
+ * The value at column `x`, row `y` (both from 0), 0 if the cell is empty,
+ * or -1 if `board` is null or the position is off the board.
+ *
+ * # Safety
+ *
+ * `board` must be null or a live board from this library.
+ */
+int32_t sudoku_get(const SudokuBoard *board, uint8_t x, uint8_t y);
+
+/**
+ * Writes the board on one line, `.` for empty cells, plus a NUL. `len` is
+ * the size of `buf`, which needs one byte per cell and one more.
+ *
+ * # Safety
+ *
+ * `board` must be null or a live board from this library, and `buf` null
+ * or valid for writing `len` bytes.
+ */
+SudokuStatus sudoku_to_string(const SudokuBoard *board, char *buf, size_t len);
+
+/**
+ * Frees a board. Null is ignored.
+ *
+ * # Safety
+ *
+ * `board` must be null or a board from this library not yet freed.
+ */
+void sudoku_free(SudokuBoard *board);
+
+#endif /* SUDOKU_H */
@@ -0,0 +1,374 @@
+//! C bindings. Boards are opaque `SudokuBoard` pointers owned by the caller,
+//! who frees them with `sudoku_free`. Every fallible call returns a
+//! `SudokuStatus` and writes its result through an out pointer.
+//! `include/sudoku.h` declares the API. Regenerate it from this directory
+//! with `cbindgen --config cbindgen.toml --output include/sudoku.h`.
+
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+use sudoku::generator::Difficulty;
+use sudoku::{formats, SolveError, Sudoku, SudokuError};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SudokuStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    ParseError = 3,
+    InvalidPuzzle = 4,
+    NoSolution = 5,
+    BufferTooSmall = 6,
+    InvalidArgument = 7,
+}
+
+impl SudokuStatus {
+    const ALL: [SudokuStatus; 8] = [
+        SudokuStatus::Ok,
+        SudokuStatus::NullPointer,
+        SudokuStatus::InvalidUtf8,
+        SudokuStatus::ParseError,
+        SudokuStatus::InvalidPuzzle,
+        SudokuStatus::NoSolution,
+        SudokuStatus::BufferTooSmall,
+        SudokuStatus::InvalidArgument,
+    ];
+
+    /// C callers can pass any integer, which must not become an enum
+    /// value unchecked.
+    fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|&s| s as u32 == code)
+    }
+}
+
+pub struct SudokuBoard(Sudoku);
+
+impl From<SudokuError> for SudokuStatus {
+    fn from(e: SudokuError) -> Self {
+        match e {
+            SudokuError::ParseError(_) => SudokuStatus::ParseError,
+            _ => SudokuStatus::InvalidArgument,
+        }
+    }
+}
+
+impl From<SolveError> for SudokuStatus {
+    fn from(e: SolveError) -> Self {
+        match e {
+            SolveError::NoSolution => SudokuStatus::NoSolution,
+            _ => SudokuStatus::InvalidPuzzle,
+        }
+    }
+}
+
+fn boxed(board: Sudoku, out: *mut *mut SudokuBoard) -> SudokuStatus {
+    // SAFETY: callers checked `out` for null; the caller of the public
+    // function guarantees it is writable.
+    unsafe { *out = Box::into_raw(Box::new(SudokuBoard(board))) };
+    SudokuStatus::Ok
+}
+
+/// A static, NUL terminated description of a `SudokuStatus` code. Codes
+/// outside the enum get a generic message.
+#[no_mangle]
+pub extern "C" fn sudoku_status_message(status: u32) -> *const c_char {
+    let message: &'static CStr = match SudokuStatus::from_code(status) {
+        Some(SudokuStatus::Ok) => c"Ok.",
+        Some(SudokuStatus::NullPointer) => c"A required pointer was null.",
+        Some(SudokuStatus::InvalidUtf8) => c"Input is not valid UTF-8.",
+        Some(SudokuStatus::ParseError) => c"Input is not a sudoku the parser understands.",
+        Some(SudokuStatus::InvalidPuzzle) => c"Puzzle givens contradict each other.",
+        Some(SudokuStatus::NoSolution) => c"Puzzle has no solution.",
+        Some(SudokuStatus::BufferTooSmall) => c"Output buffer is too small.",
+        Some(SudokuStatus::InvalidArgument) => c"Argument out of range.",
+        None => c"Unknown status code.",
+    };
+    message.as_ptr()
+}
+
+/// Parses any format `sudoku::formats::parse` understands.
+///
+/// # Safety
+///
+/// `input` must be null or a NUL terminated string, and `out` null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_parse(
+    input: *const c_char,
+    out: *mut *mut SudokuBoard,
+) -> SudokuStatus {
+    if input.is_null() || out.is_null() {
+        return SudokuStatus::NullPointer;
+    }
+    let Ok(text) = CStr::from_ptr(input).to_str() else {
+        return SudokuStatus::InvalidUtf8;
+    };
+    match formats::parse(text) {
+        Ok(board) => boxed(board, out),
+        Err(e) => e.into(),
+    }
+}
+
+/// Writes a new, solved board to `out`. `board` is left unchanged.
+///
+/// # Safety
+///
+/// `board` must be null or a live board from this library, and `out` null
+/// or writable.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_solve(
+    board: *const SudokuBoard,
+    out: *mut *mut SudokuBoard,
+) -> SudokuStatus {
+    if board.is_null() || out.is_null() {
+        return SudokuStatus::NullPointer;
+    }
+    match (*board).0.solve_with_propagation() {
+        Ok(solved) => boxed(solved, out),
+        Err(e) => e.into(),
+    }
+}
+
+/// `difficulty` is 0 (easy) to 3 (expert). The same seed always gives the
+/// same puzzle.
+///
+/// # Safety
+///
+/// `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_generate(
+    seed: u64,
+    difficulty: u32,
+    out: *mut *mut SudokuBoard,
+) -> SudokuStatus {
+    if out.is_null() {
+        return SudokuStatus::NullPointer;
+    }
+    let difficulty = match difficulty {
+        0 => Difficulty::Easy,
+        1 => Difficulty::Medium,
+        2 => Difficulty::Hard,
+        3 => Difficulty::Expert,
+        _ => return SudokuStatus::InvalidArgument,
+    };
+    boxed(Sudoku::generate_with_seed(seed, difficulty), out)
+}
+
+/// The difficulty as `sudoku_generate` numbers it, or -1 if `board` is null.
+///
+/// # Safety
+///
+/// `board` must be null or a live board from this library.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_grade(board: *const SudokuBoard) -> i32 {
+    if board.is_null() {
+        return -1;
+    }
+    (*board).0.grade() as i32
+}
+
+/// The value at column `x`, row `y` (both from 0), 0 if the cell is empty,
+/// or -1 if `board` is null or the position is off the board.
+///
+/// # Safety
+///
+/// `board` must be null or a live board from this library.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_get(board: *const SudokuBoard, x: u8, y: u8) -> i32 {
+    if board.is_null() {
+        return -1;
+    }
+    let board = &(*board).0;
+    let side = board.size().side();
+    if x >= side || y >= side {
+        return -1;
+    }
+    board[sudoku::Pos::new_unchecked(x, y)]
+        .value()
+        .map_or(0, i32::from)
+}
+
+/// Writes the board on one line, `.` for empty cells, plus a NUL. `len` is
+/// the size of `buf`, which needs one byte per cell and one more.
+///
+/// # Safety
+///
+/// `board` must be null or a live board from this library, and `buf` null
+/// or valid for writing `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_to_string(
+    board: *const SudokuBoard,
+    buf: *mut c_char,
+    len: usize,
+) -> SudokuStatus {
+    if board.is_null() || buf.is_null() {
+        return SudokuStatus::NullPointer;
+    }
+    let line = (*board).0.to_line_string();
+    if line.len() >= len {
+        return SudokuStatus::BufferTooSmall;
+    }
+    ptr::copy_nonoverlapping(line.as_ptr(), buf.cast(), line.len());
+    *buf.add(line.len()) = 0;
+    SudokuStatus::Ok
+}
+
+/// Frees a board. Null is ignored.
+///
+/// # Safety
+///
+/// `board` must be null or a board from this library not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_free(board: *mut SudokuBoard) {
+    if !board.is_null() {
+        drop(Box::from_raw(board));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    const LINE: &str =
+        ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+
+    #[test]
+    fn parses_solves_and_frees() {
+        let input = CString::new(LINE).unwrap();
+        let mut board = ptr::null_mut();
+        let mut solved = ptr::null_mut();
+        let mut buf = [0 as c_char; 82];
+        unsafe {
+            assert_eq!(sudoku_parse(input.as_ptr(), &mut board), SudokuStatus::Ok);
+            assert_eq!(sudoku_get(board, 1, 0), 5);
+            assert_eq!(sudoku_get(board, 0, 0), 0);
+            assert_eq!(sudoku_get(board, 9, 0), -1);
+            assert_eq!(sudoku_grade(board), 0);
+            assert_eq!(sudoku_solve(board, &mut solved), SudokuStatus::Ok);
+            assert_eq!(
+                sudoku_to_string(solved, buf.as_mut_ptr(), 81),
+                SudokuStatus::BufferTooSmall
+            );
+            assert_eq!(
+                sudoku_to_string(solved, buf.as_mut_ptr(), buf.len()),
+                SudokuStatus::Ok
+            );
+            let text = CStr::from_ptr(buf.as_ptr()).to_str().unwrap();
+            assert!(text.starts_with("652483917"));
+            sudoku_free(board);
+            sudoku_free(solved);
+            sudoku_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn reports_errors_as_status_codes() {
+        let garbage = CString::new("not a sudoku").unwrap();
+        let clash = CString::new(LINE.replacen('.', "5", 1)).unwrap();
+        let mut board = ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                sudoku_parse(garbage.as_ptr(), &mut board),
+                SudokuStatus::ParseError
+            );
+            assert_eq!(
+                sudoku_parse(ptr::null(), &mut board),
+                SudokuStatus::NullPointer
+            );
+            assert_eq!(
+                sudoku_generate(1, 9, &mut board),
+                SudokuStatus::InvalidArgument
+            );
+            assert_eq!(sudoku_parse(clash.as_ptr(), &mut board), SudokuStatus::Ok);
+            let mut solved = ptr::null_mut();
+            assert_eq!(
+                sudoku_solve(board, &mut solved),
+                SudokuStatus::InvalidPuzzle
+            );
+            sudoku_free(board);
+            let message = CStr::from_ptr(sudoku_status_message(SudokuStatus::NoSolution as u32));
+            assert_eq!(message.to_str().unwrap(), "Puzzle has no solution.");
+            let message = CStr::from_ptr(sudoku_status_message(99));
+            assert_eq!(message.to_str().unwrap(), "Unknown status code.");
+        }
+    }
+
+    /// The C spelling cbindgen gives the Rust types used in signatures.
+    fn c_type(rust: &str) -> String {
+        let pointer = |target: &str, prefix: &str| {
+            let target = c_type(target);
+            let gap = if target.ends_with('*') { "" } else { " " };
+            format!("{prefix}{target}{gap}*")
+        };
+        if let Some(target) = rust.strip_prefix("*const ") {
+            return pointer(target, "const ");
+        }
+        if let Some(target) = rust.strip_prefix("*mut ") {
+            return pointer(target, "");
+        }
+        match rust {
+            "c_char" => "char",
+            "u8" => "uint8_t",
+            "u32" => "uint32_t",
+            "u64" => "uint64_t",
+            "i32" => "int32_t",
+            "usize" => "size_t",
+            other => other,
+        }
+        .to_string()
+    }
+
+    fn declare(c_type: String, name: &str) -> String {
+        let gap = if c_type.ends_with('*') { "" } else { " " };
+        format!("{c_type}{gap}{name}")
+    }
+
+    #[test]
+    fn header_matches_the_exports() {
+        let header = include_str!("../include/sudoku.h");
+        let source = include_str!("lib.rs").lines().collect::<Vec<_>>();
+        let mut exported = 0;
+        for (i, line) in source.iter().enumerate() {
+            if !line.starts_with("pub ") || !line.contains("extern \"C\" fn ") {
+                continue;
+            }
+            let end = i + source[i..].iter().position(|l| l.ends_with('{')).unwrap();
+            let signature = source[i..=end].join(" ");
+            let (head, rest) = signature.split_once('(').unwrap();
+            let name = head.rsplit(' ').next().unwrap();
+            let (params, ret) = rest.rsplit_once(')').unwrap();
+            let params = params
+                .split(',')
+                .filter_map(|p| p.split_once(':'))
+                .map(|(arg, ty)| declare(c_type(ty.trim()), arg.trim()))
+                .collect::<Vec<_>>();
+            let ret = match ret.split_once("->") {
+                Some((_, ty)) => c_type(ty.trim_end_matches('{').trim()),
+                None => "void".to_string(),
+            };
+            let docs = source[..i]
+                .iter()
+                .rev()
+                .skip_while(|l| l.starts_with("#["))
+                .take_while(|l| l.starts_with("///"))
+                .collect::<Vec<_>>();
+            let mut comment = String::from("/**\n");
+            for doc in docs.iter().rev() {
+                comment += &format!(" *{}\n", &doc[3..]);
+            }
+            comment += " */\n";
+            let declaration = format!("{}({});\n", declare(ret, name), params.join(", "));
+            assert!(
+                header.contains(&(comment + &declaration)),
+                "{name} is declared or documented differently"
+            );
+            exported += 1;
+        }
+        let declared = header.lines().filter(|l| l.ends_with(");")).count();
+        assert_eq!(declared, exported);
+        assert!(header.contains("/* Generated from src/lib.rs by cbindgen. */"));
+    }
+}
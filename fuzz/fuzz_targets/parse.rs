@@ -0,0 +1,29 @@
+//! Every parser must return an error rather than panic, whatever the input.
+
+#![no_main]
+
+use std::str::FromStr;
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::variants::Killer;
+use sudoku::{formats, Sudoku};
+
+fuzz_target!(|text: &str| {
+    if let Ok(board) = Sudoku::from_str(text) {
+        // Anything that parses must print back to something that parses
+        // to the same values.
+        let again = Sudoku::from_str(&board.to_line_string()).expect("round trip");
+        assert_eq!(again.to_line_string(), board.to_line_string());
+    }
+    let _ = formats::parse(text);
+    for format in [
+        formats::Format::Line,
+        formats::Format::Grid,
+        formats::Format::Sdk,
+        formats::Format::Ss,
+    ] {
+        let _ = formats::parse_as(text, format);
+    }
+    let _ = Killer::from_str(text);
+    let _ = formats::BankPuzzle::parse(text);
+});
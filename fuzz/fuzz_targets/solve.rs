@@ -0,0 +1,46 @@
+//! Solvers must handle any board, including ones whose givens clash, and
+//! must agree with each other.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sudoku::{SolveError, SolveOptions, SolverKind, Sudoku};
+
+#[derive(Debug, arbitrary::Arbitrary)]
+enum Input {
+    /// Any 81 values, wrapped into 0 to 9.
+    Raw([u8; 81]),
+    /// A board from the crate's own `Arbitrary`, which is always solvable.
+    Valid(Box<Sudoku>),
+}
+
+fuzz_target!(|input: Input| {
+    let board = match input {
+        Input::Raw(values) => match Sudoku::try_from(values.map(|v| v % 10)) {
+            Ok(board) => board,
+            Err(_) => return,
+        },
+        Input::Valid(board) => *board,
+    };
+    // Bounded so degenerate boards can't stall the fuzzer.
+    let options = SolveOptions {
+        max_nodes: Some(1_000),
+        ..SolveOptions::default()
+    };
+    match board.solve_with_options(&options) {
+        Ok(solution) => {
+            assert!(solution.is_solved());
+            assert!(board
+                .iter()
+                .zip(solution.iter())
+                .all(|(a, b)| a.value().is_none() || a.value() == b.value()));
+            assert!(board.count_solutions_with(SolverKind::Dlx, 2) >= 1);
+        }
+        // Only claimed after an exhaustive search, which DLX must repeat.
+        Err(SolveError::NoSolution | SolveError::InvalidPuzzle) => {
+            assert_eq!(board.count_solutions_with(SolverKind::Dlx, 1), 0);
+        }
+        Err(_) => {}
+    }
+    let _ = board.hint();
+});
@@ -0,0 +1,106 @@
+//! The `sudoku` Python module:
+//!
+//! ```python
+//! import sudoku
+//! board = sudoku.Sudoku(".5..83.17...")
+//! print(board.solve())
+//! [sudoku.grade(line) for line in open("puzzles.txt")]
+//! ```
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use ::sudoku::formats;
+
+fn value_error(e: impl ToString) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+#[pyclass(name = "Sudoku")]
+#[derive(Clone)]
+struct PySudoku(::sudoku::Sudoku);
+
+#[pymethods]
+impl PySudoku {
+    /// Parses any format the crate understands.
+    #[new]
+    fn new(text: &str) -> PyResult<Self> {
+        formats::parse(text).map(PySudoku).map_err(value_error)
+    }
+    /// The value at column `x`, row `y`, counting from 0, or None.
+    fn get(&self, x: u8, y: u8) -> PyResult<Option<u8>> {
+        let side = self.0.size().side();
+        if x >= side || y >= side {
+            return Err(value_error(::sudoku::SudokuError::OutOfBounds));
+        }
+        Ok(self.0[::sudoku::Pos::new_unchecked(x, y)].value())
+    }
+    /// Clears the cell when `value` is None.
+    #[pyo3(signature = (x, y, value = None))]
+    fn set(&mut self, x: u8, y: u8, value: Option<u8>) -> PyResult<()> {
+        let pos = ::sudoku::Pos::try_new(x, y).map_err(value_error)?;
+        self.0.set(pos, value).map_err(value_error)
+    }
+    fn solve(&self) -> PyResult<Self> {
+        self.0
+            .solve_with_propagation()
+            .map(PySudoku)
+            .map_err(value_error)
+    }
+    fn count_solutions(&self, limit: usize) -> usize {
+        self.0.count_solutions(limit)
+    }
+    fn grade(&self) -> String {
+        format!("{:?}", self.0.grade())
+    }
+    fn is_solved(&self) -> bool {
+        self.0.is_solved()
+    }
+    fn is_valid(&self) -> bool {
+        self.0.is_valid()
+    }
+    /// The 81-character line form, `.` for empty cells.
+    fn line(&self) -> String {
+        self.0.to_line_string()
+    }
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+    fn __repr__(&self) -> String {
+        format!("Sudoku({:?})", self.0.to_line_string())
+    }
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+/// Solves a puzzle given as text, returning the solution's line form.
+#[pyfunction]
+fn solve(text: &str) -> PyResult<String> {
+    PySudoku::new(text)?.solve().map(|s| s.line())
+}
+
+/// A new puzzle for `difficulty`: easy, medium, hard or expert. With a
+/// `seed` the same puzzle comes back every time.
+#[pyfunction]
+#[pyo3(signature = (difficulty = "medium", seed = None))]
+fn generate(difficulty: &str, seed: Option<u64>) -> PyResult<PySudoku> {
+    let difficulty = difficulty.parse().map_err(value_error)?;
+    Ok(PySudoku(match seed {
+        Some(seed) => ::sudoku::Sudoku::generate_with_seed(seed, difficulty),
+        None => ::sudoku::Sudoku::generate(difficulty),
+    }))
+}
+
+#[pyfunction]
+fn grade(text: &str) -> PyResult<String> {
+    Ok(PySudoku::new(text)?.grade())
+}
+
+#[pymodule]
+fn sudoku(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PySudoku>()?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(generate, m)?)?;
+    m.add_function(wrap_pyfunction!(grade, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,196 @@
+//! JSON endpoints over the solver, generator and grader:
+//!
+//! - `POST /solve` `{"puzzle": "..."}` → `{"solution": "..."}`
+//! - `POST /generate` `{"difficulty": "hard", "seed": 7}` → `{"puzzle": "...", "solution": "..."}`
+//! - `POST /grade` `{"puzzle": "..."}` → `{"difficulty": "Medium"}`
+//! - `POST /hint` `{"puzzle": "..."}` → `{"technique": "NakedSingle", ..., "explanation": "..."}` or `null`
+//!
+//! Puzzles may be in any format `sudoku::formats::parse` reads; responses
+//! use the 81-character line form. Errors are `{"error": "..."}` with a 400
+//! status, or 503 when a solve runs out of time.
+
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use sudoku::generator::Difficulty;
+use sudoku::{formats, SolveError, SolveOptions, Sudoku};
+
+/// How long `/solve` may search before giving up.
+pub const SOLVE_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/solve", post(solve))
+        .route("/generate", post(generate))
+        .route("/grade", post(grade))
+        .route("/hint", post(hint))
+}
+
+pub struct Error(StatusCode, String);
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct Body {
+            error: String,
+        }
+        (self.0, Json(Body { error: self.1 })).into_response()
+    }
+}
+
+impl From<SolveError> for Error {
+    fn from(e: SolveError) -> Self {
+        let status = match e {
+            SolveError::TimedOut | SolveError::Cancelled => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Error(status, e.to_string())
+    }
+}
+
+impl From<sudoku::SudokuError> for Error {
+    fn from(e: sudoku::SudokuError) -> Self {
+        Error(StatusCode::BAD_REQUEST, e.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PuzzleRequest {
+    pub puzzle: String,
+}
+
+#[derive(Serialize)]
+pub struct SolveResponse {
+    pub solution: String,
+}
+
+async fn solve(Json(req): Json<PuzzleRequest>) -> Result<Json<SolveResponse>, Error> {
+    let options = SolveOptions {
+        timeout: Some(SOLVE_TIMEOUT),
+        ..SolveOptions::default()
+    };
+    let solution = formats::parse(&req.puzzle)?.solve_async(options).await?;
+    Ok(Json(SolveResponse {
+        solution: solution.to_line_string(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct GenerateRequest {
+    #[serde(default = "medium")]
+    pub difficulty: String,
+    pub seed: Option<u64>,
+}
+
+fn medium() -> String {
+    "medium".into()
+}
+
+#[derive(Serialize)]
+pub struct GenerateResponse {
+    pub puzzle: String,
+    pub solution: String,
+}
+
+async fn generate(Json(req): Json<GenerateRequest>) -> Result<Json<GenerateResponse>, Error> {
+    let difficulty: Difficulty = req.difficulty.parse()?;
+    // Generating digs through many candidate puzzles; keep it off the
+    // async workers.
+    let puzzle = tokio::task::spawn_blocking(move || match req.seed {
+        Some(seed) => Sudoku::generate_with_seed(seed, difficulty),
+        None => Sudoku::generate(difficulty),
+    })
+    .await
+    .map_err(|e| Error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let solution = puzzle.solve_with_propagation()?;
+    Ok(Json(GenerateResponse {
+        puzzle: puzzle.to_line_string(),
+        solution: solution.to_line_string(),
+    }))
+}
+
+#[derive(Serialize)]
+pub struct GradeResponse {
+    pub difficulty: String,
+}
+
+async fn grade(Json(req): Json<PuzzleRequest>) -> Result<Json<GradeResponse>, Error> {
+    let puzzle = formats::parse(&req.puzzle)?;
+    Ok(Json(GradeResponse {
+        difficulty: format!("{:?}", puzzle.grade()),
+    }))
+}
+
+/// Cells are 0-based `[x, y]` pairs.
+#[derive(Serialize)]
+pub struct HintResponse {
+    pub technique: String,
+    pub positions: Vec<[u8; 2]>,
+    pub placement: Option<([u8; 2], u8)>,
+    pub eliminations: Vec<([u8; 2], u8)>,
+    pub explanation: String,
+}
+
+async fn hint(Json(req): Json<PuzzleRequest>) -> Result<Json<Option<HintResponse>>, Error> {
+    let puzzle = formats::parse(&req.puzzle)?;
+    let xy = |p: sudoku::Pos| [p.x(), p.y()];
+    Ok(Json(puzzle.hint().map(|h| HintResponse {
+        technique: format!("{:?}", h.technique),
+        positions: h.positions.iter().map(|&p| xy(p)).collect(),
+        placement: h.placement.map(|(p, v)| (xy(p), v)),
+        eliminations: h.eliminations.iter().map(|&(p, v)| (xy(p), v)).collect(),
+        explanation: h.explain(),
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn post(path: &str, body: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::post(path)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_owned()))
+            .unwrap();
+        let response = router().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    const LINE: &str =
+        ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+
+    #[tokio::test]
+    async fn solves_grades_and_hints() {
+        let body = format!(r#"{{"puzzle": "{LINE}"}}"#);
+        let (status, json) = post("/solve", &body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["solution"].as_str().unwrap().starts_with("652483917"));
+        let (_, json) = post("/grade", &body).await;
+        assert_eq!(json["difficulty"], "Easy");
+        let (_, json) = post("/hint", &body).await;
+        assert!(json["technique"].is_string());
+        assert!(json["explanation"].as_str().unwrap().ends_with('.'));
+    }
+
+    #[tokio::test]
+    async fn generates_and_reports_errors() {
+        let (status, json) = post("/generate", r#"{"difficulty": "easy", "seed": 1}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["puzzle"].as_str().unwrap().len(), 81);
+        let (status, json) = post("/solve", r#"{"puzzle": "123"}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(json["error"].is_string());
+    }
+}
@@ -0,0 +1,12 @@
+//! `sudoku-server [ADDR]` serves the endpoints of [`sudoku_server::router`]
+//! on `ADDR`, by default `127.0.0.1:3000`.
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:3000".into());
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    eprintln!("listening on {addr}");
+    axum::serve(listener, sudoku_server::router()).await
+}
@@ -0,0 +1,238 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Candidates, Pos, SolveError, Sudoku};
+
+/// The cells a puzzle with several solutions leaves open. See
+/// [`Sudoku::ambiguous_cells`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ambiguity {
+    /// Each cell that takes different values in different solutions, in
+    /// board order, with every value it takes in some solution.
+    pub cells: Vec<(Pos, Candidates)>,
+    /// Solutions that between them show every one of those values, starting
+    /// with the one [`Sudoku::solve`] finds.
+    pub solutions: Vec<Sudoku>,
+}
+
+impl Ambiguity {
+    /// True if the puzzle has exactly one solution.
+    pub fn is_unique(&self) -> bool {
+        self.cells.is_empty()
+    }
+    /// A solution with `value` at `pos`, if there is one.
+    pub fn example(&self, pos: Pos, value: u8) -> Option<&Sudoku> {
+        self.solutions
+            .iter()
+            .find(|s| s[pos].value() == Some(value))
+    }
+}
+
+impl Sudoku {
+    /// Works out every value each empty cell takes across all solutions,
+    /// without listing the solutions themselves. The open cells hold the
+    /// puzzle's unavoidable sets: groups of cells whose values can be
+    /// swapped around in some solution, so each set needs a clue for the
+    /// puzzle to become unique.
+    ///
+    /// Fails like [`Sudoku::solve`] on a puzzle with no solution.
+    pub fn ambiguous_cells(&self) -> Result<Ambiguity, SolveError> {
+        let first = self.solve()?;
+        let size = self.size();
+        // The values some solution found so far puts in each cell.
+        let mut seen = vec![Candidates::empty(); size.cell_count()];
+        let mut solutions = Vec::new();
+        let mut record = |solution: Sudoku, seen: &mut [Candidates]| {
+            for cell in solution.iter() {
+                if let Some(v) = cell.value() {
+                    seen[size.index_of(cell.position())].insert(v);
+                }
+            }
+            solutions.push(solution);
+        };
+        record(first, &mut seen);
+
+        let mut trial = self.clone();
+        for cell in self.iter().filter(|c| c.value().is_none()) {
+            let pos = cell.position();
+            for v in cell.get_candidates(self).iter() {
+                if seen[size.index_of(pos)].contains(v) {
+                    continue;
+                }
+                trial.set(pos, Some(v)).expect("the cell is empty");
+                if let Ok(solution) = trial.solve_with_propagation() {
+                    record(solution, &mut seen);
+                }
+            }
+            trial.set(pos, None).expect("the cell is empty");
+        }
+
+        let cells = self
+            .iter()
+            .filter(|c| c.value().is_none())
+            .map(|c| (c.position(), seen[size.index_of(c.position())]))
+            .filter(|(_, values)| values.count() > 1)
+            .collect();
+        Ok(Ambiguity { cells, solutions })
+    }
+
+    /// The smallest sets of extra clues, of at most `max` cells each, that
+    /// leave the puzzle with one solution: the one [`Sudoku::solve`] finds.
+    /// Cells in board order, sets in no particular order. Empty if every
+    /// fix needs more than `max` clues, and a single empty set for a puzzle
+    /// that is unique already.
+    pub fn suggest_clues(&self, max: usize) -> Result<Vec<Vec<(Pos, u8)>>, SolveError> {
+        let target = self.solve()?;
+        for k in 0..=max {
+            let mut found = Vec::new();
+            extend(&mut self.clone(), &target, &mut Vec::new(), k, &mut found);
+            if !found.is_empty() {
+                return Ok(found);
+            }
+        }
+        Ok(Vec::new())
+    }
+}
+
+// Every fix must tell the target apart from each other solution, so it has
+// a clue in one of the cells where they differ: try each of those in turn
+// against some other solution of the board so far.
+fn extend(
+    board: &mut Sudoku,
+    target: &Sudoku,
+    chosen: &mut Vec<(Pos, u8)>,
+    k: usize,
+    found: &mut Vec<Vec<(Pos, u8)>>,
+) {
+    let Some(other) = board.solutions().find(|s| s != target) else {
+        let mut fix = chosen.clone();
+        fix.sort_by_key(|&(pos, _)| board.size().index_of(pos));
+        if !found.contains(&fix) {
+            found.push(fix);
+        }
+        return;
+    };
+    if chosen.len() == k {
+        return;
+    }
+    for cell in target.iter() {
+        let pos = cell.position();
+        if other[pos].value() == cell.value() {
+            continue;
+        }
+        let v = cell.value().expect("solutions are full");
+        board.set(pos, Some(v)).expect("the cell is empty");
+        chosen.push((pos, v));
+        extend(board, target, chosen, k, found);
+        chosen.pop();
+        board.set(pos, None).expect("the cell is empty");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::Size;
+
+    // Four cells in two rows of a band and two columns of different
+    // stacks, holding `a b / b a`: emptying them leaves two solutions.
+    fn deadly_rectangle(grid: &Sudoku) -> Option<[Pos; 4]> {
+        let at = |x, y| grid[Pos::new_unchecked(x, y)].value();
+        for y1 in 0..9 {
+            for y2 in (y1 + 1)..(y1 / 3 * 3 + 3) {
+                for x1 in 0..9 {
+                    for x2 in (x1 / 3 * 3 + 3)..9 {
+                        if at(x1, y1) == at(x2, y2) && at(x2, y1) == at(x1, y2) {
+                            return Some(
+                                [(x1, y1), (x2, y1), (x1, y2), (x2, y2)]
+                                    .map(|(x, y)| Pos::new_unchecked(x, y)),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn finds_an_unavoidable_set() {
+        let (grid, cells) = (0..)
+            .map(Sudoku::random_solution_with_seed)
+            .find_map(|g| deadly_rectangle(&g).map(|cells| (g, cells)))
+            .unwrap();
+        let mut puzzle = grid.clone();
+        for pos in cells {
+            puzzle.set_forced(pos, None).unwrap();
+        }
+        let ambiguity = puzzle.ambiguous_cells().unwrap();
+        assert!(!ambiguity.is_unique());
+        assert_eq!(
+            ambiguity.cells.iter().map(|&(p, _)| p).collect::<Vec<_>>(),
+            cells
+        );
+        for &(pos, values) in &ambiguity.cells {
+            assert_eq!(values.count(), 2);
+            for v in values.iter() {
+                let example = ambiguity.example(pos, v).unwrap();
+                assert!(example.is_solved());
+            }
+        }
+        assert_eq!(ambiguity.solutions.len(), 2);
+        assert_eq!(ambiguity.solutions[0], puzzle.solve().unwrap());
+    }
+
+    #[test]
+    fn suggests_clues_that_restore_uniqueness() {
+        let (grid, cells) = (0..)
+            .map(Sudoku::random_solution_with_seed)
+            .find_map(|g| deadly_rectangle(&g).map(|cells| (g, cells)))
+            .unwrap();
+        let mut puzzle = grid.clone();
+        for pos in cells {
+            puzzle.set_forced(pos, None).unwrap();
+        }
+        assert_eq!(puzzle.suggest_clues(0), Ok(Vec::new()));
+        let target = puzzle.solve().unwrap();
+        let fixes = puzzle.suggest_clues(3).unwrap();
+        assert_eq!(fixes.len(), 4);
+        for fix in &fixes {
+            let &[(pos, v)] = fix.as_slice() else {
+                panic!("expected a single clue, got {fix:?}");
+            };
+            assert_eq!(target[pos].value(), Some(v));
+            let mut fixed = puzzle.clone();
+            fixed.set(pos, Some(v)).unwrap();
+            assert_eq!(fixed.count_solutions(2), 1);
+        }
+
+        // Six empty rows need far more than two clues.
+        let mut wider = ".".repeat(81);
+        wider.replace_range(..27, &target.to_line_string()[..27]);
+        let open = Sudoku::from_str(&wider).unwrap();
+        assert!(open.suggest_clues(2).unwrap().is_empty());
+        assert_eq!(grid.suggest_clues(2), Ok(vec![Vec::new()]));
+    }
+
+    #[test]
+    fn unique_puzzles_leave_nothing_open() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let ambiguity = s.ambiguous_cells().unwrap();
+        assert!(ambiguity.is_unique());
+        assert_eq!(ambiguity.solutions, vec![s.solve().unwrap()]);
+
+        let open = Sudoku::new(Size::new(2).unwrap())
+            .ambiguous_cells()
+            .unwrap();
+        assert_eq!(open.cells.len(), 16);
+        assert!(open.cells.iter().all(|(_, values)| values.count() == 4));
+
+        let clash = Sudoku::from_str(&alloc::format!("11{}", ".".repeat(79))).unwrap();
+        assert_eq!(clash.ambiguous_cells(), Err(SolveError::InvalidPuzzle));
+    }
+}
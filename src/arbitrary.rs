@@ -0,0 +1,78 @@
+//! `arbitrary::Arbitrary` for fuzzing and property tests. Everything is
+//! classic 9x9 and always valid: positions on the board, values in range,
+//! and boards without clashes.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::rng::Rng;
+use crate::{solver, Candidates, Cell, Pos, Size, Sudoku};
+
+impl<'a> Arbitrary<'a> for Pos {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Pos::new_unchecked(
+            u.int_in_range(0..=8)?,
+            u.int_in_range(0..=8)?,
+        ))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Cell {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let value = match u.int_in_range(0..=9)? {
+            0 => None,
+            v => Some(v),
+        };
+        let mut cell = Cell::new_unchecked(value, Pos::arbitrary(u)?);
+        if value.is_none() {
+            cell.set_pencil_marks(Candidates::from_bits(u.int_in_range(0..=0x1ff)? << 1));
+        }
+        Ok(cell)
+    }
+}
+
+/// A completely filled, valid grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolvedGrid(pub Sudoku);
+
+impl<'a> Arbitrary<'a> for SolvedGrid {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut rng = Rng::from_seed(u.arbitrary()?);
+        Ok(SolvedGrid(solver::random_solution(&mut rng)))
+    }
+}
+
+/// A solvable puzzle: some of the cells of a [`SolvedGrid`], kept as
+/// givens. It may have more than one solution.
+impl<'a> Arbitrary<'a> for Sudoku {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let SolvedGrid(solution) = u.arbitrary()?;
+        let mut values = alloc::vec::Vec::with_capacity(81);
+        for cell in solution.iter() {
+            values.push(if u.arbitrary()? { cell.value() } else { None });
+        }
+        Ok(Sudoku::from_values(Size::CLASSIC, values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_valid_values() {
+        let bytes = (0..4096u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect::<Vec<_>>();
+        let mut u = Unstructured::new(&bytes);
+        for _ in 0..8 {
+            let pos = Pos::arbitrary(&mut u).unwrap();
+            assert!(pos.x() < 9 && pos.y() < 9);
+            let cell = Cell::arbitrary(&mut u).unwrap();
+            assert!(cell.value().is_none_or(|v| (1..=9).contains(&v)));
+            let SolvedGrid(grid) = u.arbitrary().unwrap();
+            assert!(grid.is_solved());
+            let puzzle = Sudoku::arbitrary(&mut u).unwrap();
+            assert!(puzzle.count_solutions(1) == 1);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::thread;
+
+use crate::{SolveError, Sudoku};
+
+/// Solves puzzles in parallel, one chunk per available core. Results are in
+/// the same order as the input.
+pub fn solve_batch(puzzles: &[Sudoku]) -> Vec<Result<Sudoku, SolveError>> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = puzzles.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        let handles = puzzles
+            .chunks(chunk)
+            .map(|part| {
+                scope.spawn(move || {
+                    part.iter()
+                        .map(Sudoku::solve_with_propagation)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn batch_preserves_order() {
+        let good = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let bad = Sudoku::from_str(&format!("55{}", ".".repeat(79))).unwrap();
+        let mut puzzles = vec![good.clone(); 10];
+        puzzles.insert(3, bad);
+        let results = solve_batch(&puzzles);
+        assert_eq!(results.len(), 11);
+        assert_eq!(results[3], Err(SolveError::InvalidPuzzle));
+        assert_eq!(results[0], good.solve());
+        assert_eq!(results[10], good.solve());
+        assert!(solve_batch(&[]).is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+//! Timing the solver backends against each other, to catch performance
+//! regressions. `cargo bench --features bench` runs [`benchmark`] over the
+//! embedded puzzles.
+
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use crate::{SolverKind, Sudoku};
+
+/// Well-known puzzles that are hard for solvers, one per line.
+pub const HARD_PUZZLES: &str = include_str!("../data/hard.txt");
+
+pub fn hard_puzzles() -> Vec<Sudoku> {
+    HARD_PUZZLES
+        .lines()
+        .map(|line| line.parse().expect("embedded puzzles are valid"))
+        .collect()
+}
+
+/// How long one backend took over a set of puzzles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    pub kind: SolverKind,
+    pub puzzles: usize,
+    pub total: Duration,
+    pub slowest: Duration,
+    /// Puzzles the backend failed to solve. Should always be zero.
+    pub failures: usize,
+}
+
+impl Timing {
+    pub fn mean(&self) -> Duration {
+        self.total / self.puzzles.max(1) as u32
+    }
+}
+
+/// Solves every puzzle `rounds` times with each backend.
+pub fn benchmark(puzzles: &[Sudoku], kinds: &[SolverKind], rounds: usize) -> Vec<Timing> {
+    kinds
+        .iter()
+        .map(|&kind| {
+            let mut timing = Timing {
+                kind,
+                puzzles: puzzles.len() * rounds,
+                total: Duration::ZERO,
+                slowest: Duration::ZERO,
+                failures: 0,
+            };
+            for _ in 0..rounds {
+                for puzzle in puzzles {
+                    let start = Instant::now();
+                    let solved = puzzle.solve_with(kind);
+                    let elapsed = start.elapsed();
+                    if solved.is_err() {
+                        timing.failures += 1;
+                    }
+                    timing.total += elapsed;
+                    timing.slowest = timing.slowest.max(elapsed);
+                }
+            }
+            timing
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_backend_solves_the_dataset() {
+        let puzzles = hard_puzzles();
+        assert!(puzzles.iter().all(|p| p.count_solutions(2) == 1));
+        let kinds = [SolverKind::Propagation, SolverKind::Dlx];
+        let timings = benchmark(&puzzles, &kinds, 1);
+        assert_eq!(timings.len(), 2);
+        for t in timings {
+            assert_eq!(t.puzzles, puzzles.len());
+            assert_eq!(t.failures, 0);
+            assert!(t.slowest <= t.total);
+        }
+    }
+}
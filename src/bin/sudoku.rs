@@ -0,0 +1,152 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::process::ExitCode;
+
+use sudoku::formats::{self, Format};
+use sudoku::{Difficulty, English, Messages, Rules, SolverKind, Sudoku, Symmetry};
+
+const USAGE: &str = "\
+Usage: sudoku <command> [args]
+
+Commands:
+  solve [FILE...]                  Solve each puzzle and print the grid
+  generate [--difficulty LEVEL]    Print a new puzzle (easy, medium, hard, expert)
+           [--count N] [--seed SEED]
+           [--symmetry none|rotational|mirror|diagonal]
+  grade [FILE...]                  Print the difficulty of each puzzle
+  check [FILE...]                  Report whether each puzzle is valid and unique
+
+Puzzles are read one per line from the given files, or stdin if there are none.
+solve, grade and check take --rules RULES for variants (x, windoku, anti-knight, anti-king, x+windoku).
+solve and check take --solver backtracking|propagation|dlx.";
+
+fn puzzles(files: &[String]) -> io::Result<Vec<(String, Result<Sudoku, sudoku::SudokuError>)>> {
+    let readers: Vec<Box<dyn BufRead>> = if files.is_empty() {
+        vec![Box::new(BufReader::new(io::stdin()))]
+    } else {
+        files
+            .iter()
+            .map(|f| Ok(Box::new(BufReader::new(File::open(f)?)) as Box<dyn BufRead>))
+            .collect::<io::Result<_>>()?
+    };
+    let mut out = Vec::new();
+    for reader in readers {
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if !line.is_empty() {
+                out.push((line.to_string(), formats::parse_as(line, Format::Line)));
+            }
+        }
+    }
+    Ok(out)
+}
+
+// Puzzle-level output goes through `messages`; the usage text and option
+// errors are the binary's own.
+fn run(args: &[String], messages: &dyn Messages) -> Result<bool, String> {
+    let (command, rest) = args.split_first().ok_or(USAGE)?;
+    let mut ok = true;
+    match command.as_str() {
+        "solve" | "grade" | "check" => {
+            let mut rules = Rules::CLASSIC;
+            let mut solver = SolverKind::Propagation;
+            let mut files = Vec::new();
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                if arg == "--rules" {
+                    let value = rest.next().ok_or("Missing value for --rules.")?;
+                    rules = value.parse().map_err(|e| messages.error(&e))?;
+                } else if arg == "--solver" {
+                    let value = rest.next().ok_or("Missing value for --solver.")?;
+                    solver = value.parse().map_err(|e| messages.error(&e))?;
+                } else {
+                    files.push(arg.clone());
+                }
+            }
+            for (line, puzzle) in puzzles(&files).map_err(|e| e.to_string())? {
+                let puzzle = match puzzle {
+                    Ok(p) => p.with_rules(rules),
+                    Err(e) => {
+                        eprintln!("{line}: {}", messages.error(&e));
+                        ok = false;
+                        continue;
+                    }
+                };
+                match command.as_str() {
+                    "solve" => match puzzle.solve_with(solver) {
+                        Ok(solution) => println!("{solution}\n"),
+                        Err(e) => {
+                            eprintln!("{line}: {}", messages.solve_error(&e));
+                            ok = false;
+                        }
+                    },
+                    "grade" => println!("{line} {}", messages.difficulty(puzzle.grade())),
+                    _ => {
+                        let count = if puzzle.is_valid() {
+                            Some(puzzle.count_solutions_with(solver, 2))
+                        } else {
+                            ok = false;
+                            None
+                        };
+                        println!("{line} {}", messages.solution_count(count));
+                    }
+                }
+            }
+        }
+        "generate" => {
+            let mut difficulty = Difficulty::Medium;
+            let mut count = 1usize;
+            let mut seed = None;
+            let mut symmetry = Symmetry::None;
+            let mut rest = rest.iter();
+            while let Some(flag) = rest.next() {
+                let value = rest.next().ok_or(format!("Missing value for {flag}."))?;
+                match flag.as_str() {
+                    "--difficulty" | "-d" => {
+                        difficulty = value.parse().map_err(|e| messages.error(&e))?
+                    }
+                    "--count" | "-n" => {
+                        count = value.parse().map_err(|_| format!("Bad count {value}."))?
+                    }
+                    "--symmetry" => symmetry = value.parse().map_err(|e| messages.error(&e))?,
+                    "--seed" => {
+                        seed = Some(
+                            value
+                                .parse::<u64>()
+                                .map_err(|_| format!("Bad seed {value}."))?,
+                        )
+                    }
+                    _ => return Err(format!("Unknown option {flag}.\n\n{USAGE}")),
+                }
+            }
+            for i in 0..count {
+                // Consecutive seeds, so a run can be reproduced as a whole.
+                let puzzle = match seed {
+                    Some(seed) => Sudoku::generate_symmetric_with_seed(
+                        seed.wrapping_add(i as u64),
+                        difficulty,
+                        symmetry,
+                    ),
+                    None => Sudoku::generate_symmetric(difficulty, symmetry),
+                };
+                println!("{}\n{puzzle}\n", puzzle.to_line_string());
+            }
+        }
+        "help" | "--help" | "-h" => println!("{USAGE}"),
+        _ => return Err(format!("Unknown command {command}.\n\n{USAGE}")),
+    }
+    Ok(ok)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match run(&args, &English) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(2)
+        }
+    }
+}
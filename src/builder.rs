@@ -0,0 +1,134 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Pos, Rules, Size, Sudoku, SudokuError};
+
+/// Builds a board from values computed in code. Every value placed becomes
+/// a given. Mistakes are kept until [`SudokuBuilder::build`], which reports
+/// the first of them.
+#[derive(Debug, Clone)]
+pub struct SudokuBuilder {
+    size: Size,
+    rules: Rules,
+    values: Vec<Option<u8>>,
+    error: Option<SudokuError>,
+}
+
+impl SudokuBuilder {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            rules: Rules::CLASSIC,
+            values: vec![None; size.cell_count()],
+            error: None,
+        }
+    }
+    /// Starts from `f(pos)` for every position of the board.
+    pub fn from_fn(size: Size, mut f: impl FnMut(Pos) -> Option<u8>) -> Self {
+        size.positions()
+            .fold(Self::new(size), |b, pos| match f(pos) {
+                Some(v) => b.place(pos, v),
+                None => b,
+            })
+    }
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+    pub fn place(mut self, pos: Pos, value: u8) -> Self {
+        let checked = if !self.size.contains(pos) {
+            Err(SudokuError::OutOfBounds)
+        } else if !self.size.is_valid_value(value) {
+            Err(SudokuError::InvalidValue(value))
+        } else {
+            Ok(())
+        };
+        match checked {
+            Ok(()) => self.values[self.size.index_of(pos)] = Some(value),
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+        self
+    }
+    /// Places a whole row, left to right, with `0` for empty cells.
+    pub fn place_row(mut self, y: u8, row: &[u8]) -> Self {
+        if row.len() != self.size.side() as usize {
+            self.error.get_or_insert(SudokuError::InvalidSize);
+            return self;
+        }
+        for (x, &v) in (0..).zip(row) {
+            match Pos::try_new(x, y) {
+                Ok(pos) if v != 0 => self = self.place(pos, v),
+                Ok(_) => {}
+                Err(e) => {
+                    self.error.get_or_insert(e);
+                }
+            }
+        }
+        self
+    }
+    /// Fails with the first mistake made while building, or with
+    /// `Conflict` if two values clash under the board's rules.
+    pub fn build(self) -> Result<Sudoku, SudokuError> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let board = Sudoku::from_values(self.size, self.values).with_rules(self.rules);
+        match board.conflicts().first() {
+            Some(&(a, _)) => Err(SudokuError::Conflict(board[a].value().unwrap_or(0))),
+            None => Ok(board),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn builds_from_rows_and_functions() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let built = SudokuBuilder::from_fn(Size::CLASSIC, |p| s[p].value())
+            .build()
+            .unwrap();
+        assert_eq!(built, s);
+        let row = SudokuBuilder::new(Size::CLASSIC)
+            .place_row(0, &[0, 5, 0, 0, 8, 3, 0, 1, 7])
+            .place(Pos::new_unchecked(3, 1), 1)
+            .build()
+            .unwrap();
+        assert_eq!(row.to_line_string()[..13], s.to_line_string()[..13]);
+        assert!(row[(1, 0)].is_given());
+    }
+
+    #[test]
+    fn build_reports_the_first_mistake() {
+        let b = SudokuBuilder::new(Size::new(2).unwrap());
+        assert_eq!(
+            b.clone().place(Pos::new_unchecked(0, 0), 5).build(),
+            Err(SudokuError::InvalidValue(5))
+        );
+        assert_eq!(
+            b.clone()
+                .place(Pos::new_unchecked(4, 0), 1)
+                .place_row(0, &[1, 2])
+                .build(),
+            Err(SudokuError::OutOfBounds)
+        );
+        assert_eq!(
+            b.clone().place_row(1, &[1, 2, 3, 1]).build(),
+            Err(SudokuError::Conflict(1))
+        );
+        let x = SudokuBuilder::new(Size::CLASSIC)
+            .rules(Rules::X)
+            .place(Pos::new_unchecked(0, 0), 1)
+            .place(Pos::new_unchecked(8, 8), 1);
+        assert_eq!(x.build(), Err(SudokuError::Conflict(1)));
+    }
+}
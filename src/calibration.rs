@@ -0,0 +1,261 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::grading::se_rating;
+use crate::{GradingScale, LogicalSolver, ParseError, SolveLog, Sudoku, SudokuError, Technique};
+
+const TECHNIQUES: usize = Technique::ALL.len();
+// How many of the external ratings each weight is tried at per round.
+const STEPS: usize = 32;
+const ROUNDS: usize = 20;
+
+/// A rating scale with a weight per technique: a puzzle scores the highest
+/// weight among the techniques its solve used, 1.0 if it needed none, and
+/// `unsolved` if the solve got stuck. The default weights match
+/// [`crate::SeRating`]; [`TechniqueWeights::calibrate`] fits them to
+/// someone else's ratings.
+///
+/// Weights save as text with one `Technique weight` line each, `unsolved`
+/// included, through `Display` and `FromStr`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TechniqueWeights {
+    weights: [f32; TECHNIQUES],
+    pub unsolved: f32,
+}
+
+impl Default for TechniqueWeights {
+    fn default() -> Self {
+        Self {
+            weights: Technique::ALL.map(se_rating),
+            unsolved: 10.0,
+        }
+    }
+}
+
+fn index(technique: Technique) -> usize {
+    Technique::ALL.iter().position(|&t| t == technique).unwrap()
+}
+
+// A solve log boiled down to what the scale looks at.
+#[derive(Clone, Copy)]
+struct Sample {
+    used: u32,
+    solved: bool,
+    target: f32,
+}
+
+impl TechniqueWeights {
+    pub fn weight(&self, technique: Technique) -> f32 {
+        self.weights[index(technique)]
+    }
+    pub fn set_weight(&mut self, technique: Technique, weight: f32) {
+        self.weights[index(technique)] = weight;
+    }
+
+    /// Solves every puzzle with the default solver and fits the weights to
+    /// the ratings paired with them. See [`TechniqueWeights::fit`].
+    pub fn calibrate(&self, puzzles: &[(Sudoku, f32)]) -> Self {
+        let solver = LogicalSolver::default();
+        let logs = puzzles
+            .iter()
+            .map(|(p, rating)| (solver.solve_log(p), *rating))
+            .collect::<Vec<_>>();
+        self.fit(&logs)
+    }
+    /// Starting from these weights, adjusts one weight at a time to cut the
+    /// mean squared difference from the external ratings, until no single
+    /// change helps. Each weight is only tried at values the ratings take,
+    /// and weights of techniques no solve used stay as they are.
+    pub fn fit(&self, logs: &[(SolveLog, f32)]) -> Self {
+        let samples = logs.iter().map(sample).collect::<Vec<_>>();
+        let mut targets = samples.iter().map(|s| s.target).collect::<Vec<_>>();
+        targets.sort_by(f32::total_cmp);
+        targets.dedup();
+        let steps = STEPS.min(targets.len());
+        let tried = (0..steps)
+            .map(|i| targets[i * (targets.len() - 1) / (steps - 1).max(1)])
+            .collect::<Vec<_>>();
+
+        let mut fitted = self.clone();
+        let mut error = fitted.error(&samples);
+        for _ in 0..ROUNDS {
+            let mut improved = false;
+            for k in 0..=TECHNIQUES {
+                let used = samples.iter().any(|s| match k {
+                    TECHNIQUES => !s.solved,
+                    _ => s.solved && s.used & (1 << k) != 0,
+                });
+                if !used {
+                    continue;
+                }
+                for &value in &tried {
+                    let mut candidate = fitted.clone();
+                    *candidate.slot(k) = value;
+                    let e = candidate.error(&samples);
+                    if e < error {
+                        (fitted, error, improved) = (candidate, e, true);
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+        fitted
+    }
+    /// The mean squared difference between these weights' scores and the
+    /// external ratings, 0 for no logs.
+    pub fn mean_squared_error(&self, logs: &[(SolveLog, f32)]) -> f32 {
+        self.error(&logs.iter().map(sample).collect::<Vec<_>>())
+    }
+
+    fn slot(&mut self, k: usize) -> &mut f32 {
+        match k {
+            TECHNIQUES => &mut self.unsolved,
+            _ => &mut self.weights[k],
+        }
+    }
+    fn rate(&self, used: u32, solved: bool) -> f32 {
+        if !solved {
+            return self.unsolved;
+        }
+        (0..TECHNIQUES)
+            .filter(|k| used & (1 << k) != 0)
+            .map(|k| self.weights[k])
+            .reduce(f32::max)
+            .unwrap_or(1.0)
+    }
+    fn error(&self, samples: &[Sample]) -> f32 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+        let total = samples
+            .iter()
+            .map(|s| {
+                let diff = self.rate(s.used, s.solved) - s.target;
+                diff * diff
+            })
+            .sum::<f32>();
+        total / samples.len() as f32
+    }
+}
+
+fn used(techniques: &[Technique]) -> u32 {
+    techniques.iter().fold(0, |used, &t| used | 1 << index(t))
+}
+
+fn sample((log, target): &(SolveLog, f32)) -> Sample {
+    Sample {
+        used: used(&log.techniques),
+        solved: log.solved,
+        target: *target,
+    }
+}
+
+impl GradingScale for TechniqueWeights {
+    type Score = f32;
+
+    fn score(&self, log: &SolveLog) -> f32 {
+        self.rate(used(&log.techniques), log.solved)
+    }
+}
+
+impl fmt::Display for TechniqueWeights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (t, w) in Technique::ALL.iter().zip(self.weights) {
+            writeln!(f, "{t:?} {w}")?;
+        }
+        writeln!(f, "unsolved {}", self.unsolved)
+    }
+}
+
+/// Reads what `Display` writes. Missing lines keep their default weight;
+/// blank lines and lines starting with `#` are skipped.
+impl FromStr for TechniqueWeights {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SudokuError::ParseError(ParseError::Invalid("Invalid technique weight."));
+        let mut weights = Self::default();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, weight) = line.split_once(' ').ok_or_else(invalid)?;
+            let weight = weight.trim().parse::<f32>().map_err(|_| invalid())?;
+            if !weight.is_finite() {
+                return Err(invalid());
+            }
+            let k = match name {
+                "unsolved" => TECHNIQUES,
+                _ => Technique::ALL
+                    .iter()
+                    .position(|t| alloc::format!("{t:?}") == name)
+                    .ok_or_else(invalid)?,
+            };
+            *weights.slot(k) = weight;
+        }
+        Ok(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{Difficulty, SeRating};
+
+    #[test]
+    fn fits_a_published_scale() {
+        let solver = LogicalSolver::default();
+        let logs = (0..16)
+            .map(|seed| {
+                let difficulty = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard][seed % 3];
+                solver.solve_log(&Sudoku::generate_with_seed(seed as u64, difficulty))
+            })
+            .collect::<Vec<_>>();
+        // A scale that rates everything a step harder than ours.
+        let mut truth = TechniqueWeights::default();
+        for t in Technique::ALL {
+            truth.set_weight(t, truth.weight(t) + 1.0);
+        }
+        truth.unsolved = 8.0;
+        let rated = logs
+            .iter()
+            .map(|log| (log.clone(), truth.score(log)))
+            .collect::<Vec<_>>();
+
+        let start = TechniqueWeights::default();
+        assert!(start.mean_squared_error(&rated) > 0.5);
+        let fitted = start.fit(&rated);
+        assert!(fitted.mean_squared_error(&rated) < 1e-6);
+        for (log, rating) in &rated {
+            assert_eq!(fitted.score(log), *rating);
+        }
+        assert_eq!(TechniqueWeights::default().fit(&[]), start);
+    }
+
+    #[test]
+    fn saves_as_text() {
+        let mut weights = TechniqueWeights::default();
+        weights.set_weight(Technique::XWing, 4.5);
+        weights.unsolved = 7.25;
+        let text = weights.to_string();
+        assert!(text.starts_with("NakedSingle 2.3\nHiddenSingle 1.5\n"));
+        assert!(text.contains("XWing 4.5\n"));
+        assert_eq!(text.parse::<TechniqueWeights>(), Ok(weights.clone()));
+        let partial = "# editorial scale\nXWing 4.5\n\nunsolved 7.25\n";
+        assert_eq!(partial.parse::<TechniqueWeights>(), Ok(weights));
+        for bad in ["Guess 1.0", "XWing", "XWing nan", "XWing high"] {
+            assert!(bad.parse::<TechniqueWeights>().is_err(), "{bad}");
+        }
+
+        let easy = Sudoku::generate_with_seed(2, Difficulty::Easy);
+        assert_eq!(
+            Some(easy.grade_with(&TechniqueWeights::default())),
+            easy.grade_with(&SeRating)
+        );
+    }
+}
@@ -0,0 +1,157 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Candidates(u32);
+
+/// How a board keeps pencil marks up to date as values change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CandidateMode {
+    /// Pencil marks only change when the player changes them.
+    Manual,
+    /// Placing a value removes it from the pencil marks of the cell's peers.
+    #[default]
+    AutoRemove,
+    /// Every empty cell's pencil marks are its candidates, recomputed after
+    /// each change of value. Marks toggled by hand last until the next one.
+    AutoFill,
+}
+
+/// Reads the variant names, in any case.
+impl core::str::FromStr for CandidateMode {
+    type Err = crate::SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "manual" => Ok(CandidateMode::Manual),
+            "autoremove" => Ok(CandidateMode::AutoRemove),
+            "autofill" => Ok(CandidateMode::AutoFill),
+            _ => Err(crate::SudokuError::ParseError(crate::ParseError::Invalid(
+                "Unknown candidate mode.",
+            ))),
+        }
+    }
+}
+
+const ALL: u32 = 0b11_1111_1110;
+// Bits for every value a cell on the largest board can hold.
+const ANY: u32 = (1 << 26) - 2;
+
+impl Candidates {
+    pub fn empty() -> Self {
+        Self(0)
+    }
+    pub fn all() -> Self {
+        Self(ALL)
+    }
+    /// Every value from 1 to `max`.
+    pub fn up_to(max: u8) -> Self {
+        Self::from_bits(((1u64 << (max.min(25) + 1)) - 2) as u32)
+    }
+    pub fn from_bits(bits: u32) -> Self {
+        Self(bits & ANY)
+    }
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+    pub fn contains(&self, value: u8) -> bool {
+        (1..=25).contains(&value) && self.0 & (1 << value) != 0
+    }
+    pub fn insert(&mut self, value: u8) {
+        if (1..=25).contains(&value) {
+            self.0 |= 1 << value;
+        }
+    }
+    pub fn remove(&mut self, value: u8) {
+        if (1..=25).contains(&value) {
+            self.0 &= !(1 << value);
+        }
+    }
+    pub fn count(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    /// The only candidate left, if there is exactly one.
+    pub fn single(&self) -> Option<u8> {
+        if self.count() == 1 {
+            Some(self.0.trailing_zeros() as u8)
+        } else {
+            None
+        }
+    }
+    /// The values from 1 to 9 not in the set.
+    pub fn complement(&self) -> Self {
+        Self(!self.0 & ALL)
+    }
+    /// Values in `self` that aren't in `other`.
+    pub fn difference(&self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = u8> {
+        let bits = self.0;
+        (1..=25).filter(move |v| bits & (1 << v) != 0)
+    }
+}
+
+impl FromIterator<u8> for Candidates {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let mut c = Candidates::empty();
+        for v in iter {
+            c.insert(v);
+        }
+        c
+    }
+}
+
+impl core::ops::BitOr for Candidates {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitAnd for Candidates {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_remove_and_count() {
+        let mut c = Candidates::empty();
+        c.insert(3);
+        c.insert(9);
+        c.insert(0);
+        assert!(c.contains(3) && c.contains(9) && !c.contains(0));
+        assert_eq!(c.count(), 2);
+        c.remove(9);
+        assert_eq!(c.single(), Some(3));
+        assert_eq!(Candidates::all().count(), 9);
+    }
+
+    #[test]
+    fn iterates_in_order() {
+        let c = [7u8, 2, 5].into_iter().collect::<Candidates>();
+        assert_eq!(c.iter().collect::<Vec<_>>(), vec![2, 5, 7]);
+        assert_eq!(
+            c.complement().iter().collect::<Vec<_>>(),
+            vec![1, 3, 4, 6, 8, 9]
+        );
+    }
+
+    #[test]
+    fn holds_values_of_large_boards() {
+        let all = Candidates::up_to(16);
+        assert_eq!(all.count(), 16);
+        assert!(all.contains(16) && !all.contains(17));
+        let c = [3u8, 16].into_iter().collect::<Candidates>();
+        assert_eq!(all.difference(c).count(), 14);
+        assert_eq!(Candidates::up_to(25).count(), 25);
+    }
+}
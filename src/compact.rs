@@ -0,0 +1,153 @@
+use alloc::vec::Vec;
+
+use crate::{Pos, Size, Sudoku, SudokuError};
+
+/// The values of a 9x9 board packed two to a byte, in 41 bytes, for
+/// keeping lots of puzzles in memory or in an index. Only values are kept:
+/// boards come back with every filled cell a given and classic rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompactSudoku([u8; 41]);
+
+impl Default for CompactSudoku {
+    /// The empty board.
+    fn default() -> Self {
+        Self([0; 41])
+    }
+}
+
+impl CompactSudoku {
+    /// Cell `i`, in index order, sits in the high nibble of byte `i / 2`
+    /// when `i` is even and in the low one when it is odd, 0 for an empty
+    /// cell. The last low nibble is always 0.
+    pub fn as_bytes(&self) -> &[u8; 41] {
+        &self.0
+    }
+    /// Fails on nibbles above 9 and a non-zero last nibble.
+    pub fn from_bytes(bytes: [u8; 41]) -> Result<Self, SudokuError> {
+        if bytes[40] & 0x0F != 0 {
+            return Err(SudokuError::InvalidValue(bytes[40] & 0x0F));
+        }
+        match bytes
+            .iter()
+            .flat_map(|b| [b >> 4, b & 0x0F])
+            .find(|&v| v > 9)
+        {
+            Some(v) => Err(SudokuError::InvalidValue(v)),
+            None => Ok(Self(bytes)),
+        }
+    }
+    /// The value at `pos`, or `None` for an empty cell or a position off
+    /// the board.
+    pub fn get(&self, pos: Pos) -> Option<u8> {
+        Size::CLASSIC
+            .contains(pos)
+            .then(|| self.value(Size::CLASSIC.index_of(pos)))
+            .flatten()
+    }
+    pub fn clue_count(&self) -> usize {
+        (0..81).filter(|&i| self.value(i).is_some()).count()
+    }
+    pub fn to_sudoku(&self) -> Sudoku {
+        Sudoku::from_values(Size::CLASSIC, (0..81).map(|i| self.value(i)))
+    }
+    /// Packs every board, failing on the first one that isn't 9x9.
+    pub fn pack_all<'a>(
+        boards: impl IntoIterator<Item = &'a Sudoku>,
+    ) -> Result<Vec<Self>, SudokuError> {
+        boards.into_iter().map(Self::try_from).collect()
+    }
+    pub fn unpack_all(compact: &[Self]) -> Vec<Sudoku> {
+        compact.iter().map(Self::to_sudoku).collect()
+    }
+
+    fn value(&self, i: usize) -> Option<u8> {
+        let byte = self.0[i / 2];
+        let v = if i.is_multiple_of(2) {
+            byte >> 4
+        } else {
+            byte & 0x0F
+        };
+        (v != 0).then_some(v)
+    }
+}
+
+impl TryFrom<&Sudoku> for CompactSudoku {
+    type Error = SudokuError;
+
+    fn try_from(board: &Sudoku) -> Result<Self, Self::Error> {
+        if board.size() != Size::CLASSIC {
+            return Err(SudokuError::InvalidSize);
+        }
+        let mut bytes = [0; 41];
+        for (i, cell) in board.iter().enumerate() {
+            let v = cell.value().unwrap_or(0);
+            bytes[i / 2] |= if i.is_multiple_of(2) { v << 4 } else { v };
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl From<CompactSudoku> for Sudoku {
+    fn from(compact: CompactSudoku) -> Self {
+        compact.to_sudoku()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn round_trips_boards() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let compact = CompactSudoku::try_from(&s).unwrap();
+        assert_eq!(compact.as_bytes()[0], 0x05);
+        assert_eq!(compact.get(Pos::new_unchecked(1, 0)), Some(5));
+        assert_eq!(compact.get(Pos::new_unchecked(0, 0)), None);
+        assert_eq!(
+            compact.clue_count(),
+            s.iter().filter(|c| c.value().is_some()).count()
+        );
+        assert_eq!(Sudoku::from(compact), s);
+        assert_eq!(CompactSudoku::from_bytes(*compact.as_bytes()), Ok(compact));
+        assert_eq!(
+            CompactSudoku::default().to_sudoku(),
+            Sudoku::new(Size::CLASSIC)
+        );
+
+        let mut bytes = [0; 41];
+        bytes[3] = 0xA0;
+        assert_eq!(
+            CompactSudoku::from_bytes(bytes),
+            Err(SudokuError::InvalidValue(10))
+        );
+        bytes[3] = 0;
+        bytes[40] = 0x01;
+        assert!(CompactSudoku::from_bytes(bytes).is_err());
+        let small = Sudoku::new(Size::new(2).unwrap());
+        assert_eq!(
+            CompactSudoku::try_from(&small),
+            Err(SudokuError::InvalidSize)
+        );
+    }
+
+    #[test]
+    fn packs_batches() {
+        let boards = (0..20)
+            .map(Sudoku::random_solution_with_seed)
+            .collect::<Vec<_>>();
+        let compact = CompactSudoku::pack_all(&boards).unwrap();
+        assert_eq!(CompactSudoku::unpack_all(&compact), boards);
+        let distinct = compact.iter().collect::<HashSet<_>>();
+        assert_eq!(distinct.len(), 20);
+        let mut with_small = boards.clone();
+        with_small.push(Sudoku::new(Size::new(2).unwrap()));
+        assert!(CompactSudoku::pack_all(&with_small).is_err());
+    }
+}
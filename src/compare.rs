@@ -0,0 +1,132 @@
+use alloc::vec::Vec;
+
+use crate::{Candidates, Pos, Sudoku};
+
+/// How a cell's value differs between two boards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CellChange {
+    Added {
+        pos: Pos,
+        value: u8,
+    },
+    Removed {
+        pos: Pos,
+        value: u8,
+    },
+    Changed {
+        pos: Pos,
+        from: u8,
+        to: u8,
+    },
+    /// Pencil marks changed. Only reported to observers, never by `diff`.
+    Candidates {
+        pos: Pos,
+        from: Candidates,
+        to: Candidates,
+    },
+}
+
+impl CellChange {
+    pub fn pos(self) -> Pos {
+        match self {
+            CellChange::Added { pos, .. }
+            | CellChange::Removed { pos, .. }
+            | CellChange::Changed { pos, .. }
+            | CellChange::Candidates { pos, .. } => pos,
+        }
+    }
+}
+
+impl Sudoku {
+    /// What it takes to turn this board's values into `other`'s, in
+    /// row-major order. Givens and pencil marks are ignored. Panics if the
+    /// boards differ in size.
+    pub fn diff(&self, other: &Sudoku) -> Vec<CellChange> {
+        assert_eq!(self.size(), other.size(), "Boards differ in size.");
+        self.iter()
+            .zip(other.iter())
+            .filter_map(|(a, b)| {
+                let pos = a.position();
+                match (a.value(), b.value()) {
+                    (None, Some(value)) => Some(CellChange::Added { pos, value }),
+                    (Some(value), None) => Some(CellChange::Removed { pos, value }),
+                    (Some(from), Some(to)) if from != to => {
+                        Some(CellChange::Changed { pos, from, to })
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+    /// Filled cells whose value doesn't match `solution`. Empty cells don't
+    /// count as mistakes. Panics if the boards differ in size.
+    pub fn check_against(&self, solution: &Sudoku) -> Vec<Pos> {
+        assert_eq!(self.size(), solution.size(), "Boards differ in size.");
+        self.iter()
+            .zip(solution.iter())
+            .filter(|(a, b)| a.value().is_some() && a.value() != b.value())
+            .map(|(a, _)| a.position())
+            .collect()
+    }
+    /// Percentage of cells holding a value, from 0 to 100. Wrong values
+    /// count too; see [`Sudoku::check_against`].
+    pub fn progress(&self) -> f32 {
+        let filled = self.iter().filter(|c| c.value().is_some()).count();
+        filled as f32 * 100.0 / self.size().cell_count() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::Size;
+
+    #[test]
+    fn reports_each_kind_of_change() {
+        let before = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert!(before.diff(&before).is_empty());
+        let mut after = before.clone();
+        after.set(Pos::new_unchecked(0, 0), Some(2)).unwrap();
+        after.set_forced(Pos::new_unchecked(1, 0), Some(6)).unwrap();
+        after.set_forced(Pos::new_unchecked(4, 0), None).unwrap();
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                CellChange::Added {
+                    pos: Pos::new_unchecked(0, 0),
+                    value: 2
+                },
+                CellChange::Changed {
+                    pos: Pos::new_unchecked(1, 0),
+                    from: 5,
+                    to: 6
+                },
+                CellChange::Removed {
+                    pos: Pos::new_unchecked(4, 0),
+                    value: 8
+                },
+            ]
+        );
+        assert_eq!(after.diff(&before)[1].pos(), Pos::new_unchecked(1, 0));
+    }
+
+    #[test]
+    fn checks_entries_against_solution() {
+        let mut s = Sudoku::new(Size::new(2).unwrap());
+        let solution = s.solve().unwrap();
+        assert_eq!(s.progress(), 0.0);
+        assert!(s.check_against(&solution).is_empty());
+        let (right, wrong) = (Pos::new_unchecked(0, 0), Pos::new_unchecked(1, 0));
+        s.set(right, solution[right].value()).unwrap();
+        let other = solution[wrong].value().unwrap() % 4 + 1;
+        s.set(wrong, Some(other)).unwrap();
+        assert_eq!(s.check_against(&solution), vec![wrong]);
+        assert_eq!(s.progress(), 12.5);
+        assert_eq!(solution.progress(), 100.0);
+    }
+}
@@ -0,0 +1,291 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::{Candidates, House, Pos, Size, SolveError, Sudoku};
+
+/// A rule the values on a board must follow. Implement it to add variant
+/// rules such as anti-knight or thermometers.
+///
+/// [`Sudoku::solve`], the other built-in solvers and [`Sudoku::is_valid`]
+/// only know the board's [`Rules`](crate::Rules), which
+/// [`ConstraintSet::standard`] mirrors. To check or solve under extra
+/// constraints, put them in a [`ConstraintSet`] and use
+/// [`ConstraintSet::is_satisfied`] and [`Sudoku::solve_with_constraints`].
+pub trait Constraint {
+    /// Values the rule forbids in the empty cell at `pos`, given the values
+    /// already placed.
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates;
+    /// False if the placed values break the rule. Empty cells never do.
+    fn is_satisfied(&self, board: &Sudoku) -> bool;
+}
+
+impl Constraint for House {
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        let mut cells = board.rules().house_positions(board.size(), *self);
+        if !cells.any(|p| p == pos) {
+            return Candidates::empty();
+        }
+        if let Some(masks) = board.masks().filter(|m| m.tracks(*self)) {
+            return masks.seen_in(*self, board[pos].value());
+        }
+        board
+            .house(*self)
+            .filter(|c| c.position() != pos)
+            .filter_map(|c| c.value())
+            .collect()
+    }
+    fn is_satisfied(&self, board: &Sudoku) -> bool {
+        if let Some(masks) = board.masks().filter(|m| m.tracks(*self)) {
+            return !masks.repeats_in(*self);
+        }
+        let mut seen = Candidates::empty();
+        board.house(*self).filter_map(|c| c.value()).all(|v| {
+            let fresh = !seen.contains(v);
+            seen.insert(v);
+            fresh
+        })
+    }
+}
+
+/// No two cells a knight's move apart hold the same value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AntiKnight;
+
+/// No two cells a king's move apart, diagonals included, hold the same
+/// value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AntiKing;
+
+pub(crate) const KNIGHT_MOVES: [(i8, i8); 8] = [
+    (1, 2),
+    (2, 1),
+    (-1, 2),
+    (-2, 1),
+    (1, -2),
+    (2, -1),
+    (-1, -2),
+    (-2, -1),
+];
+
+pub(crate) const KING_MOVES: [(i8, i8); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// The cells one of `moves` away from `pos` that are still on the board.
+pub(crate) fn moves_from(
+    size: Size,
+    pos: Pos,
+    moves: &[(i8, i8)],
+) -> impl Iterator<Item = Pos> + '_ {
+    moves.iter().filter_map(move |&(dx, dy)| {
+        let x = pos.x().checked_add_signed(dx)?;
+        let y = pos.y().checked_add_signed(dy)?;
+        Some(Pos::new_unchecked(x, y)).filter(|&p| size.contains(p))
+    })
+}
+
+// Shared by the move based constraints: forbid the values placed one move
+// away, and check every placed value against its own moves.
+fn move_eliminations(board: &Sudoku, pos: Pos, moves: &[(i8, i8)]) -> Candidates {
+    moves_from(board.size(), pos, moves)
+        .filter_map(|p| board[p].value())
+        .collect()
+}
+
+fn moves_satisfied(board: &Sudoku, moves: &[(i8, i8)]) -> bool {
+    board.iter().all(|c| {
+        c.value()
+            .is_none_or(|v| !move_eliminations(board, c.position(), moves).contains(v))
+    })
+}
+
+impl Constraint for AntiKnight {
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        move_eliminations(board, pos, &KNIGHT_MOVES)
+    }
+    fn is_satisfied(&self, board: &Sudoku) -> bool {
+        moves_satisfied(board, &KNIGHT_MOVES)
+    }
+}
+
+impl Constraint for AntiKing {
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        move_eliminations(board, pos, &KING_MOVES)
+    }
+    fn is_satisfied(&self, board: &Sudoku) -> bool {
+        moves_satisfied(board, &KING_MOVES)
+    }
+}
+
+/// The rules a board is checked and solved against.
+#[derive(Default)]
+pub struct ConstraintSet {
+    constraints: Vec<Box<dyn Constraint>>,
+}
+
+impl ConstraintSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// One constraint per house of the board: rows, columns, boxes and any
+    /// houses its rules add, plus [`AntiKnight`] and [`AntiKing`] if the
+    /// rules ask for them.
+    pub fn standard(board: &Sudoku) -> Self {
+        let mut set = Self::new();
+        for house in board.houses() {
+            set.push(house);
+        }
+        if board.rules().anti_knight {
+            set.push(AntiKnight);
+        }
+        if board.rules().anti_king {
+            set.push(AntiKing);
+        }
+        set
+    }
+    pub fn push(&mut self, constraint: impl Constraint + 'static) {
+        self.constraints.push(Box::new(constraint));
+    }
+    pub fn with(mut self, constraint: impl Constraint + 'static) -> Self {
+        self.push(constraint);
+        self
+    }
+    pub fn len(&self) -> usize {
+        self.constraints.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+    /// Values from 1 to the board's side that no constraint forbids at `pos`.
+    pub fn candidates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        self.constraints
+            .iter()
+            .fold(Candidates::up_to(board.size().side()), |free, c| {
+                free.difference(c.eliminates(board, pos))
+            })
+    }
+    pub fn is_satisfied(&self, board: &Sudoku) -> bool {
+        self.constraints.iter().all(|c| c.is_satisfied(board))
+    }
+    /// Backtracks over the most constrained cell. Slower than
+    /// [`Sudoku::solve`], but works with any set of constraints.
+    pub fn solve(&self, board: &Sudoku) -> Result<Sudoku, SolveError> {
+        if !self.is_satisfied(board) {
+            return Err(SolveError::InvalidPuzzle);
+        }
+        let mut board = board.clone();
+        if self.fill(&mut board) {
+            Ok(board)
+        } else {
+            Err(SolveError::NoSolution)
+        }
+    }
+
+    fn fill(&self, board: &mut Sudoku) -> bool {
+        let mut best: Option<(Pos, Candidates)> = None;
+        for cell in board.iter().filter(|c| c.value().is_none()) {
+            let candidates = self.candidates(board, cell.position());
+            if best.is_none_or(|(_, b)| candidates.count() < b.count()) {
+                best = Some((cell.position(), candidates));
+            }
+        }
+        let Some((pos, candidates)) = best else {
+            return self.is_satisfied(board);
+        };
+        for v in candidates.iter() {
+            board[pos].value = Some(v);
+            if self.fill(board) {
+                return true;
+            }
+        }
+        board[pos].value = None;
+        false
+    }
+}
+
+impl Sudoku {
+    /// Solves under `constraints` instead of the board's rules, with
+    /// [`ConstraintSet::solve`]. Start from [`ConstraintSet::standard`] to
+    /// keep the rules and add to them.
+    pub fn solve_with_constraints(
+        &self,
+        constraints: &ConstraintSet,
+    ) -> Result<Sudoku, SolveError> {
+        constraints.solve(self)
+    }
+}
+
+impl core::fmt::Debug for ConstraintSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ConstraintSet")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::Rules;
+
+    #[test]
+    fn standard_constraints_match_board_candidates() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let set = ConstraintSet::standard(&s);
+        assert_eq!(set.len(), 27);
+        let pos = Pos::new_unchecked(7, 1);
+        assert_eq!(set.candidates(&s, pos), s[pos].get_candidates(&s));
+        assert!(set.is_satisfied(&s));
+        assert_eq!(set.solve(&s), s.solve());
+    }
+
+    #[test]
+    fn custom_constraints_take_part_in_solving() {
+        let board = Sudoku::new(Size::CLASSIC);
+        let set = ConstraintSet::standard(&board).with(AntiKnight);
+        let solved = board.solve_with_constraints(&set).unwrap();
+        assert!(solved.is_solved());
+        assert!(AntiKnight.is_satisfied(&solved));
+        assert!(!AntiKnight.is_satisfied(&board.solve().unwrap()));
+    }
+
+    #[test]
+    fn anti_rules_join_the_standard_set() {
+        let rules = Rules {
+            anti_knight: true,
+            anti_king: true,
+            ..Rules::CLASSIC
+        };
+        let mut board = Sudoku::new(Size::CLASSIC).with_rules(rules);
+        assert_eq!(ConstraintSet::standard(&board).len(), 29);
+        board.set(Pos::new_unchecked(4, 4), Some(5)).unwrap();
+        board.set(Pos::new_unchecked(2, 2), Some(3)).unwrap();
+        let free = |board: &Sudoku, x, y| board[(x, y)].get_candidates(board);
+        assert!(!free(&board, 5, 6).contains(5));
+        assert!(!free(&board, 2, 3).contains(5));
+        assert!(!free(&board, 3, 3).contains(3));
+        assert!(free(&board, 2, 1).contains(5));
+        board.set(Pos::new_unchecked(5, 6), Some(5)).unwrap();
+        assert!(!board.is_valid());
+        assert!(!ConstraintSet::standard(&board).is_satisfied(&board));
+        assert_eq!(
+            board.conflicts(),
+            vec![(Pos::new_unchecked(4, 4), Pos::new_unchecked(5, 6))]
+        );
+        assert!(!AntiKnight.is_satisfied(&board));
+        assert!(AntiKing.is_satisfied(&board));
+        assert!(board.clone().with_rules(Rules::CLASSIC).is_valid());
+    }
+}
@@ -0,0 +1,109 @@
+use alloc::vec::Vec;
+
+use crate::{Size, Sudoku, SudokuError};
+
+fn checked(v: Option<u8>) -> Result<Option<u8>, SudokuError> {
+    match v {
+        Some(x) if x == 0 || x > 9 => Err(SudokuError::InvalidValue(x)),
+        v => Ok(v),
+    }
+}
+
+fn from_digit(v: u8) -> Result<Option<u8>, SudokuError> {
+    checked(Some(v).filter(|&v| v != 0))
+}
+
+// Rows are indexed by `y`, columns by `x`.
+impl TryFrom<[[Option<u8>; 9]; 9]> for Sudoku {
+    type Error = SudokuError;
+
+    fn try_from(rows: [[Option<u8>; 9]; 9]) -> Result<Self, Self::Error> {
+        let values = rows
+            .iter()
+            .flatten()
+            .map(|&v| checked(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Sudoku::from_values(Size::CLASSIC, values))
+    }
+}
+
+impl TryFrom<[[u8; 9]; 9]> for Sudoku {
+    type Error = SudokuError;
+
+    fn try_from(rows: [[u8; 9]; 9]) -> Result<Self, Self::Error> {
+        let values = rows
+            .iter()
+            .flatten()
+            .map(|&v| from_digit(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Sudoku::from_values(Size::CLASSIC, values))
+    }
+}
+
+impl TryFrom<[u8; 81]> for Sudoku {
+    type Error = SudokuError;
+
+    fn try_from(values: [u8; 81]) -> Result<Self, Self::Error> {
+        let values = values
+            .iter()
+            .map(|&v| from_digit(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Sudoku::from_values(Size::CLASSIC, values))
+    }
+}
+
+// Only 9x9 boards fit these arrays.
+impl Sudoku {
+    /// Panics if the board isn't 9x9.
+    pub fn to_array(&self) -> [[Option<u8>; 9]; 9] {
+        assert_eq!(self.size(), Size::CLASSIC, "Board is not 9x9.");
+        let mut rows = [[None; 9]; 9];
+        for cell in self.iter() {
+            let pos = cell.position();
+            rows[pos.y() as usize][pos.x() as usize] = cell.value();
+        }
+        rows
+    }
+    /// Values in index order, with 0 for empty cells. Panics if the board isn't 9x9.
+    pub fn to_flat_array(&self) -> [u8; 81] {
+        assert_eq!(self.size(), Size::CLASSIC, "Board is not 9x9.");
+        let mut values = [0; 81];
+        for cell in self.iter() {
+            values[cell.position().to_index()] = cell.value().unwrap_or(0);
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn arrays_round_trip() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let rows = s.to_array();
+        assert_eq!(rows[0][1], Some(5));
+        assert_eq!(Sudoku::try_from(rows).unwrap(), s);
+        let flat = s.to_flat_array();
+        assert_eq!(flat[0], 0);
+        assert_eq!(Sudoku::try_from(flat).unwrap(), s);
+        let digits = rows.map(|row| row.map(|v| v.unwrap_or(0)));
+        assert_eq!(Sudoku::try_from(digits).unwrap(), s);
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        let mut flat = [0u8; 81];
+        flat[5] = 10;
+        assert_eq!(Sudoku::try_from(flat), Err(SudokuError::InvalidValue(10)));
+        let mut rows = [[None; 9]; 9];
+        rows[2][3] = Some(0);
+        assert_eq!(Sudoku::try_from(rows), Err(SudokuError::InvalidValue(0)));
+    }
+}
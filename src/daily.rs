@@ -0,0 +1,169 @@
+use crate::{Difficulty, ParseError, Sudoku, SudokuError};
+
+/// Bumped whenever a change to the generator or to the way a date becomes a
+/// seed would give a different daily puzzle. Clients showing the same
+/// version agree on every day's board.
+pub const DAILY_VERSION: u32 = 2;
+
+/// A day in the proleptic Gregorian calendar. With the `chrono` feature, a
+/// `chrono::NaiveDate` converts into one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// `month` and `day` count from 1. Fails for days the month doesn't
+    /// have.
+    pub fn new(year: i32, month: u8, day: u8) -> Result<Self, SudokuError> {
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days = match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => 0,
+        };
+        if day == 0 || day > days {
+            return Err(SudokuError::ParseError(ParseError::Invalid(
+                "Invalid date.",
+            )));
+        }
+        Ok(Self { year, month, day })
+    }
+    pub fn year(self) -> i32 {
+        self.year
+    }
+    pub fn month(self) -> u8 {
+        self.month
+    }
+    pub fn day(self) -> u8 {
+        self.day
+    }
+    /// Days since 1970-01-01, negative before it.
+    pub fn days_since_epoch(self) -> i64 {
+        // Counts years from March so the leap day comes last.
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+/// `YYYY-MM-DD`, as in ISO 8601.
+impl core::str::FromStr for Date {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SudokuError::ParseError(ParseError::Invalid("Invalid date."));
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = |digits: usize| {
+            parts
+                .next()
+                .filter(|p| p.len() == digits && p.bytes().all(|b| b.is_ascii_digit()))
+                .ok_or_else(invalid)
+        };
+        let (year, month, day) = (next(4)?, next(2)?, next(2)?);
+        Date::new(
+            year.parse().map_err(|_| invalid())?,
+            month.parse().map_err(|_| invalid())?,
+            day.parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+impl core::fmt::Display for Date {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for Date {
+    fn from(date: chrono::NaiveDate) -> Self {
+        use chrono::Datelike;
+        // chrono only makes real days, with months and days in range.
+        Self {
+            year: date.year(),
+            month: date.month() as u8,
+            day: date.day() as u8,
+        }
+    }
+}
+
+// The version goes in the top bits so a new version starts a fresh
+// sequence instead of shifting the old one by a few days.
+fn seed(date: Date, difficulty: Difficulty) -> u64 {
+    let day = date.days_since_epoch() as u64 & 0xFFFF_FFFF;
+    (u64::from(DAILY_VERSION) << 40) | ((difficulty as u64) << 32) | day
+}
+
+impl Sudoku {
+    /// The puzzle of the day: every client asking for the same date and
+    /// difficulty gets the same board, offline and on any platform, for as
+    /// long as [`DAILY_VERSION`] stays the same.
+    pub fn daily(date: Date, difficulty: Difficulty) -> Sudoku {
+        Sudoku::generate_with_seed(seed(date, difficulty), difficulty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_counts_days() {
+        let date = "2024-02-29".parse::<Date>().unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2024, 2, 29));
+        assert_eq!(date.to_string(), "2024-02-29");
+        assert_eq!(date.days_since_epoch(), 19_782);
+        assert_eq!(Date::new(1970, 1, 1).unwrap().days_since_epoch(), 0);
+        assert_eq!(Date::new(1969, 12, 31).unwrap().days_since_epoch(), -1);
+        assert_eq!(Date::new(2000, 3, 1).unwrap().days_since_epoch(), 11_017);
+        for bad in [
+            "2023-02-29",
+            "1900-02-29",
+            "2024-13-01",
+            "2024-1-01",
+            "24-01-01",
+        ] {
+            assert!(bad.parse::<Date>().is_err(), "{bad}");
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn converts_chrono_dates() {
+        let naive = chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        assert_eq!(Date::from(naive), Date::new(2024, 2, 29).unwrap());
+        let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+        for naive in [naive, chrono::NaiveDate::from_ymd_opt(-44, 3, 15).unwrap()] {
+            let days = naive.signed_duration_since(epoch).num_days();
+            assert_eq!(Date::from(naive).days_since_epoch(), days);
+        }
+    }
+
+    #[test]
+    fn daily_puzzle_is_stable() {
+        let date = Date::new(2024, 1, 1).unwrap();
+        let puzzle = Sudoku::daily(date, Difficulty::Medium);
+        assert_eq!(puzzle, Sudoku::daily(date, Difficulty::Medium));
+        assert!(puzzle.has_unique_solution());
+        let next = Date::new(2024, 1, 2).unwrap();
+        assert_ne!(puzzle, Sudoku::daily(next, Difficulty::Medium));
+        assert_ne!(
+            puzzle.solve(),
+            Sudoku::daily(date, Difficulty::Hard).solve()
+        );
+        // Changing this means changing DAILY_VERSION.
+        assert_eq!(
+            puzzle.to_line_string(),
+            "..3912...8.......9..1.....4....6..8378..5....2....8.4..7.4.3.5....6..3..352.9...8"
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! An append-only store of puzzles on disk, indexed by difficulty and by a
+//! hash of their canonical form so disguised copies are easy to spot.
+//!
+//! The store is two files. The data file starts with `SDKD` and a version
+//! byte, then holds each puzzle as the 41 bytes of its [`CompactSudoku`].
+//! The index file, next to it with an `idx` extension, starts with `SDKI`
+//! and the same version byte, then holds a record per puzzle in the same
+//! order: its canonical hash as a little-endian `u64` and its difficulty as
+//! one byte. Puzzles are written before their index records, and opening a
+//! store rebuilds any records a crash left out. A record a crash only half
+//! wrote, at the end of either file, is dropped.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+
+use crate::symmetry::fnv1a;
+use crate::{CompactSudoku, Difficulty, Rng, Sudoku};
+
+const DATA_MAGIC: &[u8; 4] = b"SDKD";
+const INDEX_MAGIC: &[u8; 4] = b"SDKI";
+/// The file version [`PuzzleDb::open`] writes and understands.
+pub const DB_VERSION: u8 = 1;
+
+const HEADER_LEN: usize = 5;
+const PUZZLE_LEN: usize = 41;
+const RECORD_LEN: usize = 9;
+const DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Expert,
+];
+
+/// The puzzles live in memory in compact form; every insert is appended to
+/// both files straight away.
+#[derive(Debug)]
+pub struct PuzzleDb {
+    data: File,
+    index: File,
+    puzzles: Vec<CompactSudoku>,
+    difficulties: Vec<Difficulty>,
+    by_difficulty: [Vec<usize>; 4],
+    by_hash: HashMap<u64, Vec<usize>>,
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn compact(puzzle: &Sudoku) -> io::Result<CompactSudoku> {
+    CompactSudoku::try_from(puzzle).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+// Every disguise of a puzzle has the same canonical form, and so the same
+// hash of it. Only the values count, as in the store.
+fn canonical(puzzle: &CompactSudoku) -> CompactSudoku {
+    let form = puzzle.to_sudoku().canonicalize();
+    CompactSudoku::try_from(&form).unwrap()
+}
+
+fn hash(canonical: &CompactSudoku) -> u64 {
+    fnv1a(canonical.as_bytes())
+}
+
+// Opens `path` for reading and appending, writing the header to a new or
+// empty file and checking it on an existing one. Returns the file and the
+// bytes after the header.
+fn open_file(path: &Path, magic: &[u8; 4]) -> io::Result<(File, Vec<u8>)> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    if bytes.is_empty() {
+        file.write_all(magic)?;
+        file.write_all(&[DB_VERSION])?;
+        return Ok((file, bytes));
+    }
+    match bytes.split_at_checked(HEADER_LEN) {
+        Some((header, rest)) if header[..4] == magic[..] && header[4] == DB_VERSION => {
+            Ok((file, rest.to_vec()))
+        }
+        _ => Err(invalid("Not a puzzle database.")),
+    }
+}
+
+// Cuts `bytes`, and the file they came from, back to whole records.
+fn drop_partial(file: &File, bytes: &mut Vec<u8>, record_len: usize) -> io::Result<()> {
+    let whole = bytes.len() - bytes.len() % record_len;
+    if whole < bytes.len() {
+        file.set_len((HEADER_LEN + whole) as u64)?;
+        bytes.truncate(whole);
+    }
+    Ok(())
+}
+
+impl PuzzleDb {
+    /// Opens the store at `path`, creating it if it doesn't exist. The
+    /// index goes next to it, at `path` with the extension `idx`.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let (data, mut puzzles) = open_file(path, DATA_MAGIC)?;
+        let (index, mut records) = open_file(&index_path(path), INDEX_MAGIC)?;
+        drop_partial(&data, &mut puzzles, PUZZLE_LEN)?;
+        drop_partial(&index, &mut records, RECORD_LEN)?;
+        let mut db = Self {
+            data,
+            index,
+            puzzles: Vec::new(),
+            difficulties: Vec::new(),
+            by_difficulty: Default::default(),
+            by_hash: HashMap::new(),
+        };
+        let mut records = records.chunks_exact(RECORD_LEN);
+        for chunk in puzzles.chunks_exact(PUZZLE_LEN) {
+            let bytes: [u8; PUZZLE_LEN] = chunk.try_into().unwrap();
+            let puzzle = CompactSudoku::from_bytes(bytes)
+                .map_err(|_| invalid("Corrupt puzzle in database."))?;
+            match records.next() {
+                Some(record) => {
+                    let hash = u64::from_le_bytes(record[..8].try_into().unwrap());
+                    let difficulty = *DIFFICULTIES
+                        .get(record[8] as usize)
+                        .ok_or_else(|| invalid("Corrupt index record."))?;
+                    db.remember(puzzle, hash, difficulty);
+                }
+                None => {
+                    let hash = hash(&canonical(&puzzle));
+                    let difficulty = puzzle.to_sudoku().grade();
+                    db.write_record(hash, difficulty)?;
+                    db.remember(puzzle, hash, difficulty);
+                }
+            }
+        }
+        if records.next().is_some() {
+            return Err(invalid("Index lists puzzles the database doesn't have."));
+        }
+        Ok(db)
+    }
+
+    /// Grades the puzzle and appends it, returning its id. Only 9x9 boards
+    /// fit; other sizes fail with `InvalidInput`. Copies are stored too, so
+    /// check [`PuzzleDb::contains_isomorph`] first to keep them out.
+    pub fn insert(&mut self, puzzle: &Sudoku) -> io::Result<usize> {
+        self.insert_with_difficulty(puzzle, puzzle.grade())
+    }
+    /// Like [`PuzzleDb::insert`], keeping a difficulty graded elsewhere.
+    pub fn insert_with_difficulty(
+        &mut self,
+        puzzle: &Sudoku,
+        difficulty: Difficulty,
+    ) -> io::Result<usize> {
+        let puzzle = compact(puzzle)?;
+        let hash = hash(&canonical(&puzzle));
+        self.data.write_all(puzzle.as_bytes())?;
+        self.write_record(hash, difficulty)?;
+        Ok(self.remember(puzzle, hash, difficulty))
+    }
+    /// True if the store holds the puzzle or a copy of it with digits
+    /// relabelled or rows, columns, bands and stacks moved around.
+    pub fn contains_isomorph(&self, puzzle: &Sudoku) -> bool {
+        let Ok(puzzle) = compact(puzzle) else {
+            return false;
+        };
+        let form = canonical(&puzzle);
+        self.by_hash
+            .get(&hash(&form))
+            .is_some_and(|ids| ids.iter().any(|&id| canonical(&self.puzzles[id]) == form))
+    }
+    /// A puzzle of the given difficulty picked by `rng`, or `None` if there
+    /// are none.
+    pub fn sample(&self, difficulty: Difficulty, rng: &mut Rng) -> Option<Sudoku> {
+        let ids = &self.by_difficulty[difficulty as usize];
+        if ids.is_empty() {
+            return None;
+        }
+        self.get(ids[rng.below(ids.len())])
+    }
+    pub fn get(&self, id: usize) -> Option<Sudoku> {
+        self.puzzles.get(id).map(CompactSudoku::to_sudoku)
+    }
+    pub fn difficulty(&self, id: usize) -> Option<Difficulty> {
+        self.difficulties.get(id).copied()
+    }
+    /// The ids of every puzzle of the given difficulty, oldest first.
+    pub fn ids_with_difficulty(&self, difficulty: Difficulty) -> &[usize] {
+        &self.by_difficulty[difficulty as usize]
+    }
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+    /// Flushes both files to disk.
+    pub fn sync(&self) -> io::Result<()> {
+        self.data.sync_data()?;
+        self.index.sync_data()
+    }
+
+    fn write_record(&mut self, hash: u64, difficulty: Difficulty) -> io::Result<()> {
+        let mut record = [0; RECORD_LEN];
+        record[..8].copy_from_slice(&hash.to_le_bytes());
+        record[8] = difficulty as u8;
+        self.index.write_all(&record)
+    }
+    fn remember(&mut self, puzzle: CompactSudoku, hash: u64, difficulty: Difficulty) -> usize {
+        let id = self.puzzles.len();
+        self.puzzles.push(puzzle);
+        self.difficulties.push(difficulty);
+        self.by_difficulty[difficulty as usize].push(id);
+        self.by_hash.entry(hash).or_default().push(id);
+        id
+    }
+}
+
+fn index_path(path: &Path) -> PathBuf {
+    path.with_extension("idx")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    // A scratch directory, removed again when the test is done with it.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("sudoku-db-{}-{name}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+        fn db(&self) -> PathBuf {
+            self.0.join("puzzles.db")
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn stores_and_reopens() {
+        let dir = TempDir::new("reopen");
+        let path = dir.db();
+        let puzzles = (0..6)
+            .map(|seed| Sudoku::generate_with_seed(seed, Difficulty::Easy))
+            .collect::<Vec<_>>();
+        {
+            let mut db = PuzzleDb::open(&path).unwrap();
+            assert!(db.is_empty());
+            for (i, p) in puzzles.iter().enumerate() {
+                assert_eq!(db.insert(p).unwrap(), i);
+            }
+            db.sync().unwrap();
+        }
+        let db = PuzzleDb::open(&path).unwrap();
+        assert_eq!(db.len(), 6);
+        assert_eq!(db.get(2).as_ref(), Some(&puzzles[2]));
+        assert_eq!(db.difficulty(2), Some(puzzles[2].grade()));
+        let disguised = puzzles[4].transpose().rotate90();
+        assert!(db.contains_isomorph(&disguised));
+        assert!(!db.contains_isomorph(&Sudoku::generate_with_seed(99, Difficulty::Easy)));
+
+        let difficulty = db.difficulty(0).unwrap();
+        let mut rng = Rng::from_seed(1);
+        for _ in 0..10 {
+            let picked = db.sample(difficulty, &mut rng).unwrap();
+            assert_eq!(picked.grade(), difficulty);
+        }
+        let missing = DIFFICULTIES
+            .into_iter()
+            .find(|&d| db.ids_with_difficulty(d).is_empty())
+            .unwrap();
+        assert_eq!(db.sample(missing, &mut rng), None);
+    }
+
+    #[test]
+    fn rebuilds_a_short_index() {
+        let dir = TempDir::new("rebuild");
+        let path = dir.db();
+        let puzzle = Sudoku::generate_with_seed(3, Difficulty::Medium);
+        {
+            let mut db = PuzzleDb::open(&path).unwrap();
+            db.insert_with_difficulty(&puzzle, Difficulty::Hard)
+                .unwrap();
+            db.insert(&puzzle.mirror_horizontal()).unwrap();
+        }
+        // As if the second index record never made it to disk.
+        let index = index_path(&path);
+        let bytes = fs::read(&index).unwrap();
+        fs::write(&index, &bytes[..bytes.len() - RECORD_LEN]).unwrap();
+        let db = PuzzleDb::open(&path).unwrap();
+        assert_eq!(db.len(), 2);
+        assert_eq!(db.difficulty(0), Some(Difficulty::Hard));
+        assert_eq!(db.by_hash.len(), 1);
+        assert_eq!(fs::read(&index).unwrap(), bytes);
+
+        fs::write(&path, b"nope").unwrap();
+        let err = PuzzleDb::open(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let small_dir = TempDir::new("small");
+        let mut db = PuzzleDb::open(small_dir.db()).unwrap();
+        let small = Sudoku::new(crate::Size::new(2).unwrap());
+        assert_eq!(
+            db.insert(&small).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn drops_a_half_written_puzzle() {
+        let dir = TempDir::new("partial");
+        let path = dir.db();
+        let puzzles = [1, 2].map(|seed| Sudoku::generate_with_seed(seed, Difficulty::Easy));
+        {
+            let mut db = PuzzleDb::open(&path).unwrap();
+            db.insert(&puzzles[0]).unwrap();
+        }
+        // As if the second puzzle was cut off mid-write, before its index.
+        let whole = fs::read(&path).unwrap();
+        let second = compact(&puzzles[1]).unwrap();
+        let mut bytes = whole.clone();
+        bytes.extend_from_slice(&second.as_bytes()[..PUZZLE_LEN / 2]);
+        fs::write(&path, &bytes).unwrap();
+        {
+            let mut db = PuzzleDb::open(&path).unwrap();
+            assert_eq!(db.len(), 1);
+            assert_eq!(fs::read(&path).unwrap(), whole);
+            assert_eq!(db.insert(&puzzles[1]).unwrap(), 1);
+        }
+        let index = index_path(&path);
+        let mut records = fs::read(&index).unwrap();
+        records.truncate(records.len() - RECORD_LEN / 2);
+        fs::write(&index, &records).unwrap();
+        let db = PuzzleDb::open(&path).unwrap();
+        assert_eq!(db.len(), 2);
+        assert_eq!(db.get(1).as_ref(), Some(&puzzles[1]));
+        assert_eq!(db.difficulty(1), Some(puzzles[1].grade()));
+        assert_eq!(fs::read(&index).unwrap().len(), HEADER_LEN + 2 * RECORD_LEN);
+    }
+}
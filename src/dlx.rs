@@ -0,0 +1,230 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Rules, Size};
+
+// Knuth's dancing links over the exact cover form of a board. Each choice of
+// value `v` for cell `i` is a row covering four or more columns: the cell
+// itself plus "house has v" for every house the cell is in. Node 0 is the
+// root; nodes 1..=columns are the column headers. Linked cells under the
+// anti-knight and anti-king rules add secondary "pair has v" columns, which
+// rows may cover at most once and which stay out of the root's list.
+pub(crate) struct Dlx {
+    side: usize,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    // Board row (`i * side + v - 1`) each node belongs to.
+    row: Vec<usize>,
+    size: Vec<usize>,
+    // First node of every board row.
+    first: Vec<usize>,
+    covered: Vec<bool>,
+    // Board rows fixed by `place`.
+    fixed: Vec<usize>,
+}
+
+impl Dlx {
+    pub(crate) fn new(size: Size, rules: Rules) -> Self {
+        let side = size.side() as usize;
+        let cells = size.cell_count();
+        let houses = rules.houses(size).collect::<Vec<_>>();
+        let pairs = rules.link_pairs(size);
+        let primary = cells + houses.len() * side;
+        let columns = primary + pairs.len() * side;
+        let mut dlx = Self {
+            side,
+            left: (0..=columns)
+                .map(|c| c.checked_sub(1).unwrap_or(columns))
+                .collect(),
+            right: (0..=columns).map(|c| (c + 1) % (columns + 1)).collect(),
+            up: (0..=columns).collect(),
+            down: (0..=columns).collect(),
+            column: (0..=columns).collect(),
+            row: vec![usize::MAX; columns + 1],
+            size: vec![0; columns + 1],
+            first: Vec::with_capacity(cells * side),
+            covered: vec![false; columns + 1],
+            fixed: Vec::new(),
+        };
+        if columns > primary {
+            dlx.left[0] = primary;
+            dlx.right[primary] = 0;
+            for c in primary + 1..=columns {
+                dlx.left[c] = c;
+                dlx.right[c] = c;
+            }
+        }
+        let mut pairs_of = vec![Vec::new(); cells];
+        for (k, &(a, b)) in pairs.iter().enumerate() {
+            pairs_of[a].push(k);
+            pairs_of[b].push(k);
+        }
+        for pos in size.positions() {
+            let i = size.index_of(pos);
+            let of = rules.houses_containing(size, pos);
+            let units = houses
+                .iter()
+                .enumerate()
+                .filter(|(_, h)| of.contains(h))
+                .map(|(u, _)| u)
+                .collect::<Vec<_>>();
+            for v in 0..side {
+                let mut cols = vec![1 + i];
+                cols.extend(units.iter().map(|u| 1 + cells + u * side + v));
+                cols.extend(pairs_of[i].iter().map(|k| 1 + primary + k * side + v));
+                dlx.add_row(i * side + v, &cols);
+            }
+        }
+        dlx
+    }
+
+    fn add_row(&mut self, row: usize, cols: &[usize]) {
+        let start = self.left.len();
+        self.first.push(start);
+        for (k, &c) in cols.iter().enumerate() {
+            let node = start + k;
+            self.left.push(if k == 0 {
+                start + cols.len() - 1
+            } else {
+                node - 1
+            });
+            self.right
+                .push(if k + 1 == cols.len() { start } else { node + 1 });
+            self.up.push(self.up[c]);
+            self.down.push(c);
+            self.column.push(c);
+            self.row.push(row);
+            let above = self.up[c];
+            self.down[above] = node;
+            self.up[c] = node;
+            self.size[c] += 1;
+        }
+    }
+
+    fn cover(&mut self, c: usize) {
+        self.covered[c] = true;
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = r;
+        self.left[r] = l;
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.size[self.column[j]] += 1;
+                self.down[u] = j;
+                self.up[d] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = c;
+        self.left[r] = c;
+        self.covered[c] = false;
+    }
+
+    // Covers every other column of the row `node` is on.
+    fn select(&mut self, node: usize) {
+        let mut j = self.right[node];
+        while j != node {
+            self.cover(self.column[j]);
+            j = self.right[j];
+        }
+    }
+
+    fn deselect(&mut self, node: usize) {
+        let mut j = self.left[node];
+        while j != node {
+            self.uncover(self.column[j]);
+            j = self.left[j];
+        }
+    }
+
+    /// Fixes the value of a cell before searching. False if it clashes with
+    /// values fixed earlier.
+    pub(crate) fn place(&mut self, i: usize, v: u8) -> bool {
+        let node = self.first[i * self.side + v as usize - 1];
+        let mut j = node;
+        loop {
+            if self.covered[self.column[j]] {
+                return false;
+            }
+            j = self.right[j];
+            if j == node {
+                break;
+            }
+        }
+        self.cover(self.column[node]);
+        self.select(node);
+        self.fixed.push(self.row[node]);
+        true
+    }
+
+    /// Counts up to `limit` solutions, keeping the values of the first one
+    /// found in `Size::index_of` order.
+    pub(crate) fn count(&mut self, limit: usize, first: &mut Option<Vec<u8>>) -> usize {
+        let mut chosen = Vec::new();
+        let mut found = 0;
+        self.search(limit, &mut chosen, &mut found, first);
+        found
+    }
+
+    fn search(
+        &mut self,
+        limit: usize,
+        chosen: &mut Vec<usize>,
+        found: &mut usize,
+        first: &mut Option<Vec<u8>>,
+    ) {
+        if self.right[0] == 0 {
+            *found += 1;
+            if first.is_none() {
+                let mut values = vec![0; self.first.len() / self.side];
+                let rows = chosen.iter().map(|&n| self.row[n]);
+                for row in self.fixed.iter().copied().chain(rows) {
+                    values[row / self.side] = (row % self.side) as u8 + 1;
+                }
+                *first = Some(values);
+            }
+            return;
+        }
+        let mut c = self.right[0];
+        let mut j = self.right[c];
+        while j != 0 {
+            if self.size[j] < self.size[c] {
+                c = j;
+            }
+            j = self.right[j];
+        }
+        self.cover(c);
+        let mut r = self.down[c];
+        while r != c && *found < limit {
+            chosen.push(r);
+            self.select(r);
+            self.search(limit, chosen, found, first);
+            self.deselect(r);
+            chosen.pop();
+            r = self.down[r];
+        }
+        self.uncover(c);
+    }
+}
@@ -0,0 +1,105 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SudokuError {
+    OutOfBounds,
+    InvalidSize,
+    InvalidValue(u8),
+    Conflict(u8),
+    GivenCell,
+    InvalidCage,
+    InvalidRegions,
+    InvalidLine,
+    InvalidClue,
+    ParseError(ParseError),
+}
+
+/// Why text couldn't be read as a board or one of the crate's types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// A character that isn't a value or blank the parser accepts, or a
+    /// value too large for the board. `index` counts characters from the
+    /// start of the input; `row` and `column` are its 1-based line and
+    /// column there.
+    InvalidCharacter {
+        character: char,
+        index: usize,
+        row: usize,
+        column: usize,
+    },
+    /// The input held `cells` cells, which isn't a supported board size.
+    WrongLength { cells: usize },
+    /// Input that isn't anything the parser knows, such as an unknown name.
+    Invalid(&'static str),
+}
+
+impl ParseError {
+    /// An `InvalidCharacter` for the character at `index` of `input`.
+    pub(crate) fn at(input: &str, index: usize) -> Self {
+        let (mut row, mut column) = (1, 1);
+        let mut character = '\0';
+        for (i, c) in input.chars().enumerate() {
+            if i == index {
+                character = c;
+                break;
+            }
+            if c == '\n' {
+                row += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        ParseError::InvalidCharacter {
+            character,
+            index,
+            row,
+            column,
+        }
+    }
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseError::InvalidCharacter {
+                character,
+                row,
+                column,
+                ..
+            } => write!(
+                f,
+                "Invalid character {character:?} at row {row}, column {column}."
+            ),
+            ParseError::WrongLength { cells } => {
+                write!(f, "Expected 16, 81, 256 or 625 cells but found {cells}.")
+            }
+            ParseError::Invalid(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl From<ParseError> for SudokuError {
+    fn from(e: ParseError) -> Self {
+        SudokuError::ParseError(e)
+    }
+}
+
+impl core::fmt::Display for SudokuError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SudokuError::OutOfBounds => write!(f, "Position out of bounds."),
+            SudokuError::InvalidSize => write!(f, "Board size is not supported."),
+            SudokuError::InvalidValue(v) => write!(f, "Cell number {v} is invalid."),
+            SudokuError::Conflict(v) => write!(f, "Number {v} conflicts with a peer cell."),
+            SudokuError::GivenCell => write!(f, "Cell is a given and can't be changed."),
+            SudokuError::InvalidCage => write!(f, "Cage cells or sum are invalid."),
+            SudokuError::InvalidClue => write!(f, "Clue is invalid."),
+            SudokuError::InvalidLine => write!(f, "Thermometer or arrow cells are invalid."),
+            SudokuError::InvalidRegions => {
+                write!(f, "Regions must be connected and hold one cell per value.")
+            }
+            SudokuError::ParseError(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl core::error::Error for SudokuError {}
@@ -0,0 +1,218 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Hint, House, Pos, Technique};
+
+/// Writes hints as prose. Implement it to word hints differently, or in
+/// another language; [`English`] is the built-in one.
+pub trait HintFormatter {
+    fn technique_name(&self, technique: Technique) -> String;
+    fn explain(&self, hint: &Hint) -> String;
+}
+
+/// Explanations like "8 can only go in r3c5 within box 2." Rows, columns
+/// and houses are counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct English;
+
+impl HintFormatter for English {
+    fn technique_name(&self, technique: Technique) -> String {
+        match technique {
+            Technique::NakedSingle => "Naked single",
+            Technique::HiddenSingle => "Hidden single",
+            Technique::PointingPair => "Pointing pair",
+            Technique::Claiming => "Claiming",
+            Technique::NakedPair => "Naked pair",
+            Technique::NakedTriple => "Naked triple",
+            Technique::NakedQuad => "Naked quad",
+            Technique::HiddenPair => "Hidden pair",
+            Technique::HiddenTriple => "Hidden triple",
+            Technique::HiddenQuad => "Hidden quad",
+            Technique::XWing => "X-Wing",
+            Technique::Swordfish => "Swordfish",
+            Technique::Jellyfish => "Jellyfish",
+            Technique::XYWing => "XY-Wing",
+            Technique::XYZWing => "XYZ-Wing",
+            Technique::SimpleColoring => "Simple coloring",
+            Technique::XChain => "X-Chain",
+        }
+        .into()
+    }
+
+    fn explain(&self, hint: &Hint) -> String {
+        let cells = list(hint.positions.iter().map(|&p| cell(p)));
+        let houses = list(hint.houses.iter().map(|&h| house(h)));
+        let cover = list(hint.cover.iter().map(|&h| house(h)));
+        let removals = removals(&hint.eliminations);
+        // Every technique that eliminates works on a single value, except
+        // the subsets.
+        let value = hint.eliminations.first().map_or(0, |&(_, v)| v);
+        match (hint.technique, hint.placement) {
+            (Technique::NakedSingle, Some((pos, v))) => {
+                format!("{v} is the only candidate left in {}.", cell(pos))
+            }
+            (Technique::HiddenSingle, Some((pos, v))) => {
+                format!("{v} can only go in {} within {houses}.", cell(pos))
+            }
+            (Technique::PointingPair | Technique::Claiming, _) => format!(
+                "Within {houses}, {value} can only go in {cells}, which are all in {cover}, \
+                 so remove {removals}."
+            ),
+            (Technique::NakedPair | Technique::NakedTriple | Technique::NakedQuad, _) => format!(
+                "{cells} only have {} candidates between them, so no other cell of {houses} \
+                 can take those: remove {removals}.",
+                hint.positions.len()
+            ),
+            (Technique::HiddenPair | Technique::HiddenTriple | Technique::HiddenQuad, _) => {
+                format!(
+                    "Within {houses}, {} values can only go in {cells}, so those cells can't \
+                 hold anything else: remove {removals}.",
+                    ["two", "three", "four"][hint.positions.len().clamp(2, 4) - 2]
+                )
+            }
+            (Technique::XWing | Technique::Swordfish | Technique::Jellyfish, _) => format!(
+                "Within {houses}, {value} can only go in {cover}, so the rest of {cover} \
+                 can't: remove {removals}."
+            ),
+            (Technique::XYWing | Technique::XYZWing, _) => format!(
+                "Whatever {} holds, {value} goes in {}, so remove {removals}.",
+                cell(hint.positions[0]),
+                list_with(hint.positions[1..].iter().map(|&p| cell(p)), "or")
+            ),
+            (Technique::SimpleColoring, _) => format!(
+                "Coloring {cells} in two alternating colors along the strong links on \
+                 {value}, one color must hold it, so remove {removals}."
+            ),
+            (Technique::XChain, _) => {
+                let mut chain = String::new();
+                for (i, link) in hint.chain.iter().enumerate() {
+                    if i == 0 {
+                        chain.push_str(&cell(link.from));
+                    }
+                    chain.push_str(if link.strong { " = " } else { " - " });
+                    chain.push_str(&cell(link.to));
+                }
+                format!("Following {value} along {chain}, remove {removals}.")
+            }
+            // Singles always come with a placement.
+            (Technique::NakedSingle | Technique::HiddenSingle, None) => {
+                format!("Remove {removals}.")
+            }
+        }
+    }
+}
+
+impl Hint {
+    /// The hint in plain English. See [`English`].
+    pub fn explain(&self) -> String {
+        English.explain(self)
+    }
+    pub fn explain_with(&self, formatter: &(impl HintFormatter + ?Sized)) -> String {
+        formatter.explain(self)
+    }
+}
+
+fn cell(pos: Pos) -> String {
+    format!("r{}c{}", pos.y() + 1, pos.x() + 1)
+}
+
+fn house(house: House) -> String {
+    match house {
+        House::Row(y) => format!("row {}", y + 1),
+        House::Column(x) => format!("column {}", x + 1),
+        House::Box(b) => format!("box {}", b + 1),
+        House::Diagonal(0) => "the main diagonal".into(),
+        House::Diagonal(_) => "the anti-diagonal".into(),
+        House::Window(w) => format!("window {}", w + 1),
+    }
+}
+
+// "a", "a and b", "a, b and c".
+fn list(items: impl Iterator<Item = String>) -> String {
+    list_with(items, "and")
+}
+
+fn list_with(items: impl Iterator<Item = String>, last_word: &str) -> String {
+    let items = items.collect::<Vec<_>>();
+    match items.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} {last_word} {last}", rest.join(", ")),
+    }
+}
+
+// "2 from r1c5 and r1c6, 7 from r2c1", grouped by value.
+fn removals(eliminations: &[(Pos, u8)]) -> String {
+    let mut groups: Vec<(u8, Vec<Pos>)> = Vec::new();
+    for &(pos, v) in eliminations {
+        match groups.iter_mut().find(|(w, _)| *w == v) {
+            Some((_, cells)) => cells.push(pos),
+            None => groups.push((v, vec![pos])),
+        }
+    }
+    list(
+        groups
+            .into_iter()
+            .map(|(v, cells)| format!("{v} from {}", list(cells.into_iter().map(cell)))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::Sudoku;
+
+    #[test]
+    fn explains_singles_in_plain_english() {
+        let hint = Hint {
+            technique: Technique::HiddenSingle,
+            positions: vec![Pos::new_unchecked(4, 2)],
+            eliminations: Vec::new(),
+            placement: Some((Pos::new_unchecked(4, 2), 8)),
+            houses: vec![House::Box(1)],
+            cover: Vec::new(),
+            chain: Vec::new(),
+        };
+        assert_eq!(hint.explain(), "8 can only go in r3c5 within box 2.");
+        let pointing = Hint {
+            technique: Technique::PointingPair,
+            positions: vec![Pos::new_unchecked(1, 0), Pos::new_unchecked(2, 0)],
+            eliminations: vec![(Pos::new_unchecked(6, 0), 4), (Pos::new_unchecked(7, 0), 4)],
+            placement: None,
+            houses: vec![House::Box(0)],
+            cover: vec![House::Row(0)],
+            chain: Vec::new(),
+        };
+        assert_eq!(
+            pointing.explain(),
+            "Within box 1, 4 can only go in r1c2 and r1c3, which are all in row 1, \
+             so remove 4 from r1c7 and r1c8."
+        );
+    }
+
+    #[test]
+    fn formatters_can_be_swapped() {
+        struct Terse;
+        impl HintFormatter for Terse {
+            fn technique_name(&self, technique: Technique) -> String {
+                format!("{technique:?}")
+            }
+            fn explain(&self, hint: &Hint) -> String {
+                self.technique_name(hint.technique)
+            }
+        }
+        let mut s = Sudoku::from_str(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        )
+        .unwrap();
+        while let Some(hint) = s.hint() {
+            assert!(hint.explain().ends_with('.'));
+            assert_eq!(hint.explain_with(&Terse), format!("{:?}", hint.technique));
+            s.apply_hint(&hint).unwrap();
+        }
+    }
+}
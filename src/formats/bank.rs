@@ -0,0 +1,142 @@
+use std::io::{self, BufRead, Write};
+use std::string::String;
+use std::vec::Vec;
+
+use super::{parse_as, Format, ReadError};
+use crate::generator::Difficulty;
+use crate::{ParseError, Sudoku, SudokuError};
+
+/// One puzzle of a Sudoku Exchange puzzle bank, with the rating the bank
+/// lists for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BankPuzzle {
+    pub id: String,
+    pub puzzle: Sudoku,
+    pub rating: f32,
+}
+
+impl BankPuzzle {
+    /// Parses an `id grid rating` line.
+    pub fn parse(line: &str) -> Result<Self, SudokuError> {
+        let mut words = line.split_whitespace();
+        let (Some(id), Some(grid), Some(rating), None) =
+            (words.next(), words.next(), words.next(), words.next())
+        else {
+            return Err(SudokuError::ParseError(ParseError::Invalid(
+                "Expected id, grid and rating.",
+            )));
+        };
+        Ok(Self {
+            id: id.into(),
+            puzzle: parse_as(grid, Format::Line)?,
+            rating: rating
+                .parse()
+                .map_err(|_| SudokuError::ParseError(ParseError::Invalid("Invalid rating.")))?,
+        })
+    }
+    /// The crate's own grade, for comparing against `rating`.
+    pub fn grade(&self) -> Difficulty {
+        self.puzzle.grade()
+    }
+}
+
+/// A Sudoku Exchange puzzle bank: one `id grid rating` line per puzzle, the
+/// grid being 81 digits with `0` for empty cells.
+#[derive(Debug, Default)]
+pub struct SudokuBank {
+    puzzles: Vec<BankPuzzle>,
+}
+
+impl SudokuBank {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Parses puzzles one line at a time. Blank lines are skipped.
+    pub fn stream<R: BufRead>(reader: R) -> impl Iterator<Item = Result<BankPuzzle, ReadError>> {
+        reader
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(l) if l.trim().is_empty()))
+            .map(|(i, line)| {
+                BankPuzzle::parse(&line?).map_err(|error| ReadError::Parse { line: i + 1, error })
+            })
+    }
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, ReadError> {
+        Ok(Self {
+            puzzles: Self::stream(reader).collect::<Result<_, _>>()?,
+        })
+    }
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for p in &self.puzzles {
+            writeln!(
+                writer,
+                "{} {} {:.1}",
+                p.id,
+                p.puzzle.to_line_string().replace('.', "0"),
+                p.rating
+            )?;
+        }
+        Ok(())
+    }
+    pub fn push(&mut self, puzzle: BankPuzzle) {
+        self.puzzles.push(puzzle);
+    }
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &BankPuzzle> {
+        self.puzzles.iter()
+    }
+}
+
+impl IntoIterator for SudokuBank {
+    type Item = BankPuzzle;
+    type IntoIter = std::vec::IntoIter<BankPuzzle>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.puzzles.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BANK: &str = "\
+0000183b305c 050083017000100400304005608000030009090824500006000070009000050007290086103607204 1.2
+000547a0a3ed 480506000700300860000000000002004053900000000000010000050401000021000008000000300 6.5
+";
+
+    #[test]
+    fn round_trips_bank_files() {
+        let bank = SudokuBank::read(BANK.as_bytes()).unwrap();
+        assert_eq!(bank.len(), 2);
+        let first = bank.iter().next().unwrap();
+        assert_eq!(first.id, "0000183b305c");
+        assert_eq!(first.rating, 1.2);
+        assert_eq!(first.puzzle[(1, 0)].value(), Some(5));
+        assert_eq!(first.grade(), Difficulty::Easy);
+        let mut out = Vec::new();
+        bank.write(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), BANK);
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        let text = format!("{BANK}\nabc 123 1.0\n{}\n", &BANK[..94]);
+        let results = SudokuBank::stream(text.as_bytes()).collect::<Vec<_>>();
+        assert!(matches!(results[2], Err(ReadError::Parse { line: 4, .. })));
+        assert!(matches!(
+            results[3],
+            Err(ReadError::Parse {
+                line: 5,
+                error: SudokuError::ParseError(ParseError::Invalid(
+                    "Expected id, grid and rating."
+                ))
+            })
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+use std::io::{self, BufRead, Write};
+use std::vec::Vec;
+
+use super::{parse_as, Format, ReadError};
+use crate::{ParseError, Sudoku, SudokuError};
+
+/// Puzzles paired with their solutions, in the `quizzes,solutions` CSV
+/// format of the Kaggle sudoku datasets: a header line, then two 81-digit
+/// columns with `0` for empty cells.
+#[derive(Debug, Default)]
+pub struct Dataset {
+    pairs: Vec<(Sudoku, Sudoku)>,
+}
+
+fn parse_pair(line: &str) -> Result<(Sudoku, Sudoku), SudokuError> {
+    let mut columns = line.split(',').map(str::trim);
+    let mut next = || {
+        let column = columns
+            .next()
+            .ok_or(SudokuError::ParseError(ParseError::Invalid(
+                "Missing CSV column.",
+            )))?;
+        parse_as(column, Format::Line)
+    };
+    Ok((next()?, next()?))
+}
+
+impl Dataset {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Parses pairs one line at a time. The header and blank lines are
+    /// skipped; columns after the first two are ignored.
+    pub fn stream<R: BufRead>(
+        reader: R,
+    ) -> impl Iterator<Item = Result<(Sudoku, Sudoku), ReadError>> {
+        reader
+            .lines()
+            .enumerate()
+            .filter(|(i, line)| match line {
+                Ok(l) => !(l.trim().is_empty() || *i == 0 && parse_pair(l).is_err()),
+                Err(_) => true,
+            })
+            .map(|(i, line)| {
+                parse_pair(&line?).map_err(|error| ReadError::Parse { line: i + 1, error })
+            })
+    }
+    pub fn from_csv<R: BufRead>(reader: R) -> Result<Self, ReadError> {
+        Ok(Self {
+            pairs: Self::stream(reader).collect::<Result<_, _>>()?,
+        })
+    }
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "quizzes,solutions")?;
+        for (puzzle, solution) in &self.pairs {
+            writeln!(
+                writer,
+                "{},{}",
+                puzzle.to_line_string().replace('.', "0"),
+                solution.to_line_string().replace('.', "0")
+            )?;
+        }
+        Ok(())
+    }
+    pub fn push(&mut self, puzzle: Sudoku, solution: Sudoku) {
+        self.pairs.push((puzzle, solution));
+    }
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &(Sudoku, Sudoku)> {
+        self.pairs.iter()
+    }
+}
+
+impl IntoIterator for Dataset {
+    type Item = (Sudoku, Sudoku);
+    type IntoIter = std::vec::IntoIter<(Sudoku, Sudoku)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pairs.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::String;
+
+    use super::*;
+
+    const CSV: &str = "quizzes,solutions
+004300209005009001070060043006002087190007400050083000600000105003508690042910300,864371259325849761971265843436192587198657432257483916689734125713528694542916378
+";
+
+    #[test]
+    fn round_trips_kaggle_csv() {
+        let dataset = Dataset::from_csv(CSV.as_bytes()).unwrap();
+        assert_eq!(dataset.len(), 1);
+        let (puzzle, solution) = &dataset.iter().next().unwrap();
+        assert_eq!(puzzle.solve().as_ref(), Ok(solution));
+        let mut out = Vec::new();
+        dataset.to_csv(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), CSV);
+    }
+
+    #[test]
+    fn reports_bad_rows() {
+        let text = format!("{CSV}\n123,456\n{}\n", &CSV[18..99]);
+        let results = Dataset::stream(text.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[1], Err(ReadError::Parse { line: 4, .. })));
+        assert!(matches!(
+            results[2],
+            Err(ReadError::Parse {
+                line: 5,
+                error: SudokuError::ParseError(ParseError::Invalid("Missing CSV column."))
+            })
+        ));
+    }
+}
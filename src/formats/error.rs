@@ -0,0 +1,31 @@
+use std::io;
+
+use crate::SudokuError;
+
+/// An error from reading puzzles or a saved session line by line.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    /// `line` is 1-based.
+    Parse {
+        line: usize,
+        error: SudokuError,
+    },
+}
+
+impl core::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "{e}"),
+            ReadError::Parse { line, error } => write!(f, "Line {line}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
@@ -0,0 +1,171 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{value_of, ParseError, Size, Sudoku, SudokuError};
+
+#[cfg(feature = "std")]
+mod bank;
+#[cfg(feature = "std")]
+mod dataset;
+#[cfg(feature = "std")]
+mod error;
+#[cfg(feature = "std")]
+mod reader;
+#[cfg(feature = "std")]
+mod sdm;
+mod ss;
+
+#[cfg(feature = "std")]
+pub use bank::{BankPuzzle, SudokuBank};
+#[cfg(feature = "std")]
+pub use dataset::Dataset;
+#[cfg(feature = "std")]
+pub use error::ReadError;
+#[cfg(feature = "std")]
+pub use reader::SudokuReader;
+#[cfg(feature = "std")]
+pub use sdm::SdmCollection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One character per cell on a single line, 81 for a classic board.
+    Line,
+    /// One row per line, optionally with whitespace and `|`, `-`, `+` borders.
+    Grid,
+    /// SadMan Sudoku `.sdk`: `#` metadata lines and an optional `[Puzzle]` section.
+    Sdk,
+    /// Simple Sudoku `.ss`: rows between `*---*` borders, `X` or `.` for empty
+    /// cells, and optionally every cell's candidates.
+    Ss,
+}
+
+pub fn detect(input: &str) -> Format {
+    let input = input.trim();
+    if input.starts_with('*') {
+        Format::Ss
+    } else if input
+        .lines()
+        .any(|l| l.trim_start().starts_with('#') || l.trim_start().starts_with('['))
+    {
+        Format::Sdk
+    } else if input.lines().count() == 1 {
+        Format::Line
+    } else {
+        Format::Grid
+    }
+}
+
+pub fn parse(input: &str) -> Result<Sudoku, SudokuError> {
+    parse_as(input, detect(input))
+}
+
+pub fn parse_as(input: &str, format: Format) -> Result<Sudoku, SudokuError> {
+    match format {
+        Format::Line | Format::Grid => parse_cells(input),
+        Format::Sdk => parse_cells(&sdk_puzzle(input)),
+        Format::Ss => ss::parse_ss(input),
+    }
+}
+
+// Keeps the lines of the `[Puzzle]` section, or every non-metadata line before
+// the first section if there is no such header.
+fn sdk_puzzle(input: &str) -> String {
+    let mut in_puzzle = !input.lines().any(|l| l.trim() == "[Puzzle]");
+    let mut out = String::new();
+    for line in input.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_puzzle = line == "[Puzzle]";
+            continue;
+        }
+        if in_puzzle {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn parse_cells(input: &str) -> Result<Sudoku, SudokuError> {
+    let mut values = Vec::with_capacity(81);
+    for (i, c) in input.chars().enumerate() {
+        match c {
+            c if value_of(c).is_some() => values.push((i, value_of(c))),
+            '.' | '0' | '_' | '*' => values.push((i, None)),
+            '|' | '-' | '+' => {}
+            c if c.is_whitespace() => {}
+            _ => return Err(ParseError::at(input, i).into()),
+        }
+    }
+    let size = Size::from_cell_count(values.len()).ok_or(ParseError::WrongLength {
+        cells: values.len(),
+    })?;
+    if let Some(&(i, _)) = values
+        .iter()
+        .find(|(_, v)| v.is_some_and(|v| !size.is_valid_value(v)))
+    {
+        return Err(ParseError::at(input, i).into());
+    }
+    Ok(Sudoku::from_values(
+        size,
+        values.into_iter().map(|(_, v)| v),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const LINE: &str =
+        ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+
+    #[test]
+    fn parses_zeros_as_empty() {
+        let zeros = LINE.replace('.', "0");
+        assert_eq!(detect(&zeros), Format::Line);
+        assert_eq!(parse(&zeros).unwrap(), Sudoku::from_str(LINE).unwrap());
+    }
+
+    #[test]
+    fn parses_bordered_grid() {
+        let grid = Sudoku::from_str(LINE).unwrap().to_string();
+        assert_eq!(detect(&grid), Format::Grid);
+        assert_eq!(parse(&grid).unwrap(), Sudoku::from_str(LINE).unwrap());
+        let spaced = "\
+            . 5 . | . 8 3 | . 1 7\n. . . | 1 . . | 4 . .\n3 . 4 | . . 5 | 6 . 8\n\
+            ------+-------+------\n\
+            . . . | . 3 . | . . 9\n. 9 . | 8 2 4 | 5 . .\n. . 6 | . . . | . 7 .\n\
+            ------+-------+------\n\
+            . . 9 | . . . | . 5 .\n. . 7 | 2 9 . | . 8 6\n1 . 3 | 6 . 7 | 2 . 4\n";
+        assert_eq!(parse(spaced).unwrap(), Sudoku::from_str(LINE).unwrap());
+    }
+
+    #[test]
+    fn parses_sdk() {
+        let sdk = "#Aanonymous\n#Dexample\n[Puzzle]\n.5..83.17\n...1..4..\n3.4..56.8\n\
+            ....3...9\n.9.8245..\n..6....7.\n..9....5.\n..729..86\n1.36.72.4\n\
+            [State]\n2........\n";
+        assert_eq!(detect(sdk), Format::Sdk);
+        assert_eq!(parse(sdk).unwrap(), Sudoku::from_str(LINE).unwrap());
+    }
+
+    #[test]
+    fn rejects_wrong_size_and_garbage() {
+        assert_eq!(
+            parse(&LINE[1..]),
+            Err(ParseError::WrongLength { cells: 80 }.into())
+        );
+        assert!(matches!(
+            parse(&LINE.replace('.', "x")),
+            Err(SudokuError::ParseError(ParseError::InvalidCharacter {
+                character: 'x',
+                index: 0,
+                ..
+            }))
+        ));
+    }
+}
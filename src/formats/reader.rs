@@ -0,0 +1,101 @@
+use std::io::BufRead;
+use std::string::String;
+
+use super::{parse_as, Format, ReadError};
+use crate::Sudoku;
+
+/// Reads puzzles one line at a time, reusing a single buffer, so files of
+/// any length can be processed without loading them whole. Blank lines are
+/// skipped.
+#[derive(Debug)]
+pub struct SudokuReader<R> {
+    reader: R,
+    buf: String,
+    line: usize,
+    csv: Option<usize>,
+}
+
+impl<R: BufRead> SudokuReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: String::new(),
+            line: 0,
+            csv: None,
+        }
+    }
+    /// Treats lines as comma separated and reads the puzzle from `column`,
+    /// counting from 0. Other columns are ignored, and a first line that
+    /// isn't a puzzle is taken to be a header and skipped.
+    pub fn csv(mut self, column: usize) -> Self {
+        self.csv = Some(column);
+        self
+    }
+}
+
+impl<R: BufRead> Iterator for SudokuReader<R> {
+    type Item = Result<Sudoku, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buf.clear();
+            match self.reader.read_line(&mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(e) => return Some(Err(e.into())),
+            }
+            let mut text = self.buf.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if let Some(column) = self.csv {
+                text = text.split(',').nth(column).unwrap_or("").trim();
+            }
+            let parsed = parse_as(text, Format::Line);
+            if parsed.is_err() && self.csv.is_some() && self.line == 1 {
+                continue;
+            }
+            return Some(parsed.map_err(|error| ReadError::Parse {
+                line: self.line,
+                error,
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str =
+        ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+
+    #[test]
+    fn reads_lines_lazily() {
+        let text = format!("{LINE}\n\n{LINE}\nnot a puzzle\n");
+        let mut reader = SudokuReader::new(text.as_bytes());
+        assert_eq!(reader.next().unwrap().unwrap().to_line_string(), LINE);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(ReadError::Parse { line: 4, .. }))
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn reads_csv_columns() {
+        let zeros = LINE.replace('.', "0");
+        let text = format!("id,quizzes,solutions\n1,{zeros},x\n2,{LINE},y\n");
+        let puzzles = SudokuReader::new(text.as_bytes())
+            .csv(1)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0], puzzles[1]);
+        let bad = SudokuReader::new(text.as_bytes())
+            .csv(2)
+            .collect::<Vec<_>>();
+        assert!(matches!(bad[0], Err(ReadError::Parse { line: 2, .. })));
+    }
+}
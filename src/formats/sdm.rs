@@ -0,0 +1,94 @@
+use std::io::{self, BufRead, Write};
+
+use super::{parse_as, Format, ReadError};
+use crate::Sudoku;
+
+/// A `.sdm` puzzle collection: one 81-character puzzle per line.
+#[derive(Debug, Default)]
+pub struct SdmCollection {
+    puzzles: Vec<Sudoku>,
+}
+
+impl SdmCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Parses puzzles one line at a time. Blank lines are skipped.
+    pub fn stream<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Sudoku, ReadError>> {
+        reader
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(l) if l.trim().is_empty()))
+            .map(|(i, line)| {
+                parse_as(line?.trim(), Format::Line)
+                    .map_err(|error| ReadError::Parse { line: i + 1, error })
+            })
+    }
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, ReadError> {
+        Ok(Self {
+            puzzles: Self::stream(reader).collect::<Result<_, _>>()?,
+        })
+    }
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for puzzle in &self.puzzles {
+            writeln!(writer, "{}", puzzle.to_line_string())?;
+        }
+        Ok(())
+    }
+    pub fn push(&mut self, puzzle: Sudoku) {
+        self.puzzles.push(puzzle);
+    }
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Sudoku> {
+        self.puzzles.iter()
+    }
+}
+
+impl From<Vec<Sudoku>> for SdmCollection {
+    fn from(puzzles: Vec<Sudoku>) -> Self {
+        Self { puzzles }
+    }
+}
+
+impl IntoIterator for SdmCollection {
+    type Item = Sudoku;
+    type IntoIter = std::vec::IntoIter<Sudoku>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.puzzles.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "\
+.5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4
+
+8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..
+";
+
+    #[test]
+    fn round_trips_collection() {
+        let collection = SdmCollection::read(FILE.as_bytes()).unwrap();
+        assert_eq!(collection.len(), 2);
+        let mut out = Vec::new();
+        collection.write(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), FILE.replace("\n\n", "\n"));
+    }
+
+    #[test]
+    fn reports_line_numbers() {
+        let text = format!("{FILE}12345\n");
+        let results = SdmCollection::stream(text.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_ok());
+        assert!(matches!(results[2], Err(ReadError::Parse { line: 4, .. })));
+    }
+}
@@ -0,0 +1,160 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use super::parse_cells;
+use crate::{symbol, Candidates, ParseError, Size, Sudoku, SudokuError};
+
+// Simple Sudoku `.ss` files draw the board between `*-----------*` lines with
+// `|` between boxes, using `.` or `X` for empty cells. Files saved with
+// candidates instead give every cell a whitespace separated token: a single
+// value, or all of its remaining candidates.
+pub(super) fn parse_ss(input: &str) -> Result<Sudoku, SudokuError> {
+    let border = |l: &str| l.trim().chars().all(|c| "*-+|".contains(c));
+    let rows = input
+        .lines()
+        .filter(|l| !l.trim().is_empty() && !border(l))
+        .collect::<Vec<_>>();
+    let tokens = |row: &str| row.replace('|', " ").split_whitespace().count();
+    if rows.len() != 9 || rows.iter().any(|&r| tokens(r) != 9) {
+        // Blanks out the borders and `X`s character for character, so errors
+        // point into the original input.
+        let mut plain = String::with_capacity(input.len());
+        for line in input.split_inclusive('\n') {
+            if border(line) {
+                plain.extend(line.chars().map(|c| if c == '\n' { c } else { ' ' }));
+            } else {
+                plain.extend(
+                    line.chars()
+                        .map(|c| if c == 'X' || c == 'x' { '.' } else { c }),
+                );
+            }
+        }
+        return parse_cells(&plain);
+    }
+
+    let mut values = Vec::with_capacity(81);
+    let mut marks = Vec::with_capacity(81);
+    for row in rows {
+        for token in row.replace('|', " ").split_whitespace() {
+            let mut set = Candidates::empty();
+            for c in token.chars() {
+                match c.to_digit(10) {
+                    Some(d @ 1..=9) => set.insert(d as u8),
+                    _ if "0.Xx".contains(c) => {}
+                    _ => {
+                        let i = input.chars().position(|c| {
+                            !(c.is_ascii_digit() || c.is_whitespace() || "*-+|.Xx".contains(c))
+                        });
+                        return Err(ParseError::at(input, i.unwrap_or(0)).into());
+                    }
+                }
+            }
+            values.push(if token.len() == 1 { set.single() } else { None });
+            marks.push(set);
+        }
+    }
+    let mut sudoku = Sudoku::from_values(Size::CLASSIC, values);
+    for (cell, marks) in sudoku.cells.iter_mut().zip(marks) {
+        if cell.value.is_none() {
+            cell.pencil_marks = marks;
+        }
+    }
+    Ok(sudoku)
+}
+
+impl Sudoku {
+    /// The board in Simple Sudoku's `.ss` layout. If any empty cell has
+    /// pencil marks, every cell is written as its value or its candidates;
+    /// like Simple Sudoku itself, a cell with one candidate then reads back
+    /// as that value.
+    pub fn to_ss_string(&self) -> String {
+        let side = self.size().side() as usize;
+        let n = self.size().box_size() as usize;
+        let with_marks = self
+            .iter()
+            .any(|c| c.value.is_none() && !c.pencil_marks.is_empty());
+        let token = |i: usize| -> String {
+            let cell = &self.cells[i];
+            match cell.value {
+                Some(v) => symbol(v).into(),
+                None if with_marks && !cell.pencil_marks.is_empty() => {
+                    cell.pencil_marks.iter().map(symbol).collect()
+                }
+                None => ".".into(),
+            }
+        };
+        let width = if with_marks {
+            (0..self.cells.len())
+                .map(|i| token(i).len())
+                .max()
+                .unwrap_or(1)
+        } else {
+            1
+        };
+        // Candidate cells are padded to `width`, two spaces apart, with a
+        // space inside each box edge.
+        let stack = n * width + if with_marks { 2 * n } else { 0 };
+        let dashes = "-".repeat(stack);
+        let full = (0..n).map(|_| dashes.as_str()).collect::<Vec<_>>();
+        let mut out = String::new();
+        let _ = writeln!(out, "*{}*", full.join("-"));
+        for y in 0..side {
+            if y > 0 && y % n == 0 {
+                let _ = writeln!(out, "|{}|", full.join("+"));
+            }
+            for x in 0..side {
+                out.push_str(if x % n == 0 { "|" } else { "" });
+                if with_marks {
+                    out.push_str(if x % n == 0 { " " } else { "  " });
+                }
+                let _ = write!(out, "{:<width$}", token(y * side + x));
+                if with_marks && x % n == n - 1 {
+                    out.push(' ');
+                }
+            }
+            out.push_str("|\n");
+        }
+        let _ = writeln!(out, "*{}*", full.join("-"));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::super::{detect, parse, Format};
+    use super::*;
+
+    const LINE: &str =
+        ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+
+    #[test]
+    fn round_trips_plain_boards() {
+        let s = Sudoku::from_str(LINE).unwrap();
+        let ss = s.to_ss_string();
+        assert!(ss.starts_with("*-----------*\n|.5.|.83|.17|\n"));
+        assert!(ss.contains("\n|---+---+---|\n"));
+        assert_eq!(detect(&ss), Format::Ss);
+        assert_eq!(parse(&ss).unwrap(), s);
+        assert_eq!(parse(&ss.replace('.', "X")).unwrap(), s);
+    }
+
+    #[test]
+    fn round_trips_candidates() {
+        let mut s = Sudoku::from_str(LINE).unwrap();
+        s.fill_all_candidates();
+        let ss = s.to_ss_string();
+        assert!(ss
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("| 269    5      2     | 49 "));
+        let parsed = parse(&ss).unwrap();
+        assert_eq!(parsed[(0, 0)].pencil_marks(), s[(0, 0)].pencil_marks());
+        // r1c3 has 2 as its only candidate.
+        assert_eq!(parsed[(2, 0)].value(), Some(2));
+        assert_eq!(parsed.to_ss_string(), ss);
+    }
+}
@@ -0,0 +1,335 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use super::Move;
+use crate::{CandidateMode, Candidates, ParseError, Pos, Sudoku, SudokuError};
+
+/// When a move was made: a Lamport clock plus the player, so every move has
+/// its own stamp and all players put them in the same order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stamp {
+    pub clock: u64,
+    pub player: u32,
+}
+
+/// A move as it travels between players. Prints and parses as
+/// `clock@player move`, like `12@3 r4c7=5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StampedMove {
+    pub stamp: Stamp,
+    pub action: Move,
+}
+
+impl fmt::Display for StampedMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}@{} {}",
+            self.stamp.clock, self.stamp.player, self.action
+        )
+    }
+}
+
+impl FromStr for StampedMove {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SudokuError::ParseError(ParseError::Invalid("Invalid stamped move."));
+        let (stamp, action) = s.trim().split_once(' ').ok_or_else(invalid)?;
+        let (clock, player) = stamp.split_once('@').ok_or_else(invalid)?;
+        Ok(Self {
+            stamp: Stamp {
+                clock: clock.parse().map_err(|_| invalid())?,
+                player: player.parse().map_err(|_| invalid())?,
+            },
+            action: action.parse()?,
+        })
+    }
+}
+
+/// One player's copy of a co-op game. Each cell holds two last-writer-wins
+/// registers, one for its entry and one for its pencil marks, so copies that
+/// have seen the same moves show the same board whatever order the moves
+/// came in. Givens never change.
+///
+/// Pencil marks only change through moves: pruning them when a value is
+/// placed would depend on the order moves arrive.
+#[derive(Debug, Clone)]
+pub struct CoopBoard {
+    player: u32,
+    clock: u64,
+    board: Sudoku,
+    entries: Vec<Option<(Stamp, Option<u8>)>>,
+    marks: Vec<Option<(Stamp, Candidates)>>,
+}
+
+impl CoopBoard {
+    /// Every player starts from the same puzzle; `player` must be different
+    /// for each of them.
+    pub fn new(puzzle: Sudoku, player: u32) -> Self {
+        let cells = puzzle.size().cell_count();
+        Self {
+            player,
+            clock: 0,
+            board: puzzle.with_candidate_mode(CandidateMode::Manual),
+            entries: vec![None; cells],
+            marks: vec![None; cells],
+        }
+    }
+    pub fn board(&self) -> &Sudoku {
+        &self.board
+    }
+    pub fn player(&self) -> u32 {
+        self.player
+    }
+
+    /// Makes a move on this copy and returns it stamped, to send to the
+    /// other players. A toggle goes out as the cell's new set of
+    /// candidates, so it means the same wherever it lands.
+    pub fn play(&mut self, m: Move) -> Result<StampedMove, SudokuError> {
+        let action = match m {
+            Move::ToggleCandidate { pos, value } => {
+                check_cell(&self.board, pos, Some(value))?;
+                let mut values = self.board[pos].pencil_marks();
+                if values.contains(value) {
+                    values.remove(value);
+                } else {
+                    values.insert(value);
+                }
+                Move::SetCandidates { pos, values }
+            }
+            m => m,
+        };
+        check_action(&self.board, action)?;
+        self.clock += 1;
+        let stamped = StampedMove {
+            stamp: Stamp {
+                clock: self.clock,
+                player: self.player,
+            },
+            action,
+        };
+        self.receive(stamped);
+        Ok(stamped)
+    }
+    /// Applies moves from other players. Moves can come in any order and
+    /// more than once; ones that touch givens or don't fit the board are
+    /// skipped. Returns how many changed the board.
+    pub fn merge(&mut self, remote: impl IntoIterator<Item = StampedMove>) -> usize {
+        let mut changed = 0;
+        for m in remote {
+            if check_action(&self.board, m.action).is_err() {
+                continue;
+            }
+            self.clock = self.clock.max(m.stamp.clock);
+            if self.receive(m) {
+                changed += 1;
+            }
+        }
+        changed
+    }
+    /// The move behind every cell's current entry and pencil marks, oldest
+    /// first. Merging them brings a new player up to date.
+    pub fn moves(&self) -> Vec<StampedMove> {
+        let size = self.board.size();
+        let entries = self.entries.iter().enumerate().filter_map(|(i, e)| {
+            let (stamp, value) = (*e)?;
+            let pos = size.pos_at(i);
+            let action = match value {
+                Some(value) => Move::Place { pos, value },
+                None => Move::Erase { pos },
+            };
+            Some(StampedMove { stamp, action })
+        });
+        let marks = self.marks.iter().enumerate().filter_map(|(i, m)| {
+            let (stamp, values) = (*m)?;
+            let action = Move::SetCandidates {
+                pos: size.pos_at(i),
+                values,
+            };
+            Some(StampedMove { stamp, action })
+        });
+        let mut moves = entries.chain(marks).collect::<Vec<_>>();
+        moves.sort_by_key(|m| m.stamp);
+        moves
+    }
+
+    // Stores a checked move if it is newer than what its register holds,
+    // returning whether it was.
+    fn receive(&mut self, m: StampedMove) -> bool {
+        let pos = m.action.pos();
+        let i = self.board.size().index_of(pos);
+        match m.action {
+            Move::Place { .. } | Move::Erase { .. } => {
+                if self.entries[i].is_some_and(|(stamp, _)| stamp >= m.stamp) {
+                    return false;
+                }
+                let value = match m.action {
+                    Move::Place { value, .. } => Some(value),
+                    _ => None,
+                };
+                self.entries[i] = Some((m.stamp, value));
+            }
+            Move::SetCandidates { values, .. } => {
+                if self.marks[i].is_some_and(|(stamp, _)| stamp >= m.stamp) {
+                    return false;
+                }
+                self.marks[i] = Some((m.stamp, values));
+            }
+            Move::ToggleCandidate { .. } => return false,
+        }
+        self.show(pos);
+        true
+    }
+    // Brings the board's cell in line with its registers.
+    fn show(&mut self, pos: Pos) {
+        let i = self.board.size().index_of(pos);
+        let value = self.entries[i].and_then(|(_, v)| v);
+        let marks = match value {
+            Some(_) => Candidates::empty(),
+            None => self.marks[i].map_or(Candidates::empty(), |(_, m)| m),
+        };
+        self.board
+            .set(pos, value)
+            .and_then(|_| self.board.set_candidates(pos, marks))
+            .expect("moves are checked before they are stored");
+    }
+}
+
+fn check_cell(board: &Sudoku, pos: Pos, value: Option<u8>) -> Result<(), SudokuError> {
+    board.check(pos, value)?;
+    if board[pos].is_given() {
+        return Err(SudokuError::GivenCell);
+    }
+    Ok(())
+}
+
+// Toggles are turned into candidate sets before they are shared, so one
+// that arrives from another player is refused.
+fn check_action(board: &Sudoku, m: Move) -> Result<(), SudokuError> {
+    match m {
+        Move::Place { pos, value } => check_cell(board, pos, Some(value)),
+        Move::Erase { pos } => check_cell(board, pos, None),
+        Move::SetCandidates { pos, values } => {
+            check_cell(board, pos, None)?;
+            match values.iter().find(|&v| !board.size().is_valid_value(v)) {
+                Some(v) => Err(SudokuError::InvalidValue(v)),
+                None => Ok(()),
+            }
+        }
+        Move::ToggleCandidate { value, .. } => Err(SudokuError::InvalidValue(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::rng::Rng;
+
+    fn puzzle() -> Sudoku {
+        Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap()
+    }
+
+    fn random_move(rng: &mut Rng, board: &Sudoku) -> Move {
+        let empty = board
+            .iter()
+            .filter(|c| !c.is_given())
+            .map(|c| c.position())
+            .collect::<Vec<_>>();
+        let pos = empty[rng.below(empty.len())];
+        let value = rng.below(9) as u8 + 1;
+        match rng.below(4) {
+            0 => Move::Erase { pos },
+            1 => Move::ToggleCandidate { pos, value },
+            _ => Move::Place { pos, value },
+        }
+    }
+
+    #[test]
+    fn copies_converge_in_any_order() {
+        let mut rng = Rng::from_seed(4);
+        let mut players = (0..3)
+            .map(|p| CoopBoard::new(puzzle(), p))
+            .collect::<Vec<_>>();
+        let mut sent = Vec::new();
+        for _ in 0..60 {
+            let p = rng.below(3);
+            let m = random_move(&mut rng, players[p].board());
+            sent.push(players[p].play(m).unwrap());
+            // Now and then a player hears about some of what was sent.
+            if rng.below(3) == 0 {
+                let q = rng.below(3);
+                let from = rng.below(sent.len());
+                players[q].merge(sent[from..].iter().copied());
+            }
+        }
+        for player in players.iter_mut() {
+            let mut shuffled = sent.clone();
+            rng.shuffle(&mut shuffled);
+            player.merge(shuffled);
+        }
+        let first = players[0].board().clone();
+        for player in &players {
+            assert_eq!(player.board(), &first);
+            assert_eq!(player.moves(), players[0].moves());
+            for cell in player.board().iter() {
+                assert_eq!(cell.pencil_marks(), first[cell.position()].pencil_marks());
+            }
+        }
+        assert_eq!(players[0].merge(sent.iter().copied()), 0);
+
+        let mut late = CoopBoard::new(puzzle(), 9);
+        late.merge(players[1].moves());
+        assert_eq!(late.board(), &first);
+    }
+
+    #[test]
+    fn later_moves_win_and_givens_stay() {
+        let pos = Pos::new_unchecked(0, 0);
+        let mut a = CoopBoard::new(puzzle(), 1);
+        let mut b = CoopBoard::new(puzzle(), 2);
+        let from_a = a.play(Move::Place { pos, value: 1 }).unwrap();
+        let from_b = b.play(Move::Place { pos, value: 6 }).unwrap();
+        // Same clock, so the higher player wins on both copies.
+        a.merge([from_b]);
+        b.merge([from_a]);
+        assert_eq!(a.board()[pos].value(), Some(6));
+        assert_eq!(b.board()[pos].value(), Some(6));
+        let erase = a.play(Move::Erase { pos }).unwrap();
+        assert!(erase.stamp > from_b.stamp);
+        assert_eq!(b.merge([erase]), 1);
+        assert_eq!(b.board()[pos].value(), None);
+
+        let mark = a.play(Move::ToggleCandidate { pos, value: 2 }).unwrap();
+        assert_eq!(mark.to_string(), "3@1 r1c1:2");
+        assert_eq!(StampedMove::from_str("3@1 r1c1:2"), Ok(mark));
+        b.merge([mark, mark]);
+        assert!(b.board()[pos].pencil_marks().contains(2));
+
+        let given = Pos::new_unchecked(1, 0);
+        assert_eq!(
+            a.play(Move::Place {
+                pos: given,
+                value: 1
+            }),
+            Err(SudokuError::GivenCell)
+        );
+        let forged = StampedMove {
+            stamp: Stamp {
+                clock: 99,
+                player: 2,
+            },
+            action: Move::Erase { pos: given },
+        };
+        assert_eq!(a.merge([forged]), 0);
+        assert_eq!(a.board()[given].value(), Some(5));
+        assert!("r1c1=5".parse::<StampedMove>().is_err());
+    }
+}
@@ -0,0 +1,383 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{CandidateMode, Candidates, ParseError, Pos, Sudoku, SudokuError};
+
+mod coop;
+#[cfg(feature = "std")]
+mod session;
+
+pub use coop::{CoopBoard, Stamp, StampedMove};
+#[cfg(feature = "std")]
+pub use session::{Session, SAVE_VERSION};
+
+/// One thing a player does to the board. Moves print and parse as short
+/// notation, with rows and columns counted from 1: `r4c7=5` places a 5,
+/// `r4c7=.` erases the cell, `r4c7-3` toggles the candidate 3 and
+/// `r4c7:1,3,5` sets the cell's candidates (`r4c7:.` clears them). `^` is
+/// also read as a toggle, as older saves wrote `r4c7^3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Move {
+    Place { pos: Pos, value: u8 },
+    Erase { pos: Pos },
+    SetCandidates { pos: Pos, values: Candidates },
+    ToggleCandidate { pos: Pos, value: u8 },
+}
+
+impl Move {
+    pub fn pos(&self) -> Pos {
+        match *self {
+            Move::Place { pos, .. }
+            | Move::Erase { pos }
+            | Move::SetCandidates { pos, .. }
+            | Move::ToggleCandidate { pos, .. } => pos,
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pos = self.pos();
+        write!(f, "r{}c{}", pos.y() + 1, pos.x() + 1)?;
+        match *self {
+            Move::Place { value, .. } => write!(f, "={value}"),
+            Move::Erase { .. } => f.write_str("=."),
+            Move::SetCandidates { values, .. } if values.is_empty() => f.write_str(":."),
+            Move::SetCandidates { values, .. } => {
+                f.write_str(":")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                Ok(())
+            }
+            Move::ToggleCandidate { value, .. } => write!(f, "-{value}"),
+        }
+    }
+}
+
+/// Reads what `Display` writes. Values are only checked against the board
+/// when the move is applied.
+impl FromStr for Move {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SudokuError::ParseError(ParseError::Invalid("Invalid move."));
+        let s = s.trim();
+        let split = s.find(['=', '-', '^', ':']).ok_or_else(invalid)?;
+        let (cell, rest) = s.split_at(split);
+        let (row, col) = cell
+            .strip_prefix('r')
+            .and_then(|c| c.split_once('c'))
+            .ok_or_else(invalid)?;
+        let index = |n: &str| {
+            n.parse::<u8>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .ok_or_else(invalid)
+        };
+        let pos = Pos::try_new(index(col)?, index(row)?)?;
+        let value = |v: &str| v.parse::<u8>().ok().filter(|&v| v > 0).ok_or_else(invalid);
+        match (&rest[..1], &rest[1..]) {
+            ("=", ".") => Ok(Move::Erase { pos }),
+            ("=", v) => Ok(Move::Place {
+                pos,
+                value: value(v)?,
+            }),
+            (":", ".") => Ok(Move::SetCandidates {
+                pos,
+                values: Candidates::empty(),
+            }),
+            (":", list) => {
+                let mut values = Candidates::empty();
+                for v in list.split(',') {
+                    let v = value(v).ok().filter(|&v| v <= 25).ok_or_else(invalid)?;
+                    values.insert(v);
+                }
+                Ok(Move::SetCandidates { pos, values })
+            }
+            (_, v) => Ok(Move::ToggleCandidate {
+                pos,
+                value: value(v)?,
+            }),
+        }
+    }
+}
+
+// What a cell looked like before a move touched it.
+#[derive(Debug, Clone, Copy)]
+struct Snapshot {
+    pos: Pos,
+    value: Option<u8>,
+    pencil_marks: Candidates,
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    board: Sudoku,
+    history: Vec<Move>,
+    undo: Vec<Vec<Snapshot>>,
+    redo: Vec<Move>,
+}
+
+impl Game {
+    pub fn new(board: Sudoku) -> Self {
+        Self {
+            board,
+            history: Vec::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+    pub fn board(&self) -> &Sudoku {
+        &self.board
+    }
+    pub fn into_board(self) -> Sudoku {
+        self.board
+    }
+    /// Moves applied so far, oldest first. Undone moves are not included.
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+    pub fn apply(&mut self, m: Move) -> Result<(), SudokuError> {
+        self.perform(m)?;
+        self.redo.clear();
+        Ok(())
+    }
+    pub fn undo(&mut self) -> Option<Move> {
+        let snapshots = self.undo.pop()?;
+        self.board.observed(|board| {
+            for s in snapshots.into_iter().rev() {
+                let cell = &mut board[s.pos];
+                cell.value = s.value;
+                cell.pencil_marks = s.pencil_marks;
+            }
+        });
+        let m = self.history.pop()?;
+        self.redo.push(m);
+        Some(m)
+    }
+    pub fn redo(&mut self) -> Option<Move> {
+        let m = self.redo.pop()?;
+        // The move was valid when first applied and the board is back in the
+        // same state, so it can't fail now.
+        self.perform(m).ok()?;
+        Some(m)
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    fn perform(&mut self, m: Move) -> Result<(), SudokuError> {
+        let touched = match m {
+            // AutoFill rewrites every empty cell's marks after a change of
+            // value.
+            Move::Place { .. } | Move::Erase { .. }
+                if self.board.candidate_mode() == CandidateMode::AutoFill =>
+            {
+                self.board.size().positions().collect()
+            }
+            Move::Place { pos, .. } => {
+                let mut cells = vec![pos];
+                let (size, rules) = (self.board.size(), self.board.rules());
+                cells.extend(
+                    self.board
+                        .houses_containing(pos)
+                        .into_iter()
+                        .flat_map(|h| rules.house_positions(size, h)),
+                );
+                cells.extend(rules.links(size, pos));
+                cells
+            }
+            Move::Erase { pos }
+            | Move::SetCandidates { pos, .. }
+            | Move::ToggleCandidate { pos, .. } => vec![pos],
+        };
+        let snapshots = touched
+            .into_iter()
+            .map(|pos| Snapshot {
+                pos,
+                value: self.board[pos].value(),
+                pencil_marks: self.board[pos].pencil_marks(),
+            })
+            .collect();
+        match m {
+            Move::Place { pos, value } => self.board.set(pos, Some(value))?,
+            Move::Erase { pos } => self.board.set(pos, None)?,
+            Move::SetCandidates { pos, values } => self.board.set_candidates(pos, values)?,
+            Move::ToggleCandidate { pos, value } => self.board.toggle_candidate(pos, value)?,
+        }
+        self.history.push(m);
+        self.undo.push(snapshots);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{Rules, Size};
+
+    fn game() -> Game {
+        Game::new(
+            Sudoku::from_str(
+                ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn undo_restores_pruned_pencil_marks() {
+        let mut g = game();
+        let pos = Pos::new_unchecked(0, 0);
+        let peer = Pos::new_unchecked(0, 1);
+        g.apply(Move::ToggleCandidate {
+            pos: peer,
+            value: 9,
+        })
+        .unwrap();
+        g.apply(Move::Place { pos, value: 9 }).unwrap();
+        assert!(!g.board()[peer].pencil_marks().contains(9));
+        assert_eq!(g.undo(), Some(Move::Place { pos, value: 9 }));
+        assert_eq!(g.board()[pos].value(), None);
+        assert!(g.board()[peer].pencil_marks().contains(9));
+        assert_eq!(g.history().len(), 1);
+    }
+
+    #[test]
+    fn undo_restores_marks_pruned_by_links() {
+        let rules = Rules {
+            anti_knight: true,
+            ..Rules::CLASSIC
+        };
+        let mut g = Game::new(Sudoku::new(Size::CLASSIC).with_rules(rules));
+        let (pos, knight) = (Pos::new_unchecked(2, 2), Pos::new_unchecked(4, 3));
+        g.apply(Move::ToggleCandidate {
+            pos: knight,
+            value: 5,
+        })
+        .unwrap();
+        g.apply(Move::Place { pos, value: 5 }).unwrap();
+        assert!(!g.board()[knight].pencil_marks().contains(5));
+        g.undo();
+        assert!(g.board()[knight].pencil_marks().contains(5));
+    }
+
+    #[test]
+    fn undo_restores_autofilled_marks() {
+        let board = game().board().clone();
+        let mut g = Game::new(board.with_candidate_mode(CandidateMode::AutoFill));
+        let marks = |g: &Game| {
+            g.board()
+                .iter()
+                .map(|c| c.pencil_marks())
+                .collect::<Vec<_>>()
+        };
+        let (pos, far) = (Pos::new_unchecked(0, 0), Pos::new_unchecked(7, 8));
+        g.apply(Move::ToggleCandidate { pos: far, value: 5 })
+            .unwrap();
+        let before = marks(&g);
+        g.apply(Move::Place { pos, value: 9 }).unwrap();
+        let placed = marks(&g);
+        g.apply(Move::Erase { pos }).unwrap();
+        g.undo();
+        assert_eq!(g.board()[pos].value(), Some(9));
+        assert_eq!(marks(&g), placed);
+        g.undo();
+        assert_eq!(marks(&g), before);
+    }
+
+    #[test]
+    fn redo_replays_and_new_moves_clear_it() {
+        let mut g = game();
+        let pos = Pos::new_unchecked(0, 0);
+        g.apply(Move::Place { pos, value: 9 }).unwrap();
+        g.apply(Move::Erase { pos }).unwrap();
+        g.undo();
+        g.undo();
+        assert!(!g.can_undo());
+        assert_eq!(g.redo(), Some(Move::Place { pos, value: 9 }));
+        assert_eq!(g.board()[pos].value(), Some(9));
+        g.apply(Move::Place { pos, value: 6 }).unwrap();
+        assert!(!g.can_redo());
+        assert_eq!(g.redo(), None);
+    }
+
+    #[test]
+    fn invalid_moves_are_not_recorded() {
+        let mut g = game();
+        let given = Pos::new_unchecked(1, 0);
+        assert_eq!(
+            g.apply(Move::Place {
+                pos: given,
+                value: 2
+            }),
+            Err(SudokuError::GivenCell)
+        );
+        assert!(g.history().is_empty());
+        assert_eq!(g.undo(), None);
+    }
+
+    #[test]
+    fn moves_round_trip_through_notation() {
+        let pos = Pos::new_unchecked(6, 3);
+        let mut values = Candidates::empty();
+        for v in [1, 3, 5] {
+            values.insert(v);
+        }
+        for (m, text) in [
+            (Move::Place { pos, value: 5 }, "r4c7=5"),
+            (Move::Erase { pos }, "r4c7=."),
+            (Move::ToggleCandidate { pos, value: 3 }, "r4c7-3"),
+            (Move::SetCandidates { pos, values }, "r4c7:1,3,5"),
+            (
+                Move::SetCandidates {
+                    pos,
+                    values: Candidates::empty(),
+                },
+                "r4c7:.",
+            ),
+        ] {
+            assert_eq!(m.to_string(), text);
+            assert_eq!(text.parse::<Move>(), Ok(m));
+        }
+        for bad in [
+            "r0c1=5",
+            "r1c1",
+            "r1c1=x",
+            "r1c1=0",
+            "c1r1=5",
+            "r1c1:1,,2",
+            "r1c1^",
+            "r1c1-",
+            "r1c1--3",
+        ] {
+            assert!(bad.parse::<Move>().is_err(), "{bad}");
+        }
+        assert_eq!(
+            "r4c7^3".parse::<Move>(),
+            Ok(Move::ToggleCandidate { pos, value: 3 })
+        );
+
+        let mut g = game();
+        let cell = Pos::new_unchecked(0, 0);
+        g.apply("r1c1:1,9".parse().unwrap()).unwrap();
+        assert_eq!(g.board()[cell].pencil_marks().count(), 2);
+        g.undo();
+        assert!(g.board()[cell].pencil_marks().is_empty());
+        assert_eq!(
+            g.apply("r1c1:1,12".parse().unwrap()),
+            Err(SudokuError::InvalidValue(12))
+        );
+    }
+}
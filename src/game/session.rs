@@ -0,0 +1,466 @@
+use std::io::{self, BufRead, Write};
+use std::string::String;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use super::{Game, Move};
+use crate::formats::ReadError;
+use crate::{
+    CandidateMode, Candidates, Difficulty, Hint, ParseError, RegionLayout, Rules, Size, SolveError,
+    Sudoku, SudokuError,
+};
+
+/// The newest save format version `Session::load` understands.
+pub const SAVE_VERSION: u32 = 1;
+
+/// A game being played: the move history plus the clock, mistakes and hints
+/// that go into the final score. The clock starts running right away.
+#[derive(Debug)]
+pub struct Session {
+    game: Game,
+    solution: Sudoku,
+    difficulty: Difficulty,
+    mistakes: usize,
+    hints_used: usize,
+    // Time banked by earlier pauses, plus when the clock last started if it
+    // is running.
+    elapsed: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Session {
+    /// Fails if the puzzle can't be solved; a puzzle with several solutions
+    /// is checked against the first one found.
+    pub fn new(puzzle: Sudoku) -> Result<Self, SolveError> {
+        let solution = puzzle.solve()?;
+        Ok(Self {
+            difficulty: puzzle.grade(),
+            game: Game::new(puzzle),
+            solution,
+            mistakes: 0,
+            hints_used: 0,
+            elapsed: Duration::ZERO,
+            running_since: Some(Instant::now()),
+        })
+    }
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+    pub fn board(&self) -> &Sudoku {
+        self.game.board()
+    }
+    pub fn solution(&self) -> &Sudoku {
+        &self.solution
+    }
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+    pub fn mistakes(&self) -> usize {
+        self.mistakes
+    }
+    pub fn hints_used(&self) -> usize {
+        self.hints_used
+    }
+    /// Applies the move, counting a mistake if it places a value the
+    /// solution doesn't have there. Returns false for such mistakes.
+    pub fn play(&mut self, m: Move) -> Result<bool, SudokuError> {
+        self.game.apply(m)?;
+        let correct = match m {
+            Move::Place { pos, value } => self.solution[pos].value() == Some(value),
+            _ => true,
+        };
+        if !correct {
+            self.mistakes += 1;
+        }
+        Ok(correct)
+    }
+    pub fn undo(&mut self) -> Option<Move> {
+        self.game.undo()
+    }
+    pub fn redo(&mut self) -> Option<Move> {
+        self.game.redo()
+    }
+    /// The next logical step, counted against the score.
+    pub fn hint(&mut self) -> Option<Hint> {
+        let hint = self.board().hint()?;
+        self.hints_used += 1;
+        Some(hint)
+    }
+    pub fn is_complete(&self) -> bool {
+        *self.board() == self.solution
+    }
+    pub fn pause(&mut self) {
+        if let Some(start) = self.running_since.take() {
+            self.elapsed += start.elapsed();
+        }
+    }
+    pub fn resume(&mut self) {
+        self.running_since.get_or_insert_with(Instant::now);
+    }
+    pub fn is_paused(&self) -> bool {
+        self.running_since.is_none()
+    }
+    /// Time played, not counting pauses.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed + self.running_since.map_or(Duration::ZERO, |s| s.elapsed())
+    }
+    /// Starts from 1000 points per difficulty level (1000 for easy up to
+    /// 4000 for expert) and loses 100 per mistake, 50 per hint and 1 per
+    /// second played, down to a tenth of the starting points.
+    pub fn score(&self) -> u32 {
+        let base = 1000 * (self.difficulty as u32 + 1);
+        let penalty =
+            100 * self.mistakes as u64 + 50 * self.hints_used as u64 + self.elapsed().as_secs();
+        (u64::from(base).saturating_sub(penalty) as u32).max(base / 10)
+    }
+}
+
+// Saves are `key value` lines after a `sudoku-session VERSION` header.
+// Unknown keys are skipped, so later versions can add fields that older
+// readers ignore. The board is stored as it was before the first move, and
+// loading replays the history on top of it so undo keeps working.
+impl Session {
+    pub fn save<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let mut start = self.game.clone();
+        while start.undo().is_some() {}
+        let board = start.board();
+        let cells =
+            |f: &dyn Fn(&crate::Cell) -> String| board.iter().map(f).collect::<Vec<_>>().join(" ");
+        let value = |c: &crate::Cell, given| match c.value() {
+            Some(v) if c.is_given() == given => v.to_string(),
+            _ => "0".to_string(),
+        };
+        writeln!(w, "sudoku-session {SAVE_VERSION}")?;
+        writeln!(w, "size {}", board.size().box_size())?;
+        writeln!(w, "rules {}", board.rules())?;
+        writeln!(w, "candidate_mode {:?}", board.candidate_mode())?;
+        if let Some(layout) = board.rules().regions {
+            let map = layout.to_string().split_whitespace().collect::<String>();
+            writeln!(w, "regions {map}")?;
+        }
+        writeln!(w, "givens {}", cells(&|c| value(c, true)))?;
+        writeln!(w, "entries {}", cells(&|c| value(c, false)))?;
+        writeln!(
+            w,
+            "candidates {}",
+            cells(&|c| format!("{:x}", c.pencil_marks().bits()))
+        )?;
+        writeln!(w, "history {}", moves(self.game.history()))?;
+        // Next redo first, the order they are replayed in.
+        let redo = self.game.redo.iter().rev().copied().collect::<Vec<_>>();
+        writeln!(w, "redo {}", moves(&redo))?;
+        writeln!(w, "difficulty {:?}", self.difficulty)?;
+        writeln!(w, "mistakes {}", self.mistakes)?;
+        writeln!(w, "hints {}", self.hints_used)?;
+        writeln!(w, "elapsed_ms {}", self.elapsed().as_millis())
+    }
+
+    /// Restores a saved session. Its clock starts paused.
+    pub fn load<R: BufRead>(reader: R) -> Result<Self, ReadError> {
+        let mut fields = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let (key, value) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+            fields.push((i + 1, key.to_string(), value.trim().to_string()));
+        }
+        let fail = |line, msg| ReadError::Parse {
+            line,
+            error: SudokuError::ParseError(ParseError::Invalid(msg)),
+        };
+        match fields.first() {
+            Some((_, key, v)) if key == "sudoku-session" => match v.parse::<u32>() {
+                Ok(version) if version <= SAVE_VERSION => {}
+                _ => return Err(fail(1, "Unsupported save version.")),
+            },
+            _ => return Err(fail(1, "Not a saved session.")),
+        }
+        let get = |key: &str| {
+            fields
+                .iter()
+                .find(|(_, k, _)| k == key)
+                .map(|(line, _, v)| (*line, v.as_str()))
+                .ok_or_else(|| fail(fields.len(), "Missing field in saved session."))
+        };
+        let number = |key: &str| -> Result<u64, ReadError> {
+            let (line, v) = get(key)?;
+            v.parse().map_err(|_| fail(line, "Invalid number."))
+        };
+
+        let (line, size) = get("size")?;
+        let size = size
+            .parse()
+            .ok()
+            .and_then(|n| Size::new(n).ok())
+            .ok_or_else(|| fail(line, "Invalid board size."))?;
+        let (line, rules) = get("rules")?;
+        let rules = rules
+            .parse::<Rules>()
+            .map_err(|error| ReadError::Parse { line, error })?;
+        let mut board = Sudoku::new(size).with_rules(rules);
+        // Only jigsaw saves have regions.
+        if let Some((line, _, map)) = fields.iter().find(|(_, k, _)| k == "regions") {
+            let line = *line;
+            board = map
+                .parse::<RegionLayout>()
+                .and_then(|layout| board.with_regions(layout))
+                .map_err(|error| ReadError::Parse { line, error })?;
+        }
+        // Saves from before modes were kept used the default. Set directly,
+        // as switching to AutoFill would overwrite the saved marks.
+        if let Some((line, _, mode)) = fields.iter().find(|(_, k, _)| k == "candidate_mode") {
+            let line = *line;
+            board.candidate_mode = mode
+                .parse::<CandidateMode>()
+                .map_err(|error| ReadError::Parse { line, error })?;
+        }
+        for (key, given) in [("givens", true), ("entries", false)] {
+            let (line, values) = get(key)?;
+            let values = per_cell(values, size, |t| t.parse::<u8>().ok())
+                .ok_or_else(|| fail(line, "Invalid cell values."))?;
+            if let Some(&v) = values.iter().find(|&&v| v != 0 && !size.is_valid_value(v)) {
+                let error = SudokuError::InvalidValue(v);
+                return Err(ReadError::Parse { line, error });
+            }
+            for (cell, v) in board.cells.iter_mut().zip(values) {
+                if v != 0 {
+                    *cell = crate::Cell::try_new(Some(v), cell.position)
+                        .map_err(|error| ReadError::Parse { line, error })?;
+                    cell.given = given;
+                }
+            }
+        }
+        board.rebuild_masks();
+        let (line, marks) = get("candidates")?;
+        let marks = per_cell(marks, size, |t| u32::from_str_radix(t, 16).ok())
+            .ok_or_else(|| fail(line, "Invalid candidates."))?;
+        for (cell, bits) in board.cells.iter_mut().zip(marks) {
+            cell.pencil_marks = Candidates::from_bits(bits);
+        }
+
+        let solution = board
+            .solve()
+            .map_err(|_| fail(1, "Saved puzzle has no solution."))?;
+        let mut game = Game::new(board);
+        for key in ["history", "redo"] {
+            let (line, text) = get(key)?;
+            for token in text.split_whitespace() {
+                let m = token
+                    .parse::<Move>()
+                    .map_err(|error| ReadError::Parse { line, error })?;
+                game.apply(m)
+                    .map_err(|error| ReadError::Parse { line, error })?;
+            }
+        }
+        let (_, redo) = get("redo")?;
+        for _ in redo.split_whitespace() {
+            game.undo();
+        }
+        let (line, difficulty) = get("difficulty")?;
+        Ok(Self {
+            difficulty: difficulty
+                .parse()
+                .map_err(|error| ReadError::Parse { line, error })?,
+            game,
+            solution,
+            mistakes: number("mistakes")? as usize,
+            hints_used: number("hints")? as usize,
+            elapsed: Duration::from_millis(number("elapsed_ms")?),
+            running_since: None,
+        })
+    }
+}
+
+fn per_cell<T>(text: &str, size: Size, parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    let values = text
+        .split_whitespace()
+        .map(parse)
+        .collect::<Option<Vec<_>>>()?;
+    (values.len() == size.cell_count()).then_some(values)
+}
+
+fn moves(moves: &[Move]) -> String {
+    moves
+        .iter()
+        .map(Move::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::Pos;
+
+    fn session() -> Session {
+        Session::new(
+            Sudoku::from_str(
+                ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn counts_mistakes_and_hints() {
+        let mut s = session();
+        s.pause();
+        assert_eq!(s.difficulty(), Difficulty::Easy);
+        let start = s.score();
+        assert!(start > 900);
+        let pos = Pos::new_unchecked(0, 0);
+        let right = s.solution()[pos].value().unwrap();
+        assert_eq!(
+            s.play(Move::Place {
+                pos,
+                value: right % 9 + 1
+            }),
+            Ok(false)
+        );
+        assert_eq!(s.play(Move::Place { pos, value: right }), Ok(true));
+        assert!(s.hint().is_some());
+        assert_eq!((s.mistakes(), s.hints_used()), (1, 1));
+        assert_eq!(s.score(), start - 150);
+        while let Some(hint) = s.board().hint() {
+            let (pos, value) = hint.placement.unwrap();
+            s.play(Move::Place { pos, value }).unwrap();
+        }
+        assert!(s.is_complete());
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let mut s = session();
+        s.pause();
+        let (a, b) = (Pos::new_unchecked(0, 0), Pos::new_unchecked(2, 0));
+        s.play(Move::ToggleCandidate { pos: b, value: 2 }).unwrap();
+        s.play(Move::Place { pos: a, value: 1 }).unwrap();
+        s.play(Move::Place { pos: b, value: 6 }).unwrap();
+        s.undo();
+        s.hint();
+        let mut out = Vec::new();
+        s.save(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("sudoku-session 1\n"));
+        assert!(text.contains("history r1c3-2 r1c1=1\nredo r1c3=6\n"));
+
+        let mut loaded = Session::load(format!("{text}future_field 1\n").as_bytes()).unwrap();
+        assert!(loaded.is_paused());
+        assert_eq!(loaded.board(), s.board());
+        assert!(loaded.board()[b].pencil_marks().contains(2));
+        assert!(loaded.board()[Pos::new_unchecked(1, 0)].is_given());
+        assert_eq!(loaded.game().history(), s.game().history());
+        assert_eq!(loaded.mistakes(), s.mistakes());
+        assert_eq!(loaded.hints_used(), 1);
+        assert_eq!(loaded.elapsed().as_millis(), s.elapsed().as_millis());
+        assert_eq!(loaded.redo(), Some(Move::Place { pos: b, value: 6 }));
+        loaded.undo();
+        loaded.undo();
+        assert_eq!(loaded.board()[a].value(), None);
+
+        let newer = text.replacen("sudoku-session 1", "sudoku-session 2", 1);
+        assert!(matches!(
+            Session::load(newer.as_bytes()),
+            Err(ReadError::Parse { line: 1, .. })
+        ));
+        let broken = text.replace("r1c1=1", "r1c1=x");
+        assert!(matches!(
+            Session::load(broken.as_bytes()),
+            Err(ReadError::Parse { line: 8, .. })
+        ));
+        let too_large = text.replacen("\ngivens 0 5 ", "\ngivens 0 10 ", 1);
+        assert_ne!(too_large, text);
+        assert!(matches!(
+            Session::load(too_large.as_bytes()),
+            Err(ReadError::Parse {
+                error: SudokuError::InvalidValue(10),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn saves_the_candidate_mode() {
+        let (pos, peer) = (Pos::new_unchecked(0, 0), Pos::new_unchecked(0, 1));
+        for mode in [CandidateMode::Manual, CandidateMode::AutoFill] {
+            let puzzle = session().board().clone().with_candidate_mode(mode);
+            let mut s = Session::new(puzzle).unwrap();
+            s.play(Move::ToggleCandidate {
+                pos: peer,
+                value: 9,
+            })
+            .unwrap();
+            s.play(Move::Place { pos, value: 9 }).unwrap();
+            let mut out = Vec::new();
+            s.save(&mut out).unwrap();
+            let text = String::from_utf8(out).unwrap();
+            assert!(text.contains(&format!("\ncandidate_mode {mode:?}\n")));
+            let loaded = Session::load(text.as_bytes()).unwrap();
+            assert_eq!(loaded.board().candidate_mode(), mode);
+            for cell in s.board().iter() {
+                let marks = loaded.board()[cell.position()].pencil_marks();
+                assert_eq!(marks, cell.pencil_marks(), "{mode:?}");
+            }
+            // Only Manual keeps the mark the placement would prune.
+            let kept = loaded.board()[peer].pencil_marks().contains(9);
+            assert_eq!(kept, mode == CandidateMode::Manual);
+        }
+    }
+
+    #[test]
+    fn saves_every_rule() {
+        let rules = Rules {
+            anti_knight: true,
+            anti_king: true,
+            ..Rules::CLASSIC
+        };
+        let mut puzzle = Sudoku::new(Size::CLASSIC)
+            .with_rules(rules)
+            .solve()
+            .unwrap();
+        puzzle.set_forced(Pos::new_unchecked(4, 4), None).unwrap();
+        let s = Session::new(puzzle).unwrap();
+        let mut out = Vec::new();
+        s.save(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("\nrules anti-knight+anti-king\n"));
+        assert!(!text.contains("\nregions "));
+        let loaded = Session::load(text.as_bytes()).unwrap();
+        assert_eq!(loaded.board().rules(), rules);
+
+        let map =
+            "112222333111122333111222333444555666444555666444555666777888999777888999777888999";
+        let layout = RegionLayout::from_str(map).unwrap();
+        let mut jigsaw = Sudoku::new(Size::CLASSIC)
+            .with_regions(layout)
+            .unwrap()
+            .solve_with(crate::SolverKind::Dlx)
+            .unwrap();
+        jigsaw.set_forced(Pos::new_unchecked(2, 0), None).unwrap();
+        let s = Session::new(jigsaw).unwrap();
+        let mut out = Vec::new();
+        s.save(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains(&format!("\nregions {map}\n")));
+        let loaded = Session::load(text.as_bytes()).unwrap();
+        assert_eq!(loaded.board().rules().regions, Some(layout));
+        assert_eq!(loaded.board(), s.board());
+        let broken = text.replace("regions 1122", "regions 1212");
+        assert!(Session::load(broken.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn pausing_stops_the_clock() {
+        let mut s = session();
+        s.pause();
+        assert!(s.is_paused());
+        let paused = s.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(s.elapsed(), paused);
+        s.resume();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(s.elapsed() >= paused + Duration::from_millis(5));
+    }
+}
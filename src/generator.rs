@@ -0,0 +1,299 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::rng::Rng;
+use crate::{solver, ParseError, Pos, Sudoku, SudokuError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl core::str::FromStr for Difficulty {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            "expert" => Ok(Difficulty::Expert),
+            _ => Err(SudokuError::ParseError(ParseError::Invalid(
+                "Unknown difficulty.",
+            ))),
+        }
+    }
+}
+
+impl Difficulty {
+    // Clue count the generator stops removing at, once the puzzle grades
+    // as hard as asked; it goes below it when the grade comes later. A pass
+    // that never reaches the grade is thrown away and `generate` starts
+    // over from a new solution.
+    fn target_clues(self) -> usize {
+        match self {
+            Difficulty::Easy => 40,
+            Difficulty::Medium => 32,
+            Difficulty::Hard => 27,
+            Difficulty::Expert => 22,
+        }
+    }
+}
+
+/// How the clues of a generated puzzle mirror each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Unchanged by a half turn.
+    Rotational180,
+    /// Unchanged by flipping left to right.
+    Mirror,
+    /// Unchanged by flipping over the main diagonal.
+    Diagonal,
+}
+
+impl Symmetry {
+    /// The position `pos` is paired with. May be `pos` itself.
+    pub fn partner(self, pos: Pos) -> Pos {
+        let (x, y) = (pos.x(), pos.y());
+        match self {
+            Symmetry::None => pos,
+            Symmetry::Rotational180 => Pos::new_unchecked(8 - x, 8 - y),
+            Symmetry::Mirror => Pos::new_unchecked(8 - x, y),
+            Symmetry::Diagonal => Pos::new_unchecked(y, x),
+        }
+    }
+}
+
+impl core::str::FromStr for Symmetry {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Symmetry::None),
+            "rotational" | "rotational180" => Ok(Symmetry::Rotational180),
+            "mirror" => Ok(Symmetry::Mirror),
+            "diagonal" => Ok(Symmetry::Diagonal),
+            _ => Err(SudokuError::ParseError(ParseError::Invalid(
+                "Unknown symmetry.",
+            ))),
+        }
+    }
+}
+
+// Starts over from a new solution until a pass makes a puzzle that grades
+// exactly as `difficulty`.
+pub(crate) fn generate(rng: &mut Rng, difficulty: Difficulty, symmetry: Symmetry) -> Sudoku {
+    loop {
+        if let Some(puzzle) = carve(rng, difficulty, symmetry) {
+            return puzzle;
+        }
+    }
+}
+
+// Removes clues from a random solution, a symmetric pair at a time, as long
+// as the puzzle stays unique and grades no harder than `difficulty`. Stops
+// at the target clue count once the grade is reached; `None` if it never
+// is.
+fn carve(rng: &mut Rng, difficulty: Difficulty, symmetry: Symmetry) -> Option<Sudoku> {
+    let mut puzzle = solver::random_solution(rng);
+    let mut positions = (0..81).map(Pos::from_index_unchecked).collect::<Vec<_>>();
+    rng.shuffle(&mut positions);
+    let mut clues = 81;
+    let mut grade = Difficulty::Easy;
+    for pos in positions {
+        let reached = grade == difficulty;
+        if reached && clues <= difficulty.target_clues() {
+            break;
+        }
+        let partner = symmetry.partner(pos);
+        let pair = if partner == pos {
+            vec![pos]
+        } else {
+            vec![pos, partner]
+        };
+        if pair.iter().any(|&p| puzzle[p].value().is_none())
+            || (reached && clues - pair.len() < difficulty.target_clues())
+        {
+            continue;
+        }
+        let values = pair
+            .iter()
+            .map(|&p| puzzle[p].value().unwrap())
+            .collect::<Vec<_>>();
+        for &p in &pair {
+            puzzle.clear_value_at(p);
+        }
+        let harder = match puzzle.has_unique_solution() {
+            true => Some(puzzle.grade()).filter(|&g| g <= difficulty),
+            false => None,
+        };
+        match harder {
+            Some(g) => {
+                clues -= pair.len();
+                grade = g;
+            }
+            None => {
+                for (&p, &v) in pair.iter().zip(&values) {
+                    puzzle.set_value_at(v, p);
+                }
+            }
+        }
+    }
+    if grade != difficulty {
+        return None;
+    }
+    puzzle.mark_givens();
+    Some(puzzle)
+}
+
+impl Sudoku {
+    #[cfg(feature = "std")]
+    pub fn generate(difficulty: Difficulty) -> Sudoku {
+        Self::generate_symmetric(difficulty, Symmetry::None)
+    }
+    #[cfg(feature = "std")]
+    pub fn generate_symmetric(difficulty: Difficulty, symmetry: Symmetry) -> Sudoku {
+        generate(&mut Rng::new(), difficulty, symmetry)
+    }
+    /// A random complete grid, for when a full board is wanted rather than a
+    /// puzzle.
+    #[cfg(feature = "std")]
+    pub fn random_solution() -> Sudoku {
+        solver::random_solution(&mut Rng::new())
+    }
+    /// Like [`Sudoku::random_solution`], the same for a given seed.
+    pub fn random_solution_with_seed(seed: u64) -> Sudoku {
+        solver::random_solution(&mut Rng::from_seed(seed))
+    }
+    /// The same seed always gives the same puzzle, on every platform.
+    pub fn generate_with_seed(seed: u64, difficulty: Difficulty) -> Sudoku {
+        Self::generate_symmetric_with_seed(seed, difficulty, Symmetry::None)
+    }
+    /// Drops every clue the solution doesn't depend on, returning the reduced
+    /// puzzle and the positions that were cleared. No remaining clue can be
+    /// removed without losing uniqueness. Puzzles without a unique solution
+    /// come back unchanged.
+    pub fn minimize(&self) -> (Sudoku, Vec<Pos>) {
+        let mut puzzle = self.clone();
+        let mut removed = Vec::new();
+        if !puzzle.has_unique_solution() {
+            return (puzzle, removed);
+        }
+        let clues = self
+            .iter()
+            .filter(|c| c.value().is_some())
+            .map(|c| c.position())
+            .collect::<Vec<_>>();
+        // Clearing cells only ever adds solutions, so a clue needed now is
+        // still needed at the end and one pass is enough.
+        for pos in clues {
+            let cell = puzzle[pos].clone();
+            puzzle.clear_value_at(pos);
+            if puzzle.has_unique_solution() {
+                removed.push(pos);
+            } else {
+                puzzle[pos] = cell;
+            }
+        }
+        (puzzle, removed)
+    }
+    pub fn generate_symmetric_with_seed(
+        seed: u64,
+        difficulty: Difficulty,
+        symmetry: Symmetry,
+    ) -> Sudoku {
+        generate(&mut Rng::from_seed(seed), difficulty, symmetry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_difficulty_names() {
+        assert_eq!("Hard".parse(), Ok(Difficulty::Hard));
+        assert!("impossible".parse::<Difficulty>().is_err());
+    }
+
+    #[test]
+    fn random_solution_is_solved() {
+        assert!(solver::random_solution(&mut Rng::from_seed(7)).is_solved());
+        let grid = Sudoku::random_solution_with_seed(3);
+        assert!(grid.is_solved());
+        assert_eq!(grid, Sudoku::random_solution_with_seed(3));
+        assert_ne!(grid, Sudoku::random_solution_with_seed(4));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn generated_puzzle_is_unique() {
+        for difficulty in [Difficulty::Easy, Difficulty::Hard] {
+            let puzzle = Sudoku::generate(difficulty);
+            assert_eq!(puzzle.grade(), difficulty);
+            assert!(puzzle.has_unique_solution());
+            assert!(puzzle.iter().all(|c| c.is_given() == c.value().is_some()));
+        }
+    }
+
+    #[test]
+    fn seeded_generation_is_reproducible() {
+        let a = Sudoku::generate_with_seed(42, Difficulty::Hard);
+        assert_eq!(a, Sudoku::generate_with_seed(42, Difficulty::Hard));
+        assert_ne!(a, Sudoku::generate_with_seed(43, Difficulty::Hard));
+        assert!(a.has_unique_solution());
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Expert] {
+            assert_eq!(
+                Sudoku::generate_with_seed(7, difficulty).grade(),
+                difficulty
+            );
+        }
+    }
+
+    #[test]
+    fn clues_follow_symmetry() {
+        for symmetry in [
+            Symmetry::Rotational180,
+            Symmetry::Mirror,
+            Symmetry::Diagonal,
+        ] {
+            let puzzle = Sudoku::generate_symmetric_with_seed(3, Difficulty::Medium, symmetry);
+            assert!(puzzle.has_unique_solution());
+            assert!(puzzle.iter().all(|c| {
+                c.value().is_some() == puzzle[symmetry.partner(c.position())].value().is_some()
+            }));
+        }
+        assert_eq!("Mirror".parse(), Ok(Symmetry::Mirror));
+    }
+
+    #[test]
+    fn minimize_keeps_only_needed_clues() {
+        let puzzle = Sudoku::generate_with_seed(5, Difficulty::Easy);
+        let (minimal, removed) = puzzle.minimize();
+        assert!(!removed.is_empty());
+        assert!(minimal.has_unique_solution());
+        assert_eq!(minimal.solve(), puzzle.solve());
+        for cell in minimal.iter().filter(|c| c.value().is_some()) {
+            let mut fewer = minimal.clone();
+            fewer.clear_value_at(cell.position());
+            assert!(!fewer.has_unique_solution());
+        }
+        assert!(removed
+            .iter()
+            .all(|&p| puzzle[p].value().is_some() && minimal[p].value().is_none()));
+        let empty = Sudoku::new(crate::Size::CLASSIC);
+        assert_eq!(empty.minimize(), (empty.clone(), vec![]));
+    }
+
+    #[test]
+    fn easy_puzzle_hits_clue_target() {
+        let puzzle = generate(&mut Rng::from_seed(1), Difficulty::Easy, Symmetry::None);
+        let clues = puzzle.iter().filter(|c| c.value().is_some()).count();
+        assert_eq!(clues, Difficulty::Easy.target_clues());
+    }
+}
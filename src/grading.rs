@@ -0,0 +1,152 @@
+use alloc::vec::Vec;
+
+use crate::{Difficulty, Technique};
+
+/// The techniques a logical solve used, in the order it used them.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SolveLog {
+    pub techniques: Vec<Technique>,
+    /// False if the solver got stuck before the board was complete.
+    pub solved: bool,
+}
+
+impl SolveLog {
+    pub fn hardest(&self) -> Option<Technique> {
+        self.techniques
+            .iter()
+            .copied()
+            .max_by(|a, b| se_rating(*a).total_cmp(&se_rating(*b)))
+    }
+}
+
+/// Turns a [`SolveLog`] into a score. See [`crate::LogicalSolver::grade_with`].
+pub trait GradingScale {
+    type Score;
+
+    fn score(&self, log: &SolveLog) -> Self::Score;
+}
+
+/// The crate's own [`Difficulty`], from the hardest technique needed.
+/// Unfinished solves are `Expert`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TechniqueTier;
+
+impl GradingScale for TechniqueTier {
+    type Score = Difficulty;
+
+    fn score(&self, log: &SolveLog) -> Difficulty {
+        if !log.solved {
+            return Difficulty::Expert;
+        }
+        log.techniques
+            .iter()
+            .map(|t| t.difficulty())
+            .max()
+            .unwrap_or(Difficulty::Easy)
+    }
+}
+
+/// A numeric rating in the style of Sudoku Explainer, from 1.0 up: the
+/// rating of the hardest technique used. `None` if the solve didn't finish,
+/// as Sudoku Explainer would then need techniques this crate doesn't have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SeRating;
+
+impl GradingScale for SeRating {
+    type Score = Option<f32>;
+
+    fn score(&self, log: &SolveLog) -> Option<f32> {
+        if !log.solved {
+            return None;
+        }
+        Some(log.hardest().map_or(1.0, se_rating))
+    }
+}
+
+/// One to five stars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stars;
+
+impl GradingScale for Stars {
+    type Score = u8;
+
+    fn score(&self, log: &SolveLog) -> u8 {
+        match SeRating.score(log) {
+            Some(r) if r <= 1.5 => 1,
+            Some(r) if r <= 2.6 => 2,
+            Some(r) if r <= 3.4 => 3,
+            Some(r) if r <= 4.4 => 4,
+            _ => 5,
+        }
+    }
+}
+
+// Close to the values Sudoku Explainer gives the same techniques.
+pub(crate) fn se_rating(technique: Technique) -> f32 {
+    match technique {
+        Technique::HiddenSingle => 1.5,
+        Technique::NakedSingle => 2.3,
+        Technique::PointingPair => 2.6,
+        Technique::Claiming => 2.8,
+        Technique::NakedPair => 3.0,
+        Technique::XWing => 3.2,
+        Technique::HiddenPair => 3.4,
+        Technique::NakedTriple => 3.6,
+        Technique::Swordfish => 3.8,
+        Technique::HiddenTriple => 4.0,
+        Technique::XYWing => 4.2,
+        Technique::XYZWing => 4.4,
+        Technique::NakedQuad => 5.0,
+        Technique::Jellyfish => 5.2,
+        Technique::HiddenQuad => 5.4,
+        Technique::SimpleColoring => 6.2,
+        Technique::XChain => 6.6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::Sudoku;
+
+    #[test]
+    fn scores_on_each_scale() {
+        let easy = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert_eq!(easy.grade_with(&TechniqueTier), easy.grade());
+        let rating = easy.grade_with(&SeRating).unwrap();
+        assert!(rating <= 2.3);
+        assert!(easy.grade_with(&Stars) <= 2);
+
+        let expert = Sudoku::from_str(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        )
+        .unwrap();
+        assert_eq!(expert.grade_with(&SeRating), None);
+        assert_eq!(expert.grade_with(&Stars), 5);
+        assert_eq!(expert.grade_with(&TechniqueTier), Difficulty::Expert);
+    }
+
+    #[test]
+    fn custom_scales_see_the_whole_log() {
+        struct Steps;
+        impl GradingScale for Steps {
+            type Score = usize;
+            fn score(&self, log: &SolveLog) -> usize {
+                log.techniques.len()
+            }
+        }
+        let solved = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap()
+        .solve()
+        .unwrap();
+        assert_eq!(solved.grade_with(&Steps), 0);
+        assert_eq!(solved.grade_with(&SeRating), Some(1.0));
+    }
+}
@@ -0,0 +1,1188 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{
+    Candidates, Difficulty, GradingScale, House, Pos, Rules, Size, SolveError, SolveLog, Sudoku,
+    SudokuError, TechniqueTier,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    PointingPair,
+    /// Box-line reduction: a line confines a value to one box.
+    Claiming,
+    NakedPair,
+    NakedTriple,
+    NakedQuad,
+    HiddenPair,
+    HiddenTriple,
+    HiddenQuad,
+    XWing,
+    Swordfish,
+    Jellyfish,
+    XYWing,
+    XYZWing,
+    SimpleColoring,
+    XChain,
+}
+
+impl Technique {
+    /// Every technique, roughly from simplest to hardest. This is the order
+    /// [`Sudoku::hint`] tries them in.
+    pub const ALL: [Technique; 17] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::PointingPair,
+        Technique::Claiming,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::HiddenTriple,
+        Technique::NakedQuad,
+        Technique::HiddenQuad,
+        Technique::XWing,
+        Technique::Swordfish,
+        Technique::XYWing,
+        Technique::XYZWing,
+        Technique::Jellyfish,
+        Technique::SimpleColoring,
+        Technique::XChain,
+    ];
+
+    pub fn difficulty(self) -> Difficulty {
+        match self {
+            Technique::NakedSingle | Technique::HiddenSingle => Difficulty::Easy,
+            Technique::PointingPair
+            | Technique::Claiming
+            | Technique::NakedPair
+            | Technique::HiddenPair => Difficulty::Medium,
+            Technique::NakedTriple
+            | Technique::NakedQuad
+            | Technique::HiddenTriple
+            | Technique::HiddenQuad
+            | Technique::XWing
+            | Technique::Swordfish
+            | Technique::XYWing
+            | Technique::XYZWing
+            | Technique::SimpleColoring => Difficulty::Hard,
+            Technique::Jellyfish | Technique::XChain => Difficulty::Expert,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    pub technique: Technique,
+    /// Cells whose candidates justify the deduction. For wings, the pivot
+    /// followed by the two pincers.
+    pub positions: Vec<Pos>,
+    pub eliminations: Vec<(Pos, u8)>,
+    pub placement: Option<(Pos, u8)>,
+    /// Houses the deduction was made in, if it depends on any. For fish,
+    /// the base sets.
+    pub houses: Vec<House>,
+    /// Houses the eliminations were made in, when they differ from `houses`:
+    /// the line or box that locked candidates clear, or a fish's cover sets.
+    pub cover: Vec<House>,
+    /// For coloring and chains, the links followed, in order.
+    pub chain: Vec<Link>,
+}
+
+/// Two cells linked on `value`. A strong link means one of them must hold
+/// it, a weak one that they can't both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Link {
+    pub from: Pos,
+    pub to: Pos,
+    pub value: u8,
+    pub strong: bool,
+}
+
+// Values plus the candidates still open for each empty cell.
+#[derive(Debug, Clone)]
+pub(crate) struct CandidateGrid {
+    size: Size,
+    rules: Rules,
+    values: Vec<Option<u8>>,
+    candidates: Vec<Candidates>,
+}
+
+impl CandidateGrid {
+    pub(crate) fn from_sudoku(s: &Sudoku) -> Self {
+        let size = s.size();
+        let mut grid = Self {
+            size,
+            rules: s.rules(),
+            values: vec![None; size.cell_count()],
+            candidates: vec![Candidates::empty(); size.cell_count()],
+        };
+        for cell in s.iter() {
+            let i = size.index_of(cell.position());
+            grid.values[i] = cell.value();
+            if cell.value().is_none() {
+                grid.candidates[i] = cell.get_candidates(s);
+            }
+        }
+        grid
+    }
+
+    // Like `from_sudoku`, narrowed to each cell's pencil marks when it has
+    // any, so eliminations already made on the board are kept.
+    pub(crate) fn from_marks(s: &Sudoku) -> Self {
+        let mut grid = Self::from_sudoku(s);
+        for cell in s.iter().filter(|c| c.value().is_none()) {
+            let marks = cell.pencil_marks();
+            if !marks.is_empty() {
+                let i = grid.size.index_of(cell.position());
+                grid.candidates[i] = grid.candidates[i] & marks;
+            }
+        }
+        grid
+    }
+
+    pub(crate) fn value(&self, pos: Pos) -> Option<u8> {
+        self.values[self.size.index_of(pos)]
+    }
+
+    pub(crate) fn candidates(&self, pos: Pos) -> Candidates {
+        self.candidates[self.size.index_of(pos)]
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.values.iter().all(|v| v.is_some())
+    }
+
+    pub(crate) fn apply(&mut self, hint: &Hint) {
+        for &(pos, v) in &hint.eliminations {
+            self.candidates[self.size.index_of(pos)].remove(v);
+        }
+        if let Some((pos, v)) = hint.placement {
+            let size = self.size;
+            self.values[size.index_of(pos)] = Some(v);
+            self.candidates[size.index_of(pos)] = Candidates::empty();
+            for h in self.rules.houses_containing(size, pos) {
+                for p in self.rules.house_positions(size, h) {
+                    self.candidates[size.index_of(p)].remove(v);
+                }
+            }
+            for p in self.rules.links(size, pos) {
+                self.candidates[size.index_of(p)].remove(v);
+            }
+        }
+    }
+
+    // Empty cells of the house that can still take `v`.
+    fn spots(&self, house: House, v: u8) -> Vec<Pos> {
+        self.rules
+            .house_positions(self.size, house)
+            .filter(|&p| self.value(p).is_none() && self.candidates(p).contains(v))
+            .collect()
+    }
+
+    pub(crate) fn find(&self, technique: Technique) -> Option<Hint> {
+        match technique {
+            Technique::NakedSingle => self.naked_single(),
+            Technique::HiddenSingle => self.hidden_single(),
+            Technique::PointingPair => self.pointing_pair(),
+            Technique::Claiming => self.claiming(),
+            Technique::NakedPair => self.naked_subset(Technique::NakedPair, 2),
+            Technique::NakedTriple => self.naked_subset(Technique::NakedTriple, 3),
+            Technique::NakedQuad => self.naked_subset(Technique::NakedQuad, 4),
+            Technique::HiddenPair => self.hidden_subset(Technique::HiddenPair, 2),
+            Technique::HiddenTriple => self.hidden_subset(Technique::HiddenTriple, 3),
+            Technique::HiddenQuad => self.hidden_subset(Technique::HiddenQuad, 4),
+            Technique::XWing => self.fish(Technique::XWing, 2),
+            Technique::Swordfish => self.fish(Technique::Swordfish, 3),
+            Technique::Jellyfish => self.fish(Technique::Jellyfish, 4),
+            Technique::XYWing => self.wing(Technique::XYWing, 2),
+            Technique::XYZWing => self.wing(Technique::XYZWing, 3),
+            Technique::SimpleColoring => self.simple_coloring(),
+            Technique::XChain => self.x_chain(),
+        }
+    }
+
+    fn naked_single(&self) -> Option<Hint> {
+        self.size.positions().find_map(|p| {
+            let v = self
+                .candidates(p)
+                .single()
+                .filter(|_| self.value(p).is_none())?;
+            Some(Hint {
+                technique: Technique::NakedSingle,
+                positions: vec![p],
+                eliminations: Vec::new(),
+                placement: Some((p, v)),
+                houses: Vec::new(),
+                cover: Vec::new(),
+                chain: Vec::new(),
+            })
+        })
+    }
+
+    fn hidden_single(&self) -> Option<Hint> {
+        self.rules.houses(self.size).find_map(|h| {
+            (1..=self.size.side()).find_map(|v| match self.spots(h, v)[..] {
+                [p] => Some(Hint {
+                    technique: Technique::HiddenSingle,
+                    positions: vec![p],
+                    eliminations: Vec::new(),
+                    placement: Some((p, v)),
+                    houses: vec![h],
+                    cover: Vec::new(),
+                    chain: Vec::new(),
+                }),
+                _ => None,
+            })
+        })
+    }
+
+    // A box whose spots for a value all lie on one line: the value must go
+    // in the box, so the rest of the line can't take it.
+    fn pointing_pair(&self) -> Option<Hint> {
+        let boxes = (0..self.size.side()).map(House::Box);
+        self.locked_candidates(Technique::PointingPair, boxes, |spots| {
+            if spots.iter().all(|p| p.y() == spots[0].y()) {
+                Some(House::Row(spots[0].y()))
+            } else if spots.iter().all(|p| p.x() == spots[0].x()) {
+                Some(House::Column(spots[0].x()))
+            } else {
+                None
+            }
+        })
+    }
+
+    // The other way round: a line whose spots all lie in one box clears the
+    // value from the rest of the box.
+    fn claiming(&self) -> Option<Hint> {
+        let side = self.size.side();
+        let lines = (0..side)
+            .map(House::Row)
+            .chain((0..side).map(House::Column));
+        self.locked_candidates(Technique::Claiming, lines, |spots| {
+            let b = self.rules.box_of(self.size, spots[0]);
+            spots
+                .iter()
+                .all(|&p| self.rules.box_of(self.size, p) == b)
+                .then_some(House::Box(b))
+        })
+    }
+
+    fn locked_candidates(
+        &self,
+        technique: Technique,
+        mut bases: impl Iterator<Item = House>,
+        cover: impl Fn(&[Pos]) -> Option<House>,
+    ) -> Option<Hint> {
+        let side = self.size.side();
+        bases.find_map(|base| {
+            (1..=side).find_map(|v| {
+                let spots = self.spots(base, v);
+                if spots.len() < 2 {
+                    return None;
+                }
+                let other = cover(&spots)?;
+                let eliminations = self
+                    .spots(other, v)
+                    .into_iter()
+                    .filter(|p| !spots.contains(p))
+                    .map(|p| (p, v))
+                    .collect::<Vec<_>>();
+                (!eliminations.is_empty()).then(|| Hint {
+                    technique,
+                    positions: spots,
+                    eliminations,
+                    placement: None,
+                    houses: vec![base],
+                    cover: vec![other],
+                    chain: Vec::new(),
+                })
+            })
+        })
+    }
+
+    // `n` empty cells of a house whose candidates only cover `n` values: no
+    // other cell of the house can take those values.
+    fn naked_subset(&self, technique: Technique, n: usize) -> Option<Hint> {
+        self.rules.houses(self.size).find_map(|h| {
+            let open = self
+                .rules
+                .house_positions(self.size, h)
+                .filter(|&p| self.value(p).is_none() && self.candidates(p).count() <= n)
+                .collect::<Vec<_>>();
+            subsets(&open, n).into_iter().find_map(|cells| {
+                let values = cells
+                    .iter()
+                    .fold(Candidates::empty(), |u, &p| u | self.candidates(p));
+                if values.count() != n {
+                    return None;
+                }
+                let eliminations = self
+                    .rules
+                    .house_positions(self.size, h)
+                    .filter(|p| self.value(*p).is_none() && !cells.contains(p))
+                    .flat_map(|p| {
+                        let shared = self.candidates(p) & values;
+                        shared.iter().map(move |v| (p, v))
+                    })
+                    .collect::<Vec<_>>();
+                (!eliminations.is_empty()).then(|| Hint {
+                    technique,
+                    positions: cells,
+                    eliminations,
+                    placement: None,
+                    houses: vec![h],
+                    cover: Vec::new(),
+                    chain: Vec::new(),
+                })
+            })
+        })
+    }
+
+    // `n` values of a house that only fit in the same `n` cells: those cells
+    // can't take anything else.
+    fn hidden_subset(&self, technique: Technique, n: usize) -> Option<Hint> {
+        self.rules.houses(self.size).find_map(|h| {
+            let values = (1..=self.size.side())
+                .filter(|&v| (2..=n).contains(&self.spots(h, v).len()))
+                .collect::<Vec<_>>();
+            subsets(&values, n).into_iter().find_map(|set| {
+                let mut cells = Vec::new();
+                for &v in &set {
+                    for p in self.spots(h, v) {
+                        if !cells.contains(&p) {
+                            cells.push(p);
+                        }
+                    }
+                }
+                if cells.len() != n {
+                    return None;
+                }
+                cells.sort_by_key(|&p| self.size.index_of(p));
+                let keep = set.iter().copied().collect::<Candidates>();
+                let eliminations = cells
+                    .iter()
+                    .flat_map(|&p| {
+                        let extra = self.candidates(p).difference(keep);
+                        extra.iter().map(move |v| (p, v))
+                    })
+                    .collect::<Vec<_>>();
+                (!eliminations.is_empty()).then(|| Hint {
+                    technique,
+                    positions: cells,
+                    eliminations,
+                    placement: None,
+                    houses: vec![h],
+                    cover: Vec::new(),
+                    chain: Vec::new(),
+                })
+            })
+        })
+    }
+
+    // `n` rows whose spots for a value fall in the same `n` columns, or the
+    // other way round: the value fills those columns from the base rows, so
+    // the rest of the columns can't take it.
+    fn fish(&self, technique: Technique, n: usize) -> Option<Hint> {
+        let side = self.size.side();
+        let rows = (0..side).map(House::Row).collect::<Vec<_>>();
+        let columns = (0..side).map(House::Column).collect::<Vec<_>>();
+        (1..=side).find_map(|v| {
+            [(&rows, &columns), (&columns, &rows)]
+                .into_iter()
+                .find_map(|(lines, crossing)| {
+                    let bases = lines
+                        .iter()
+                        .copied()
+                        .filter(|&h| (2..=n).contains(&self.spots(h, v).len()))
+                        .collect::<Vec<_>>();
+                    subsets(&bases, n).into_iter().find_map(|base| {
+                        let spots = base
+                            .iter()
+                            .flat_map(|&h| self.spots(h, v))
+                            .collect::<Vec<_>>();
+                        let cover = crossing
+                            .iter()
+                            .copied()
+                            .filter(|&h| {
+                                spots.iter().any(|&p| {
+                                    self.rules.house_positions(self.size, h).any(|q| q == p)
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        if cover.len() != n {
+                            return None;
+                        }
+                        let eliminations = cover
+                            .iter()
+                            .flat_map(|&h| self.spots(h, v))
+                            .filter(|p| !spots.contains(p))
+                            .map(|p| (p, v))
+                            .collect::<Vec<_>>();
+                        if eliminations.is_empty() {
+                            return None;
+                        }
+                        Some(Hint {
+                            technique,
+                            positions: spots,
+                            eliminations,
+                            placement: None,
+                            houses: base,
+                            cover,
+                            chain: Vec::new(),
+                        })
+                    })
+                })
+        })
+    }
+
+    fn sees(&self, a: Pos, b: Pos) -> bool {
+        a != b
+            && (self
+                .rules
+                .houses_containing(self.size, a)
+                .into_iter()
+                .any(|h| self.rules.house_positions(self.size, h).any(|p| p == b))
+                || self.rules.links(self.size, a).any(|p| p == b))
+    }
+
+    // A pivot with `n` candidates and two bivalue pincers it sees, each
+    // sharing one of the pivot's values plus a common `z`. One of the
+    // pincers must be `z`, so cells seeing both (and the pivot, when it holds
+    // `z` too) can't be.
+    fn wing(&self, technique: Technique, n: usize) -> Option<Hint> {
+        let open = self
+            .size
+            .positions()
+            .filter(|&p| self.value(p).is_none())
+            .collect::<Vec<_>>();
+        open.iter()
+            .filter(|&&p| self.candidates(p).count() == n)
+            .find_map(|&pivot| {
+                let xy = self.candidates(pivot);
+                let pincers = open
+                    .iter()
+                    .copied()
+                    .filter(|&p| self.candidates(p).count() == 2 && self.sees(pivot, p))
+                    .collect::<Vec<_>>();
+                subsets(&pincers, 2).into_iter().find_map(|pair| {
+                    let (a, b) = (self.candidates(pair[0]), self.candidates(pair[1]));
+                    let z = (a & b).single()?;
+                    let valid = if n == 2 {
+                        !xy.contains(z) && (a | b).difference(xy) == [z].into_iter().collect()
+                    } else {
+                        a != b && (a | b) == xy
+                    };
+                    if !valid {
+                        return None;
+                    }
+                    let eliminations = open
+                        .iter()
+                        .copied()
+                        .filter(|&p| {
+                            p != pivot
+                                && self.candidates(p).contains(z)
+                                && pair.iter().all(|&q| self.sees(p, q))
+                                && (n == 2 || self.sees(p, pivot))
+                        })
+                        .map(|p| (p, z))
+                        .collect::<Vec<_>>();
+                    if eliminations.is_empty() {
+                        return None;
+                    }
+                    Some(Hint {
+                        technique,
+                        positions: vec![pivot, pair[0], pair[1]],
+                        eliminations,
+                        placement: None,
+                        houses: Vec::new(),
+                        cover: Vec::new(),
+                        chain: Vec::new(),
+                    })
+                })
+            })
+    }
+
+    // The other cell of every house where `v` only has two spots.
+    fn conjugates(&self, pos: Pos, v: u8) -> Vec<Pos> {
+        let mut out = Vec::new();
+        for h in self.rules.houses_containing(self.size, pos) {
+            if let [a, b] = self.spots(h, v)[..] {
+                let other = if a == pos { b } else { a };
+                if (a == pos || b == pos) && !out.contains(&other) {
+                    out.push(other);
+                }
+            }
+        }
+        out
+    }
+
+    fn open_with(&self, v: u8) -> Vec<Pos> {
+        self.size
+            .positions()
+            .filter(|&p| self.value(p).is_none() && self.candidates(p).contains(v))
+            .collect()
+    }
+
+    // Colors each cluster of strong links on one value in two alternating
+    // colors, one of which must be true. A color seen twice in a house is
+    // false; a cell seeing both colors is eliminated.
+    fn simple_coloring(&self) -> Option<Hint> {
+        (1..=self.size.side()).find_map(|v| {
+            let open = self.open_with(v);
+            let mut colored = Vec::<Pos>::new();
+            open.iter().find_map(|&start| {
+                if colored.contains(&start) {
+                    return None;
+                }
+                let mut cluster = vec![(start, false)];
+                let mut chain = Vec::new();
+                let mut next = 0;
+                while let Some(&(p, color)) = cluster.get(next) {
+                    next += 1;
+                    for q in self.conjugates(p, v) {
+                        if cluster.iter().all(|&(c, _)| c != q) {
+                            cluster.push((q, !color));
+                            chain.push(Link {
+                                from: p,
+                                to: q,
+                                value: v,
+                                strong: true,
+                            });
+                        }
+                    }
+                }
+                colored.extend(cluster.iter().map(|&(p, _)| p));
+                if cluster.len() < 3 {
+                    return None;
+                }
+                let of = |color| {
+                    cluster
+                        .iter()
+                        .filter(move |&&(_, c)| c == color)
+                        .map(|&(p, _)| p)
+                };
+                let wrapped = [false, true]
+                    .into_iter()
+                    .find(|&color| of(color).any(|a| of(color).any(|b| self.sees(a, b))));
+                let eliminations = match wrapped {
+                    Some(color) => of(color).map(|p| (p, v)).collect::<Vec<_>>(),
+                    None => open
+                        .iter()
+                        .copied()
+                        .filter(|&p| {
+                            of(false).any(|a| self.sees(p, a)) && of(true).any(|b| self.sees(p, b))
+                        })
+                        .map(|p| (p, v))
+                        .collect(),
+                };
+                if eliminations.is_empty() {
+                    return None;
+                }
+                Some(Hint {
+                    technique: Technique::SimpleColoring,
+                    positions: cluster.iter().map(|&(p, _)| p).collect(),
+                    eliminations,
+                    placement: None,
+                    houses: Vec::new(),
+                    cover: Vec::new(),
+                    chain,
+                })
+            })
+        })
+    }
+
+    // Alternating strong and weak links on one value, starting and ending
+    // with a strong one: one of the two ends must hold the value, so cells
+    // seeing both can't. Searched breadth first, so the shortest chain from
+    // each start wins.
+    fn x_chain(&self) -> Option<Hint> {
+        let size = self.size;
+        (1..=size.side()).find_map(|v| {
+            let open = self.open_with(v);
+            open.iter().find_map(|&start| {
+                // Whether the link into a cell was strong, and the cell the
+                // link came from.
+                let mut came_from = vec![[None, None]; size.cell_count()];
+                let mut queue = vec![(start, false, 0)];
+                let mut next = 0;
+                while let Some(&(p, strong, len)) = queue.get(next) {
+                    next += 1;
+                    if strong && len >= 3 {
+                        if let Some(hint) = self.x_chain_hint(v, start, p, &came_from, &open) {
+                            return Some(hint);
+                        }
+                    }
+                    let steps = if strong {
+                        open.iter().copied().filter(|&q| self.sees(p, q)).collect()
+                    } else {
+                        self.conjugates(p, v)
+                    };
+                    for q in steps {
+                        let slot = &mut came_from[size.index_of(q)][usize::from(!strong)];
+                        if q != start && slot.is_none() {
+                            *slot = Some(p);
+                            queue.push((q, !strong, len + 1));
+                        }
+                    }
+                }
+                None
+            })
+        })
+    }
+
+    fn x_chain_hint(
+        &self,
+        v: u8,
+        start: Pos,
+        end: Pos,
+        came_from: &[[Option<Pos>; 2]],
+        open: &[Pos],
+    ) -> Option<Hint> {
+        let mut chain = Vec::new();
+        let (mut p, mut strong) = (end, true);
+        while p != start {
+            let from = came_from[self.size.index_of(p)][usize::from(strong)]?;
+            chain.push(Link {
+                from,
+                to: p,
+                value: v,
+                strong,
+            });
+            p = from;
+            strong = !strong;
+        }
+        chain.reverse();
+        let mut cells = vec![start];
+        cells.extend(chain.iter().map(|l| l.to));
+        // Parents are shared between chains, so a cell may come back.
+        if (1..cells.len()).any(|i| cells[..i].contains(&cells[i])) {
+            return None;
+        }
+        let eliminations = open
+            .iter()
+            .copied()
+            .filter(|&p| !cells.contains(&p) && self.sees(p, start) && self.sees(p, end))
+            .map(|p| (p, v))
+            .collect::<Vec<_>>();
+        if eliminations.is_empty() {
+            return None;
+        }
+        Some(Hint {
+            technique: Technique::XChain,
+            positions: cells,
+            eliminations,
+            placement: None,
+            houses: Vec::new(),
+            cover: Vec::new(),
+            chain,
+        })
+    }
+
+    pub(crate) fn next_hint(&self, techniques: &[Technique]) -> Option<Hint> {
+        techniques.iter().find_map(|&t| self.find(t))
+    }
+}
+
+// Every way of picking `n` items, keeping their order.
+fn subsets<T: Copy>(items: &[T], n: usize) -> Vec<Vec<T>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+    let mut out = Vec::new();
+    for (i, &first) in items.iter().enumerate() {
+        for mut rest in subsets(&items[i + 1..], n - 1) {
+            rest.insert(0, first);
+            out.push(rest);
+        }
+    }
+    out
+}
+
+/// Builds a [`LogicalSolver`] that only knows the techniques it's given, tried
+/// in the order they were added.
+#[derive(Debug, Clone, Default)]
+pub struct SolverBuilder {
+    techniques: Vec<Technique>,
+}
+
+impl SolverBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adding a technique twice keeps its first place.
+    pub fn with(mut self, technique: Technique) -> Self {
+        if !self.techniques.contains(&technique) {
+            self.techniques.push(technique);
+        }
+        self
+    }
+    pub fn build(self) -> LogicalSolver {
+        LogicalSolver {
+            techniques: self.techniques,
+        }
+    }
+}
+
+/// Where a guess-free solve got to. See [`LogicalSolver::solve`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogicalSolve {
+    Solved(Sudoku),
+    /// No technique applies to `board`: the puzzle with every value the
+    /// solver placed, and the candidates it had left as pencil marks, after
+    /// `steps` deductions.
+    Stalled {
+        board: Sudoku,
+        steps: usize,
+    },
+}
+
+impl LogicalSolve {
+    pub fn is_solved(&self) -> bool {
+        matches!(self, Self::Solved(_))
+    }
+    pub fn board(&self) -> &Sudoku {
+        match self {
+            Self::Solved(board) | Self::Stalled { board, .. } => board,
+        }
+    }
+}
+
+/// Solves and grades puzzles the way a person would, one technique at a
+/// time. The default solver knows every technique in [`Technique::ALL`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogicalSolver {
+    techniques: Vec<Technique>,
+}
+
+impl Default for LogicalSolver {
+    fn default() -> Self {
+        Self {
+            techniques: Technique::ALL.to_vec(),
+        }
+    }
+}
+
+impl LogicalSolver {
+    pub fn techniques(&self) -> &[Technique] {
+        &self.techniques
+    }
+    /// The first deduction any of the solver's techniques finds. Cells with
+    /// pencil marks only count the candidates still marked, so hints build
+    /// on the eliminations [`Sudoku::apply_hint`] makes.
+    pub fn hint(&self, sudoku: &Sudoku) -> Option<Hint> {
+        CandidateGrid::from_marks(sudoku).next_hint(&self.techniques)
+    }
+    /// Solves as far as the solver's techniques allow, recording each
+    /// technique used.
+    pub fn solve_log(&self, sudoku: &Sudoku) -> SolveLog {
+        let mut grid = CandidateGrid::from_sudoku(sudoku);
+        let mut techniques = Vec::new();
+        while let Some(hint) = grid.next_hint(&self.techniques) {
+            techniques.push(hint.technique);
+            grid.apply(&hint);
+        }
+        SolveLog {
+            techniques,
+            solved: grid.is_complete(),
+        }
+    }
+    /// Solves with the solver's techniques and nothing else: no guessing and
+    /// no backtracking, so a `Solved` result shows a person can finish the
+    /// puzzle by logic alone. Fails on a board that already breaks the rules.
+    pub fn solve(&self, sudoku: &Sudoku) -> Result<LogicalSolve, SolveError> {
+        if !sudoku.is_valid() {
+            return Err(SolveError::InvalidPuzzle);
+        }
+        let mut grid = CandidateGrid::from_sudoku(sudoku);
+        let mut steps = 0;
+        while let Some(hint) = grid.next_hint(&self.techniques) {
+            grid.apply(&hint);
+            steps += 1;
+        }
+        let mut board = sudoku.clone();
+        for pos in sudoku.size().positions() {
+            if let (None, Some(v)) = (sudoku[pos].value(), grid.value(pos)) {
+                board
+                    .set(pos, Some(v))
+                    .expect("logical steps only fill empty cells");
+            }
+        }
+        if grid.is_complete() {
+            return Ok(LogicalSolve::Solved(board));
+        }
+        for pos in sudoku.size().positions() {
+            if grid.value(pos).is_none() {
+                board.get_mut(pos).set_pencil_marks(grid.candidates(pos));
+            }
+        }
+        board.rebuild_masks();
+        Ok(LogicalSolve::Stalled { board, steps })
+    }
+    /// Grades by the hardest technique needed to solve the puzzle logically.
+    /// Puzzles the solver's techniques can't finish are `Expert`.
+    pub fn grade(&self, sudoku: &Sudoku) -> Difficulty {
+        self.grade_with(sudoku, &TechniqueTier)
+    }
+    pub fn grade_with<S: GradingScale>(&self, sudoku: &Sudoku, scale: &S) -> S::Score {
+        scale.score(&self.solve_log(sudoku))
+    }
+}
+
+impl Sudoku {
+    /// The simplest logical deduction available on the current board.
+    pub fn hint(&self) -> Option<Hint> {
+        LogicalSolver::default().hint(self)
+    }
+    /// Solves without guessing, using every technique the crate knows. See
+    /// [`LogicalSolver::solve`].
+    pub fn solve_logically(&self) -> Result<LogicalSolve, SolveError> {
+        LogicalSolver::default().solve(self)
+    }
+    /// Grades with every technique the crate knows. See
+    /// [`LogicalSolver::grade`].
+    pub fn grade(&self) -> Difficulty {
+        LogicalSolver::default().grade(self)
+    }
+    /// Grades with every technique the crate knows, on the given scale.
+    pub fn grade_with<S: GradingScale>(&self, scale: &S) -> S::Score {
+        LogicalSolver::default().grade_with(self, scale)
+    }
+    /// True if the given techniques alone solve the puzzle, tried in the
+    /// order given. Cheaper than grading when only the answer matters, as
+    /// when keeping the generated puzzles that need nothing but singles.
+    pub fn solvable_with(&self, techniques: &[Technique]) -> bool {
+        let mut grid = CandidateGrid::from_sudoku(self);
+        while let Some(hint) = grid.next_hint(techniques) {
+            grid.apply(&hint);
+        }
+        grid.is_complete()
+    }
+    /// Places the hint's value and removes its eliminations from the pencil
+    /// marks, pencilling in a cell's candidates first if it has no marks.
+    pub fn apply_hint(&mut self, hint: &Hint) -> Result<(), SudokuError> {
+        for &(pos, v) in &hint.eliminations {
+            self.check(pos, Some(v))?;
+            let mut marks = self[pos].pencil_marks();
+            if marks.is_empty() {
+                marks = self[pos].get_candidates(self);
+            }
+            marks.remove(v);
+            self.set_candidates(pos, marks)?;
+        }
+        match hint.placement {
+            Some((pos, v)) => self.set(pos, Some(v)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn finds_naked_single() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let hint = s.hint().unwrap();
+        assert_eq!(hint.technique, Technique::NakedSingle);
+        let (pos, v) = hint.placement.unwrap();
+        assert_eq!(s.solve().unwrap()[pos].value(), Some(v));
+    }
+
+    #[test]
+    fn finds_pointing_pair() {
+        // Box 0 only allows 1 in row 0.
+        let mut line = String::from(".........234......567");
+        line.push_str(&".".repeat(60));
+        let s = Sudoku::from_str(&line).unwrap();
+        let grid = CandidateGrid::from_sudoku(&s);
+        let hint = grid.find(Technique::PointingPair).unwrap();
+        assert_eq!(
+            hint.positions,
+            vec![
+                Pos::new_unchecked(0, 0),
+                Pos::new_unchecked(1, 0),
+                Pos::new_unchecked(2, 0)
+            ]
+        );
+        assert!(hint.eliminations.contains(&(Pos::new_unchecked(5, 0), 1)));
+        assert!(hint.placement.is_none());
+        assert_eq!(hint.houses, vec![House::Box(0)]);
+        assert_eq!(hint.cover, vec![House::Row(0)]);
+    }
+
+    #[test]
+    fn finds_claiming() {
+        // Row 0 only allows 1 in box 0.
+        let line = format!("...234567{}", ".".repeat(72));
+        let grid = CandidateGrid::from_sudoku(&Sudoku::from_str(&line).unwrap());
+        let hint = grid.find(Technique::Claiming).unwrap();
+        assert_eq!(hint.houses, vec![House::Row(0)]);
+        assert_eq!(hint.cover, vec![House::Box(0)]);
+        assert_eq!(hint.positions.len(), 3);
+        assert_eq!(hint.eliminations.len(), 6);
+        assert!(hint.eliminations.contains(&(Pos::new_unchecked(1, 2), 1)));
+    }
+
+    #[test]
+    fn finds_naked_and_hidden_pairs() {
+        let (a, b) = (Pos::new_unchecked(0, 0), Pos::new_unchecked(4, 0));
+        let mut grid = CandidateGrid::from_sudoku(&Sudoku::new(Size::CLASSIC));
+        for p in [a, b] {
+            grid.candidates[grid.size.index_of(p)] = [1, 2].into_iter().collect();
+        }
+        let hint = grid.find(Technique::NakedPair).unwrap();
+        assert_eq!(hint.positions, vec![a, b]);
+        assert_eq!(hint.houses, vec![House::Row(0)]);
+        assert_eq!(hint.eliminations.len(), 14);
+        assert!(hint.eliminations.contains(&(Pos::new_unchecked(8, 0), 2)));
+
+        let mut grid = CandidateGrid::from_sudoku(&Sudoku::new(Size::CLASSIC));
+        for x in [1, 2, 3, 5, 6, 7, 8] {
+            let i = grid.size.index_of(Pos::new_unchecked(x, 0));
+            grid.candidates[i] = grid.candidates[i].difference([3, 4].into_iter().collect());
+        }
+        assert!(grid.find(Technique::NakedPair).is_none());
+        let hint = grid.find(Technique::HiddenPair).unwrap();
+        assert_eq!(hint.positions, vec![a, b]);
+        assert_eq!(hint.houses, vec![House::Row(0)]);
+        assert_eq!(hint.eliminations.len(), 14);
+        grid.apply(&hint);
+        assert_eq!(grid.candidates(a).iter().collect::<Vec<_>>(), vec![3, 4]);
+    }
+
+    #[test]
+    fn finds_x_wing() {
+        // Rows 1 and 4 only allow 5 in columns 2 and 7.
+        let mut grid = CandidateGrid::from_sudoku(&Sudoku::new(Size::CLASSIC));
+        for y in [1, 4] {
+            for x in [0, 1, 3, 4, 5, 6, 8] {
+                grid.candidates[grid.size.index_of(Pos::new_unchecked(x, y))].remove(5);
+            }
+        }
+        let hint = grid.find(Technique::XWing).unwrap();
+        assert_eq!(hint.houses, vec![House::Row(1), House::Row(4)]);
+        assert_eq!(hint.cover, vec![House::Column(2), House::Column(7)]);
+        assert_eq!(hint.positions.len(), 4);
+        assert_eq!(hint.eliminations.len(), 14);
+        assert!(hint.eliminations.iter().all(|&(p, v)| v == 5 && p.y() != 1));
+        assert!(grid.find(Technique::Swordfish).is_none());
+    }
+
+    #[test]
+    fn finds_wings() {
+        let set = |grid: &mut CandidateGrid, x, y, values: &[u8]| {
+            let i = grid.size.index_of(Pos::new_unchecked(x, y));
+            grid.candidates[i] = values.iter().copied().collect();
+        };
+        // Pivot r1c1 {1,2}, pincers r1c5 {1,3} and r3c2 {2,3}.
+        let mut grid = CandidateGrid::from_sudoku(&Sudoku::new(Size::CLASSIC));
+        set(&mut grid, 0, 0, &[1, 2]);
+        set(&mut grid, 4, 0, &[1, 3]);
+        set(&mut grid, 1, 2, &[2, 3]);
+        let hint = grid.find(Technique::XYWing).unwrap();
+        assert_eq!(
+            hint.positions,
+            vec![
+                Pos::new_unchecked(0, 0),
+                Pos::new_unchecked(4, 0),
+                Pos::new_unchecked(1, 2)
+            ]
+        );
+        assert!(hint.eliminations.contains(&(Pos::new_unchecked(3, 2), 3)));
+        assert!(hint.eliminations.contains(&(Pos::new_unchecked(1, 0), 3)));
+        assert!(!hint.eliminations.contains(&(Pos::new_unchecked(0, 0), 3)));
+
+        // Same shape with the pivot holding 3 as well.
+        set(&mut grid, 0, 0, &[1, 2, 3]);
+        assert!(grid.find(Technique::XYWing).is_none());
+        let hint = grid.find(Technique::XYZWing).unwrap();
+        assert_eq!(hint.positions[0], Pos::new_unchecked(0, 0));
+        assert_eq!(
+            hint.eliminations,
+            vec![(Pos::new_unchecked(1, 0), 3), (Pos::new_unchecked(2, 0), 3)]
+        );
+    }
+
+    #[test]
+    fn finds_coloring_and_x_chains() {
+        // Strong links on 1 from r1c1 to r1c6, down to r7c6 and over to r7c2.
+        let mut grid = CandidateGrid::from_sudoku(&Sudoku::new(Size::CLASSIC));
+        let keep = |p: Pos| match (p.x(), p.y()) {
+            (_, 0) => p.x() == 0 || p.x() == 5,
+            (5, _) => p.y() == 0 || p.y() == 6,
+            (_, 6) => p.x() == 1,
+            _ => true,
+        };
+        for p in Size::CLASSIC.positions().filter(|&p| !keep(p)) {
+            grid.candidates[grid.size.index_of(p)].remove(1);
+        }
+        let cells = vec![
+            Pos::new_unchecked(0, 0),
+            Pos::new_unchecked(5, 0),
+            Pos::new_unchecked(5, 6),
+            Pos::new_unchecked(1, 6),
+        ];
+        let eliminated = vec![
+            (Pos::new_unchecked(1, 1), 1),
+            (Pos::new_unchecked(1, 2), 1),
+            (Pos::new_unchecked(0, 7), 1),
+            (Pos::new_unchecked(0, 8), 1),
+        ];
+
+        let hint = grid.find(Technique::SimpleColoring).unwrap();
+        assert_eq!(hint.positions, cells);
+        assert_eq!(hint.eliminations, eliminated);
+        assert!(hint.chain.iter().all(|l| l.strong && l.value == 1));
+
+        let hint = grid.find(Technique::XChain).unwrap();
+        assert_eq!(hint.positions, cells);
+        assert_eq!(hint.eliminations, eliminated);
+        assert_eq!(
+            hint.chain.iter().map(|l| l.strong).collect::<Vec<_>>(),
+            vec![true, false, true]
+        );
+        assert_eq!(hint.chain[1].from, cells[1]);
+        assert_eq!(hint.chain[1].to, cells[2]);
+    }
+
+    #[test]
+    fn solvers_only_use_their_techniques() {
+        let medium = Sudoku::from_str(
+            ".8259...........5.4.9...7..74.....685..7.6.3.....8....9...5.18...3..8...82..4...3",
+        )
+        .unwrap();
+        let singles = SolverBuilder::new()
+            .with(Technique::NakedSingle)
+            .with(Technique::HiddenSingle)
+            .with(Technique::NakedSingle)
+            .build();
+        assert_eq!(
+            singles.techniques(),
+            &[Technique::NakedSingle, Technique::HiddenSingle]
+        );
+        assert_eq!(singles.grade(&medium), Difficulty::Expert);
+        assert_eq!(LogicalSolver::default().grade(&medium), Difficulty::Medium);
+
+        let hidden_first = SolverBuilder::new().with(Technique::HiddenSingle).build();
+        assert_eq!(
+            hidden_first.hint(&medium).unwrap().technique,
+            Technique::HiddenSingle
+        );
+        assert_eq!(SolverBuilder::new().build().hint(&medium), None);
+    }
+
+    #[test]
+    fn applying_hints_solves_easy_puzzle() {
+        let mut s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        while let Some(hint) = s.hint() {
+            s.apply_hint(&hint).unwrap();
+        }
+        assert!(s.is_solved());
+    }
+
+    #[test]
+    fn applying_hints_keeps_eliminations() {
+        let mut s = Sudoku::from_str(
+            ".8259...........5.4.9...7..74.....685..7.6.3.....8....9...5.18...3..8...82..4...3",
+        )
+        .unwrap();
+        let mut eliminated = false;
+        for _ in 0..200 {
+            let Some(hint) = s.hint() else { break };
+            if hint.placement.is_none() {
+                eliminated = true;
+                let (pos, v) = hint.eliminations[0];
+                s.apply_hint(&hint).unwrap();
+                assert!(!s[pos].pencil_marks().contains(v));
+                assert_ne!(s.hint(), Some(hint));
+            } else {
+                s.apply_hint(&hint).unwrap();
+            }
+        }
+        assert!(eliminated);
+        assert!(s.is_solved());
+    }
+
+    #[test]
+    fn grades_by_hardest_technique() {
+        let easy = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert_eq!(easy.grade(), Difficulty::Easy);
+        let medium = Sudoku::from_str(
+            ".8259...........5.4.9...7..74.....685..7.6.3.....8....9...5.18...3..8...82..4...3",
+        )
+        .unwrap();
+        assert_eq!(medium.grade(), Difficulty::Medium);
+        let expert = Sudoku::from_str(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        )
+        .unwrap();
+        assert_eq!(expert.grade(), Difficulty::Expert);
+    }
+
+    #[test]
+    fn checks_a_restricted_technique_set() {
+        let singles = [Technique::NakedSingle, Technique::HiddenSingle];
+        let easy = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert!(easy.solvable_with(&singles));
+        assert!(!easy.solvable_with(&[]));
+        assert!(easy.solve().unwrap().solvable_with(&[]));
+        let medium = Sudoku::from_str(
+            ".8259...........5.4.9...7..74.....685..7.6.3.....8....9...5.18...3..8...82..4...3",
+        )
+        .unwrap();
+        assert!(!medium.solvable_with(&singles));
+        assert!(medium.solvable_with(&Technique::ALL));
+    }
+
+    #[test]
+    fn stops_where_logic_stalls() {
+        let easy = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let solved = easy.solve_logically().unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(solved.board(), &easy.solve().unwrap());
+
+        let expert = Sudoku::from_str(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        )
+        .unwrap();
+        let LogicalSolve::Stalled { board, steps } = expert.solve_logically().unwrap() else {
+            panic!("expected the solve to stall");
+        };
+        assert_eq!(board.hint(), None);
+        let placed = board.iter().filter(|c| c.value().is_some()).count()
+            - expert.iter().filter(|c| c.value().is_some()).count();
+        assert!(steps >= placed);
+        let solution = expert.solve().unwrap();
+        for cell in board.iter() {
+            let answer = solution[cell.position()].value().unwrap();
+            match cell.value() {
+                Some(v) => assert_eq!(v, answer),
+                None => assert!(cell.pencil_marks().contains(answer)),
+            }
+        }
+
+        let mut clash = easy.clone();
+        clash.set(Pos::new_unchecked(0, 0), Some(5)).unwrap();
+        assert_eq!(clash.solve_logically(), Err(SolveError::InvalidPuzzle));
+    }
+}
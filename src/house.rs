@@ -0,0 +1,484 @@
+use alloc::vec::Vec;
+
+use crate::constraint::{moves_from, KING_MOVES, KNIGHT_MOVES};
+use crate::{AntiKing, AntiKnight, Constraint};
+use crate::{Cell, ConstraintSet, ParseError, Pos, RegionLayout, Size, Sudoku, SudokuError};
+
+/// A unit that must contain each digit exactly once. Boxes are numbered left
+/// to right, top to bottom. The associated functions describe the classic
+/// 9x9 board; see [`Size`] for other board sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum House {
+    Row(u8),
+    Column(u8),
+    Box(u8),
+    /// 0 runs from the top left corner, 1 from the top right one.
+    Diagonal(u8),
+    /// An extra windoku region, see [`Size::window_count`].
+    Window(u8),
+}
+
+/// A peer holding the same value as the cell it was found for, and what
+/// keeps the two apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    pub peer: Pos,
+    pub kind: ConflictKind,
+    pub value: u8,
+}
+
+/// Why two cells can't share a value: a house they are both in, or a
+/// knight's or king's move between them under [`Rules`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    House(House),
+    Knight,
+    King,
+}
+
+/// Optional rules on top of the rows, columns and boxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rules {
+    /// X-sudoku: both main diagonals hold every value once.
+    pub diagonals: bool,
+    /// Windoku: the windows between the boxes hold every value once.
+    pub windows: bool,
+    /// Jigsaw: irregular regions take the place of the boxes. Ignored on
+    /// boards of another size than the layout's.
+    pub regions: Option<RegionLayout>,
+    /// Cells a knight's move apart never share a value.
+    pub anti_knight: bool,
+    /// Cells a king's move apart never share a value.
+    pub anti_king: bool,
+}
+
+impl Rules {
+    pub const CLASSIC: Rules = Rules {
+        diagonals: false,
+        windows: false,
+        regions: None,
+        anti_knight: false,
+        anti_king: false,
+    };
+    pub const X: Rules = Rules {
+        diagonals: true,
+        windows: false,
+        regions: None,
+        anti_knight: false,
+        anti_king: false,
+    };
+    pub const WINDOKU: Rules = Rules {
+        diagonals: false,
+        windows: true,
+        regions: None,
+        anti_knight: false,
+        anti_king: false,
+    };
+
+    pub fn houses(self, size: Size) -> impl Iterator<Item = House> {
+        let diagonals = if self.diagonals { 0..2 } else { 0..0 };
+        let windows = if self.windows {
+            0..size.window_count()
+        } else {
+            0..0
+        };
+        size.houses()
+            .chain(diagonals.map(House::Diagonal))
+            .chain(windows.map(House::Window))
+    }
+    pub fn houses_containing(self, size: Size, pos: Pos) -> Vec<House> {
+        let mut houses = size.houses_containing(pos).to_vec();
+        houses[2] = House::Box(self.box_of(size, pos));
+        if self.diagonals {
+            if pos.x() == pos.y() {
+                houses.push(House::Diagonal(0));
+            }
+            if pos.x() + pos.y() == size.side() - 1 {
+                houses.push(House::Diagonal(1));
+            }
+        }
+        if let Some(w) = size.window_of(pos).filter(|_| self.windows) {
+            houses.push(House::Window(w));
+        }
+        houses
+    }
+    /// The box `pos` is in, or its region under jigsaw rules.
+    pub fn box_of(self, size: Size, pos: Pos) -> u8 {
+        match self.layout(size) {
+            Some(layout) => layout.region_of(pos),
+            None => size.box_of(pos),
+        }
+    }
+    /// Like [`Size::house_positions`], with boxes replaced by any regions.
+    pub fn house_positions(self, size: Size, house: House) -> impl Iterator<Item = Pos> {
+        let region = match (house, self.layout(size)) {
+            (House::Box(b), Some(layout)) => Some(layout.positions(b)),
+            _ => None,
+        };
+        let plain = region.is_none().then(|| size.house_positions(house));
+        plain
+            .into_iter()
+            .flatten()
+            .chain(region.into_iter().flatten())
+    }
+    /// Cells that can't share a value with `pos` without sharing a house:
+    /// those a knight's or king's move away under the anti-knight and
+    /// anti-king rules. May repeat cells, and cells of `pos`'s houses.
+    pub fn links(self, size: Size, pos: Pos) -> impl Iterator<Item = Pos> {
+        let knight: &[_] = if self.anti_knight { &KNIGHT_MOVES } else { &[] };
+        let king: &[_] = if self.anti_king { &KING_MOVES } else { &[] };
+        moves_from(size, pos, knight).chain(moves_from(size, pos, king))
+    }
+    // Every linked pair once, as `Size::index_of` indices in order.
+    pub(crate) fn link_pairs(self, size: Size) -> Vec<(usize, usize)> {
+        size.positions()
+            .flat_map(|a| {
+                self.links(size, a)
+                    .map(move |b| (size.index_of(a), size.index_of(b)))
+            })
+            .filter(|(a, b)| a < b)
+            .collect()
+    }
+    fn layout(self, size: Size) -> Option<RegionLayout> {
+        self.regions.filter(|r| r.size() == size)
+    }
+}
+
+/// Rule names joined with `+`, e.g. `x+windoku` or `anti-knight`.
+/// `classic` adds nothing.
+impl core::str::FromStr for Rules {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Rules::CLASSIC;
+        for name in s.split('+') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "classic" => {}
+                "x" | "diagonal" => rules.diagonals = true,
+                "windoku" | "hyper" => rules.windows = true,
+                "anti-knight" | "antiknight" => rules.anti_knight = true,
+                "anti-king" | "antiking" => rules.anti_king = true,
+                _ => {
+                    return Err(SudokuError::ParseError(ParseError::Invalid(
+                        "Unknown rules.",
+                    )))
+                }
+            }
+        }
+        Ok(rules)
+    }
+}
+
+/// Writes what `FromStr` reads, like `x+anti-knight`, or `classic` when no
+/// rule is on. Regions have no name; [`RegionLayout`] writes them.
+impl core::fmt::Display for Rules {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let names = [
+            (self.diagonals, "x"),
+            (self.windows, "windoku"),
+            (self.anti_knight, "anti-knight"),
+            (self.anti_king, "anti-king"),
+        ];
+        let mut on = names.iter().filter(|(on, _)| *on).map(|(_, name)| *name);
+        match on.next() {
+            None => f.write_str("classic"),
+            Some(first) => {
+                f.write_str(first)?;
+                on.try_for_each(|name| write!(f, "+{name}"))
+            }
+        }
+    }
+}
+
+impl House {
+    pub fn all() -> impl Iterator<Item = House> {
+        Size::CLASSIC.houses()
+    }
+    pub fn containing(pos: Pos) -> [House; 3] {
+        Size::CLASSIC.houses_containing(pos)
+    }
+    pub fn positions(self) -> impl Iterator<Item = Pos> {
+        Size::CLASSIC.house_positions(self)
+    }
+}
+
+impl Sudoku {
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self.rebuild_masks();
+        self
+    }
+    /// Every house the board's rules make, rows first.
+    pub fn houses(&self) -> impl Iterator<Item = House> {
+        self.rules.houses(self.size)
+    }
+    pub fn houses_containing(&self, pos: Pos) -> Vec<House> {
+        self.rules.houses_containing(self.size, pos)
+    }
+    pub fn house(&self, house: House) -> impl Iterator<Item = &Cell> {
+        self.rules
+            .house_positions(self.size, house)
+            .map(move |p| &self[p])
+    }
+    /// True if no placed values break the board's rules: the same answer as
+    /// [`ConstraintSet::standard`]'s, found faster from the masks when the
+    /// board keeps them.
+    pub fn is_valid(&self) -> bool {
+        match self.masks() {
+            // The masks cover every house; only the moves need a look.
+            Some(masks) => {
+                !masks.has_repeats()
+                    && (!self.rules.anti_knight || AntiKnight.is_satisfied(self))
+                    && (!self.rules.anti_king || AntiKing.is_satisfied(self))
+            }
+            None => ConstraintSet::standard(self).is_satisfied(self),
+        }
+    }
+    /// Every pair of cells sharing a house, or a knight's or king's move
+    /// under those rules, and holding the same value.
+    pub fn conflicts(&self) -> Vec<(Pos, Pos)> {
+        let mut pairs = Vec::new();
+        let repeats = |h| self.masks().is_none_or(|m| m.repeats_in(h));
+        for h in self.houses().filter(|&h| repeats(h)) {
+            let cells = self
+                .house(h)
+                .filter(|c| c.value().is_some())
+                .collect::<Vec<_>>();
+            for (i, a) in cells.iter().enumerate() {
+                for b in &cells[i + 1..] {
+                    if a.value() == b.value() {
+                        pairs.push((a.position(), b.position()));
+                    }
+                }
+            }
+        }
+        for cell in self.iter().filter(|c| c.value().is_some()) {
+            let a = cell.position();
+            for b in self.rules.links(self.size, a) {
+                if self.size.index_of(a) < self.size.index_of(b) && self[b].value() == cell.value()
+                {
+                    pairs.push((a, b));
+                }
+            }
+        }
+        pairs.sort_by_key(|(a, b)| (a.y(), a.x(), b.y(), b.x()));
+        pairs.dedup();
+        pairs
+    }
+    /// The cells clashing with the value at `pos`, house by house in the
+    /// order of [`Sudoku::houses_containing`], then those a knight's and a
+    /// king's move away. A peer sharing two houses with `pos`, such as a
+    /// row and a box, is listed for each. Empty for an empty cell.
+    pub fn conflicts_for(&self, pos: Pos) -> Vec<Conflict> {
+        let Some(value) = self[pos].value() else {
+            return Vec::new();
+        };
+        let clashes = |kind| {
+            move |peer: Pos| {
+                (self[peer].value() == Some(value)).then_some(Conflict { peer, kind, value })
+            }
+        };
+        // The masks only know about houses.
+        let in_houses = self.masks().is_none_or(|m| m.repeats_at(pos, value));
+        let houses = in_houses
+            .then(|| self.houses_containing(pos))
+            .into_iter()
+            .flatten()
+            .flat_map(|house| {
+                self.rules
+                    .house_positions(self.size, house)
+                    .filter(move |&peer| peer != pos)
+                    .filter_map(clashes(ConflictKind::House(house)))
+            });
+        let knight: &[_] = if self.rules.anti_knight {
+            &KNIGHT_MOVES
+        } else {
+            &[]
+        };
+        let king: &[_] = if self.rules.anti_king {
+            &KING_MOVES
+        } else {
+            &[]
+        };
+        let knights = moves_from(self.size, pos, knight).filter_map(clashes(ConflictKind::Knight));
+        let kings = moves_from(self.size, pos, king).filter_map(clashes(ConflictKind::King));
+        houses.chain(knights).chain(kings).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn box_positions() {
+        let positions = House::Box(5).positions().collect::<Vec<_>>();
+        assert_eq!(positions.first(), Some(&Pos::new_unchecked(6, 3)));
+        assert_eq!(positions.last(), Some(&Pos::new_unchecked(8, 5)));
+    }
+
+    #[test]
+    fn containing_houses() {
+        assert_eq!(
+            House::containing(Pos::new_unchecked(0, 4)),
+            [House::Row(4), House::Column(0), House::Box(3)]
+        );
+        assert_eq!(House::all().count(), 27);
+    }
+
+    #[test]
+    fn diagonals_are_houses_under_x_rules() {
+        let center = Pos::new_unchecked(4, 4);
+        assert_eq!(Rules::X.houses_containing(Size::CLASSIC, center).len(), 5);
+        assert_eq!(Rules::X.houses(Size::CLASSIC).count(), 29);
+        let anti = Size::CLASSIC
+            .house_positions(House::Diagonal(1))
+            .collect::<Vec<_>>();
+        assert_eq!(anti[0], Pos::new_unchecked(8, 0));
+        assert_eq!(anti[8], Pos::new_unchecked(0, 8));
+        let s = Sudoku::from_str(&format!("5{}", ".".repeat(80)))
+            .unwrap()
+            .with_rules(Rules::X);
+        assert!(s.is_valid());
+        let mut s = s;
+        s.set(Pos::new_unchecked(8, 8), Some(5)).unwrap();
+        assert!(!s.is_valid());
+        assert_eq!(s.clone().with_rules(Rules::CLASSIC).conflicts(), vec![]);
+        assert_eq!(
+            s.conflicts(),
+            vec![(Pos::new_unchecked(0, 0), Pos::new_unchecked(8, 8))]
+        );
+        assert_eq!(
+            s.conflicts_for(Pos::new_unchecked(8, 8)),
+            vec![Conflict {
+                peer: Pos::new_unchecked(0, 0),
+                kind: ConflictKind::House(House::Diagonal(0)),
+                value: 5
+            }]
+        );
+    }
+
+    #[test]
+    fn conflicts_name_the_peer_and_house() {
+        let mut s = Sudoku::from_str(&format!("5{}", ".".repeat(80))).unwrap();
+        s.set(Pos::new_unchecked(2, 0), Some(5)).unwrap();
+        s.set(Pos::new_unchecked(0, 6), Some(5)).unwrap();
+        let houses = s
+            .conflicts_for(Pos::new_unchecked(0, 0))
+            .into_iter()
+            .map(|c| (c.peer, c.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            houses,
+            vec![
+                (Pos::new_unchecked(2, 0), ConflictKind::House(House::Row(0))),
+                (
+                    Pos::new_unchecked(0, 6),
+                    ConflictKind::House(House::Column(0))
+                ),
+                (Pos::new_unchecked(2, 0), ConflictKind::House(House::Box(0))),
+            ]
+        );
+        assert_eq!(s.conflicts_for(Pos::new_unchecked(1, 1)), vec![]);
+        assert_eq!(s.conflicts_for(Pos::new_unchecked(0, 6)).len(), 1);
+
+        let mut s = s.with_rules(Rules {
+            anti_knight: true,
+            anti_king: true,
+            ..Rules::CLASSIC
+        });
+        s.set(Pos::new_unchecked(4, 4), Some(7)).unwrap();
+        s.set(Pos::new_unchecked(5, 6), Some(7)).unwrap();
+        s.set(Pos::new_unchecked(6, 7), Some(7)).unwrap();
+        assert_eq!(
+            s.conflicts_for(Pos::new_unchecked(5, 6)),
+            vec![
+                Conflict {
+                    peer: Pos::new_unchecked(4, 4),
+                    kind: ConflictKind::Knight,
+                    value: 7
+                },
+                Conflict {
+                    peer: Pos::new_unchecked(6, 7),
+                    kind: ConflictKind::King,
+                    value: 7
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn windows_sit_between_boxes() {
+        let size = Size::CLASSIC;
+        assert_eq!(size.window_count(), 4);
+        let window = size.house_positions(House::Window(3)).collect::<Vec<_>>();
+        assert_eq!(window[0], Pos::new_unchecked(5, 5));
+        assert_eq!(window[8], Pos::new_unchecked(7, 7));
+        assert_eq!(size.window_of(Pos::new_unchecked(3, 1)), Some(0));
+        assert_eq!(size.window_of(Pos::new_unchecked(4, 2)), None);
+        assert_eq!(size.window_of(Pos::new_unchecked(0, 1)), None);
+        assert_eq!(
+            Rules::WINDOKU.houses_containing(size, Pos::new_unchecked(6, 2)),
+            vec![
+                House::Row(2),
+                House::Column(6),
+                House::Box(2),
+                House::Window(1)
+            ]
+        );
+        assert_eq!(
+            "Windoku + x".parse(),
+            Ok(Rules {
+                diagonals: true,
+                windows: true,
+                ..Rules::CLASSIC
+            })
+        );
+        assert_eq!(
+            "anti-knight+ANTIKING"
+                .parse::<Rules>()
+                .map(|r| (r.anti_knight, r.anti_king)),
+            Ok((true, true))
+        );
+        assert!("jigsaw".parse::<Rules>().is_err());
+        assert_eq!(Rules::CLASSIC.to_string(), "classic");
+        for flags in 0..16 {
+            let rules = Rules {
+                diagonals: flags & 1 != 0,
+                windows: flags & 2 != 0,
+                anti_knight: flags & 4 != 0,
+                anti_king: flags & 8 != 0,
+                ..Rules::CLASSIC
+            };
+            assert_eq!(rules.to_string().parse(), Ok(rules));
+        }
+    }
+
+    #[test]
+    fn valid_board_has_no_conflicts() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert!(s.is_valid());
+        assert!(s.conflicts().is_empty());
+    }
+
+    #[test]
+    fn reports_conflicting_pairs_once() {
+        let s = Sudoku::from_str(
+            "55..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert!(!s.is_valid());
+        assert_eq!(
+            s.conflicts(),
+            vec![(Pos::new_unchecked(0, 0), Pos::new_unchecked(1, 0))]
+        );
+    }
+}
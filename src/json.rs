@@ -0,0 +1,315 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::ParseError;
+
+// Just enough JSON for the crate's own save formats. Objects keep their keys
+// in order so output is stable.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub(crate) fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+    pub(crate) fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+    pub(crate) fn as_u64(&self) -> Option<u64> {
+        match self {
+            // `as` saturates, so anything that isn't a whole number in range
+            // fails to come back unchanged.
+            Json::Number(n) if *n >= 0.0 && (*n as u64) as f64 == *n => Some(*n as u64),
+            _ => None,
+        }
+    }
+    pub(crate) fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn parse(input: &str) -> Result<Json, ParseError> {
+        let mut parser = Parser { input, at: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.at < input.len() {
+            return Err(parser.error());
+        }
+        Ok(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::String(s.into())
+    }
+}
+
+impl From<u64> for Json {
+    fn from(n: u64) -> Self {
+        Json::Number(n as f64)
+    }
+}
+
+impl core::fmt::Display for Json {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{b}"),
+            Json::Number(n) => write!(f, "{n}"),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(fields) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut core::fmt::Formatter<'_>, s: &str) -> core::fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+// `at` is a byte offset into `input`.
+struct Parser<'a> {
+    input: &'a str,
+    at: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> ParseError {
+        ParseError::at(self.input, self.input[..self.at].chars().count())
+    }
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.at).copied()
+    }
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.at += 1;
+        }
+    }
+    fn expect(&mut self, word: &str) -> Result<(), ParseError> {
+        if self.input[self.at..].starts_with(word) {
+            self.at += word.len();
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+    fn value(&mut self) -> Result<Json, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.expect("null").map(|_| Json::Null),
+            Some(b't') => self.expect("true").map(|_| Json::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => {
+                self.at += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.at += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.at += 1,
+                        Some(b']') => {
+                            self.at += 1;
+                            return Ok(Json::Array(items));
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.at += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.at += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'"') {
+                        return Err(self.error());
+                    }
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.at += 1,
+                        Some(b'}') => {
+                            self.at += 1;
+                            return Ok(Json::Object(fields));
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => {
+                let start = self.at;
+                while matches!(
+                    self.peek(),
+                    Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+                ) {
+                    self.at += 1;
+                }
+                self.input[start..self.at]
+                    .parse()
+                    .map(Json::Number)
+                    .map_err(|_| {
+                        self.at = start;
+                        self.error()
+                    })
+            }
+            _ => Err(self.error()),
+        }
+    }
+    // Starts on the opening quote.
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.at += 1;
+        let mut out = String::new();
+        let mut chars = self.input[self.at..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.at += i + 1;
+                    return Ok(out);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let hex = (0..4).filter_map(|_| chars.next()).map(|(_, c)| c);
+                            let code = u32::from_str_radix(&hex.collect::<String>(), 16).ok();
+                            match code.and_then(char::from_u32) {
+                                Some(c) => c,
+                                None => {
+                                    self.at += i;
+                                    return Err(self.error());
+                                }
+                            }
+                        }
+                        _ => {
+                            self.at += i;
+                            return Err(self.error());
+                        }
+                    };
+                    out.push(escaped);
+                }
+                c => out.push(c),
+            }
+        }
+        self.at = self.input.len();
+        Err(self.error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn round_trips_values() {
+        let text = r#"{"name":"Pack \"one\"\n","count":3,"tags":["a",true,null,-1.5],"empty":{}}"#;
+        let json = Json::parse(text).unwrap();
+        assert_eq!(
+            json.get("name").and_then(Json::as_str),
+            Some("Pack \"one\"\n")
+        );
+        assert_eq!(json.get("count").and_then(Json::as_u64), Some(3));
+        assert_eq!(
+            json.get("tags").and_then(Json::as_array).map(<[_]>::len),
+            Some(4)
+        );
+        assert_eq!(json.to_string(), text);
+        assert_eq!(
+            Json::parse(" [ 1 , \"\\u00e9\" ] "),
+            Ok(Json::Array(vec![Json::Number(1.0), "é".into()]))
+        );
+    }
+
+    #[test]
+    fn points_at_the_error() {
+        let err = Json::parse("{\"a\": 1,\n \"b\" 2}").unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::InvalidCharacter {
+                character: '2',
+                row: 2,
+                column: 6,
+                ..
+            }
+        ));
+        assert!(Json::parse("[1, 2").is_err());
+        assert!(Json::parse("\"open").is_err());
+        assert!(Json::parse("{} x").is_err());
+    }
+}
@@ -1,26 +1,118 @@
-use std::collections::HashSet;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+mod ambiguity;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod builder;
+mod calibration;
+mod candidates;
+mod compact;
+mod compare;
+mod constraint;
+mod convert;
+mod daily;
+#[cfg(feature = "db")]
+pub mod db;
+mod dlx;
+mod error;
+mod explain;
+pub mod formats;
+pub mod game;
+pub mod generator;
+mod grading;
+pub mod hints;
+mod house;
+mod json;
+mod masks;
+mod messages;
+mod observer;
+pub mod pack;
+mod regions;
+pub mod render;
+pub mod replay;
+mod rng;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "std")]
+mod shared;
+mod size;
+mod solver;
+mod symmetry;
+#[cfg(feature = "async")]
+mod task;
+mod transform;
+pub mod variants;
+
+pub use ambiguity::Ambiguity;
+#[cfg(feature = "arbitrary")]
+pub use arbitrary::SolvedGrid;
+#[cfg(feature = "std")]
+pub use batch::solve_batch;
+pub use builder::SudokuBuilder;
+pub use calibration::TechniqueWeights;
+pub use candidates::{CandidateMode, Candidates};
+pub use compact::CompactSudoku;
+pub use compare::CellChange;
+pub use constraint::{AntiKing, AntiKnight, Constraint, ConstraintSet};
+pub use daily::{Date, DAILY_VERSION};
+pub use error::{ParseError, SudokuError};
+pub use explain::{English, HintFormatter};
+pub use game::{Game, Move};
+pub use generator::{Difficulty, Symmetry};
+pub use grading::{GradingScale, SeRating, SolveLog, Stars, TechniqueTier};
+pub use hints::{Hint, Link, LogicalSolve, LogicalSolver, SolverBuilder, Technique};
+pub use house::{Conflict, ConflictKind, House, Rules};
+pub use messages::Messages;
+pub use regions::RegionLayout;
+pub use rng::Rng;
+#[cfg(feature = "std")]
+pub use shared::SharedSudoku;
+pub use size::Size;
+pub use solver::{
+    Solution, Solutions, SolveError, SolveOptions, SolveStep, SolveSteps, SolverKind,
+};
+#[cfg(feature = "async")]
+pub use task::{SolveFuture, SolveStepStream};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Pos {
     x: u8,
     y: u8,
 }
 
+// Positions are only bounded by the largest supported board; each board
+// checks them against its own size. The index conversions, boxes and peers
+// use the classic 9x9 layout, see `Size` for other boards.
 impl Pos {
-    pub fn new(x: u8, y: u8) -> Self {
-        if x > 8 || y > 8 {
-            panic!("Position out of bounds.");
+    pub fn try_new(x: u8, y: u8) -> Result<Self, SudokuError> {
+        if x >= size::MAX_SIDE || y >= size::MAX_SIDE {
+            return Err(SudokuError::OutOfBounds);
         }
-        Self { x, y }
+        Ok(Self { x, y })
+    }
+    pub fn new_unchecked(x: u8, y: u8) -> Self {
+        Self::try_new(x, y).unwrap_or_else(|e| panic!("{e}"))
     }
-    pub fn from_index(i: usize) -> Self {
+    pub fn try_from_index(i: usize) -> Result<Self, SudokuError> {
         if i > 80 {
-            panic!("Position index out of bounds.");
+            return Err(SudokuError::OutOfBounds);
         }
-        Self {
+        Ok(Self {
             x: (i % 9) as u8,
             y: (i / 9) as u8,
-        }
+        })
+    }
+    pub fn from_index_unchecked(i: usize) -> Self {
+        Self::try_from_index(i).unwrap_or_else(|e| panic!("{e}"))
     }
     pub fn to_index(&self) -> usize {
         (self.y * 9 + self.x) as usize
@@ -31,136 +123,573 @@ impl Pos {
     pub fn y(&self) -> u8 {
         self.y
     }
+    pub fn row(&self) -> u8 {
+        self.y
+    }
+    pub fn col(&self) -> u8 {
+        self.x
+    }
+    pub fn box_index(&self) -> u8 {
+        Size::CLASSIC.box_of(*self)
+    }
+    pub fn same_row(&self, other: &Pos) -> bool {
+        self.y == other.y
+    }
+    pub fn same_col(&self, other: &Pos) -> bool {
+        self.x == other.x
+    }
+    pub fn same_box(&self, other: &Pos) -> bool {
+        self.box_index() == other.box_index()
+    }
+    /// The 20 other cells sharing a row, column or box with this one, in
+    /// row-major order. Panics if the position is off the 9x9 board.
+    pub fn peers(&self) -> Vec<Pos> {
+        let me = Size::CLASSIC.index_of(*self);
+        Pos::all()
+            .filter(|p| {
+                p.to_index() != me && (self.same_row(p) || self.same_col(p) || self.same_box(p))
+            })
+            .collect()
+    }
+    /// Every position of the 9x9 board in row-major order.
+    pub fn all() -> impl Iterator<Item = Pos> {
+        Size::CLASSIC.positions()
+    }
+    pub fn row_iter(y: u8) -> impl Iterator<Item = Pos> {
+        (0..9).map(move |x| Pos::new_unchecked(x, y))
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Cell {
     value: Option<u8>,
     position: Pos,
+    pencil_marks: Candidates,
+    given: bool,
+}
+
+// Givens and pencil marks are annotations; two cells are the same if they
+// hold the same value at the same place.
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.position == other.position
+    }
+}
+
+impl Eq for Cell {}
+
+impl core::hash::Hash for Cell {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+        self.position.hash(state);
+    }
 }
 
 impl Cell {
-    pub fn new(value: Option<u8>, position: Pos) -> Self {
+    pub fn try_new(value: Option<u8>, position: Pos) -> Result<Self, SudokuError> {
         if let Some(x) = value {
-            if x == 0 || x > 9 {
-                panic!("Cell number is invalid.");
+            if x == 0 || x > size::MAX_SIDE {
+                return Err(SudokuError::InvalidValue(x));
             }
         }
-        Self { value, position }
+        Ok(Self {
+            value,
+            position,
+            pencil_marks: Candidates::empty(),
+            given: false,
+        })
+    }
+    pub fn new_unchecked(value: Option<u8>, position: Pos) -> Self {
+        Self::try_new(value, position).unwrap_or_else(|e| panic!("{e}"))
     }
     pub fn value(&self) -> Option<u8> {
         self.value
     }
+    pub fn position(&self) -> Pos {
+        self.position
+    }
+    pub fn is_given(&self) -> bool {
+        self.given
+    }
+    pub fn pencil_marks(&self) -> Candidates {
+        self.pencil_marks
+    }
+    pub fn set_pencil_marks(&mut self, marks: Candidates) {
+        self.pencil_marks = marks;
+    }
+    /// Placing a value wipes the cell's own pencil marks. Givens can't be changed.
+    pub fn set_value(&mut self, value: Option<u8>) -> Result<(), SudokuError> {
+        if self.given {
+            return Err(SudokuError::GivenCell);
+        }
+        self.force_value(value)
+    }
+    fn force_value(&mut self, value: Option<u8>) -> Result<(), SudokuError> {
+        let cell = Cell::try_new(value, self.position)?;
+        if value.is_some() {
+            self.pencil_marks = cell.pencil_marks;
+        }
+        self.value = cell.value;
+        self.given &= value.is_some();
+        Ok(())
+    }
     pub fn get_constraints<'a>(&self, board: &'a Sudoku) -> impl Iterator<Item = u8> + 'a {
-        board
-            .get_rest_of_row(self.position)
-            .chain(board.get_rest_of_column(self.position))
-            .chain(board.get_rest_of_box(self.position))
-            .collect::<HashSet<_>>()
-            .into_iter()
+        self.get_constraint_set(board).iter()
+    }
+    pub fn get_constraint_set(&self, board: &Sudoku) -> Candidates {
+        let linked = board
+            .rules
+            .links(board.size, self.position)
+            .filter_map(|p| board[p].value)
+            .collect::<Candidates>();
+        let houses = match &board.masks {
+            Some(masks) => masks.seen_from(self.position, board[self.position].value),
+            None => board
+                .houses_containing(self.position)
+                .into_iter()
+                .flat_map(|h| board.get_rest_of_house(h, self.position))
+                .collect(),
+        };
+        houses | linked
+    }
+    /// Values not ruled out by the cell's row, column, box and anything
+    /// else the board's rules add.
+    pub fn get_candidates(&self, board: &Sudoku) -> Candidates {
+        Candidates::up_to(board.size.side()).difference(self.get_constraint_set(board))
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Sudoku {
+    size: Size,
+    rules: Rules,
     cells: Vec<Cell>,
+    candidate_mode: CandidateMode,
+    observers: observer::Observers,
+    // Kept in step by every change the board makes itself. `None` after a
+    // cell was handed out mutably, until the next change rebuilds them;
+    // queries scan the board meanwhile.
+    masks: Option<masks::HouseMasks>,
+}
+
+// Like pencil marks, the candidate mode is a preference rather than part of
+// the board.
+impl PartialEq for Sudoku {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.rules == other.rules && self.cells == other.cells
+    }
+}
+
+impl Eq for Sudoku {}
+
+impl core::hash::Hash for Sudoku {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.rules.hash(state);
+        self.cells.hash(state);
+    }
 }
 
 impl Sudoku {
+    /// An empty board of the given size.
+    pub fn new(size: Size) -> Self {
+        Self::from_values(size, core::iter::repeat_n(None, size.cell_count()))
+    }
+    pub fn size(&self) -> Size {
+        self.size
+    }
     pub fn iter(&self) -> impl Iterator<Item = &Cell> {
         self.cells.iter()
     }
     pub fn set_value_at(&mut self, value: u8, pos: Pos) {
-        if value == 0 || value > 9 {
+        if !self.size.is_valid_value(value) {
             panic!("Setting invalid value.");
         }
-        self.cells
-            .iter_mut()
-            .find(|c| c.position == pos)
-            .unwrap()
-            .value = Some(value);
+        self.observed(|s| {
+            s.change(pos, |c| {
+                c.value = Some(value);
+                c.pencil_marks = Candidates::empty();
+            });
+            s.update_candidates(pos, Some(value));
+        })
     }
     pub fn clear_value_at(&mut self, pos: Pos) {
-        self.cells
-            .iter_mut()
-            .find(|c| c.position == pos)
-            .unwrap()
-            .value = None;
+        self.observed(|s| {
+            s.change(pos, |c| {
+                c.value = None;
+                c.given = false;
+            });
+            s.update_candidates(pos, None);
+        })
     }
     pub fn get_rest_of_row(&'_ self, pos: Pos) -> impl Iterator<Item = u8> + '_ {
-        self.iter()
-            .filter(|&c| matches!(c.value, Some(_)))
-            .filter(move |&c| c.position.y == pos.y && c.position.x != pos.x)
-            .map(|c| c.value.unwrap())
+        self.get_rest_of_house(self.size.houses_containing(pos)[0], pos)
     }
     pub fn get_rest_of_column(&'_ self, pos: Pos) -> impl Iterator<Item = u8> + '_ {
-        self.iter()
-            .filter(|&c| matches!(c.value, Some(_)))
-            .filter(move |&c| c.position.x == pos.x && c.position.y != pos.y)
-            .map(|c| c.value.unwrap())
+        self.get_rest_of_house(self.size.houses_containing(pos)[1], pos)
     }
     pub fn get_rest_of_box(&'_ self, pos: Pos) -> impl Iterator<Item = u8> + '_ {
-        let x = match pos.x {
-            1..=2 => 0u8,
-            3..=5 => 3,
-            _ => 6,
-        };
-        let y = match pos.y {
-            1..=2 => 0u8,
-            3..=5 => 3,
-            _ => 6,
-        };
-        self.iter()
-            .filter(|&c| matches!(c.value, Some(_)))
-            .filter(move |&c| {
-                (x..=x + 2).contains(&c.position.x)
-                    && (y..=y + 2).contains(&c.position.y)
-                    && c.position != pos
-            })
-            .map(|c| c.value.unwrap())
+        self.get_rest_of_house(self.houses_containing(pos)[2], pos)
+    }
+    fn get_rest_of_house(&'_ self, house: House, pos: Pos) -> impl Iterator<Item = u8> + '_ {
+        self.house(house)
+            .filter(move |c| c.position != pos)
+            .filter_map(|c| c.value)
+    }
+    /// The cells of row `y`, empty ones included, from left to right.
+    pub fn row_cells(&self, y: u8) -> impl Iterator<Item = &Cell> {
+        self.house(House::Row(y))
+    }
+    pub fn col_cells(&self, x: u8) -> impl Iterator<Item = &Cell> {
+        self.house(House::Column(x))
+    }
+    /// Boxes are numbered in row-major order; cells come the same way.
+    pub fn box_cells(&self, b: u8) -> impl Iterator<Item = &Cell> {
+        self.house(House::Box(b))
     }
     pub fn get_cell_at_pos(&self, pos: Pos) -> &Cell {
-        self.iter().find(|c| c.position == pos).unwrap()
+        &self[pos]
+    }
+    /// Prefer [`Sudoku::set`] for values: after a change through the cell,
+    /// queries such as [`Cell::get_candidates`] scan the board until the
+    /// next change the board makes itself.
+    pub fn get_mut(&mut self, pos: Pos) -> &mut Cell {
+        &mut self[pos]
+    }
+    pub fn set(&mut self, pos: Pos, value: Option<u8>) -> Result<(), SudokuError> {
+        self.check(pos, value)?;
+        self.observed(|s| {
+            s.change(pos, |c| c.set_value(value))?;
+            s.update_candidates(pos, value);
+            Ok(())
+        })
+    }
+    /// Like `set`, but also overwrites givens.
+    pub fn set_forced(&mut self, pos: Pos, value: Option<u8>) -> Result<(), SudokuError> {
+        self.check(pos, value)?;
+        self.observed(|s| {
+            s.change(pos, |c| c.force_value(value))?;
+            s.update_candidates(pos, value);
+            Ok(())
+        })
+    }
+    /// Clears every cell that isn't a given.
+    pub fn reset(&mut self) {
+        self.observed(|s| {
+            for pos in s.size.positions() {
+                s.change(pos, |c| {
+                    if !c.given {
+                        c.value = None;
+                        c.pencil_marks = Candidates::empty();
+                    }
+                });
+            }
+            if s.candidate_mode == CandidateMode::AutoFill {
+                s.refill_candidates();
+            }
+        })
+    }
+    // Rejects positions off the board and values too large for it.
+    fn check(&self, pos: Pos, value: Option<u8>) -> Result<(), SudokuError> {
+        if !self.size.contains(pos) {
+            return Err(SudokuError::OutOfBounds);
+        }
+        match value {
+            Some(v) if !self.size.is_valid_value(v) => Err(SudokuError::InvalidValue(v)),
+            _ => Ok(()),
+        }
+    }
+    // Builds a board from its values in index order, marking filled cells as givens.
+    pub(crate) fn from_values(size: Size, values: impl IntoIterator<Item = Option<u8>>) -> Self {
+        let mut sudoku = Self {
+            size,
+            rules: Rules::CLASSIC,
+            cells: values
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| Cell::new_unchecked(v, size.pos_at(i)))
+                .collect(),
+            candidate_mode: CandidateMode::default(),
+            observers: Default::default(),
+            masks: None,
+        };
+        sudoku.mark_givens();
+        sudoku.rebuild_masks();
+        sudoku
+    }
+    // Changes the cell at `pos` in place, keeping the masks in step with
+    // its value.
+    fn change<T>(&mut self, pos: Pos, edit: impl FnOnce(&mut Cell) -> T) -> T {
+        let mut masks = self
+            .masks
+            .take()
+            .unwrap_or_else(|| masks::HouseMasks::new(self));
+        let i = self.size.index_of(pos);
+        let before = self.cells[i].value;
+        let out = edit(&mut self.cells[i]);
+        let after = self.cells[i].value;
+        if before != after {
+            if let Some(v) = before {
+                masks.remove(pos, v);
+            }
+            if let Some(v) = after {
+                masks.add(pos, v);
+            }
+        }
+        self.masks = Some(masks);
+        out
+    }
+    // For code that edits `cells` directly or changes the rules.
+    pub(crate) fn rebuild_masks(&mut self) {
+        self.masks = Some(masks::HouseMasks::new(self));
+    }
+    pub(crate) fn masks(&self) -> Option<&masks::HouseMasks> {
+        self.masks.as_ref()
+    }
+    fn marks_mut(&mut self, pos: Pos) -> &mut Candidates {
+        let i = self.size.index_of(pos);
+        &mut self.cells[i].pencil_marks
+    }
+    pub(crate) fn mark_givens(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.given = cell.value.is_some();
+        }
+    }
+    pub fn candidate_mode(&self) -> CandidateMode {
+        self.candidate_mode
+    }
+    /// Switching to `AutoFill` fills in every candidate straight away.
+    pub fn set_candidate_mode(&mut self, mode: CandidateMode) {
+        self.candidate_mode = mode;
+        if mode == CandidateMode::AutoFill {
+            self.fill_all_candidates();
+        }
+    }
+    pub fn with_candidate_mode(mut self, mode: CandidateMode) -> Self {
+        self.set_candidate_mode(mode);
+        self
+    }
+    // Keeps pencil marks in step with a change to the value at `pos`.
+    fn update_candidates(&mut self, pos: Pos, value: Option<u8>) {
+        match (self.candidate_mode, value) {
+            (CandidateMode::Manual, _) | (CandidateMode::AutoRemove, None) => {}
+            (CandidateMode::AutoRemove, Some(value)) => {
+                for house in self.houses_containing(pos) {
+                    for p in self.rules.house_positions(self.size, house) {
+                        self.marks_mut(p).remove(value);
+                    }
+                }
+                for p in self.rules.links(self.size, pos) {
+                    self.marks_mut(p).remove(value);
+                }
+            }
+            (CandidateMode::AutoFill, _) => self.refill_candidates(),
+        }
+    }
+    /// Replaces every empty cell's pencil marks with its current candidates.
+    pub fn fill_all_candidates(&mut self) {
+        self.observed(Self::refill_candidates)
+    }
+    fn refill_candidates(&mut self) {
+        for pos in self.size.positions() {
+            let marks = match self[pos].value {
+                Some(_) => Candidates::empty(),
+                None => self[pos].get_candidates(self),
+            };
+            *self.marks_mut(pos) = marks;
+        }
+    }
+    /// Empty positions, most constrained first. Ties keep board order.
+    pub fn empty_cells_mrv(&self) -> Vec<Pos> {
+        let mut cells = self
+            .iter()
+            .filter(|c| c.value.is_none())
+            .map(|c| (c.get_candidates(self).count(), c.position))
+            .collect::<Vec<_>>();
+        cells.sort_by_key(|&(count, _)| count);
+        cells.into_iter().map(|(_, pos)| pos).collect()
+    }
+    pub fn toggle_candidate(&mut self, pos: Pos, value: u8) -> Result<(), SudokuError> {
+        self.check(pos, Some(value))?;
+        self.observed(|s| {
+            let marks = s.marks_mut(pos);
+            if marks.contains(value) {
+                marks.remove(value);
+            } else {
+                marks.insert(value);
+            }
+        });
+        Ok(())
+    }
+    /// Replaces the cell's pencil marks with `marks`.
+    pub fn set_candidates(&mut self, pos: Pos, marks: Candidates) -> Result<(), SudokuError> {
+        self.check(pos, None)?;
+        if let Some(v) = marks.iter().find(|&v| !self.size.is_valid_value(v)) {
+            return Err(SudokuError::InvalidValue(v));
+        }
+        self.observed(|s| *s.marks_mut(pos) = marks);
+        Ok(())
+    }
+    /// Like `set`, but refuses values already present in one of the cell's houses.
+    pub fn set_checked(&mut self, pos: Pos, value: Option<u8>) -> Result<(), SudokuError> {
+        self.check(pos, value)?;
+        if let Some(v) = value {
+            if self
+                .get_cell_at_pos(pos)
+                .get_constraints(self)
+                .any(|c| c == v)
+            {
+                return Err(SudokuError::Conflict(v));
+            }
+        }
+        self.set(pos, value)
+    }
+    pub fn to_line_string(&self) -> String {
+        self.iter().map(|c| c.value.map_or('.', symbol)).collect()
     }
 }
 
-impl std::str::FromStr for Sudoku {
-    type Err = &'static str;
+// Digits for 1 to 9, then letters from 'A' for the larger boards.
+fn symbol(value: u8) -> char {
+    match value {
+        1..=9 => (b'0' + value) as char,
+        _ => (b'A' + value - 10) as char,
+    }
+}
 
-    fn from_str(str: &str) -> Result<Self, Self::Err> {
-        if str.trim().chars().count() != 81 {
-            return Err("Sudoku str size was not 81.");
+// The other way round, taking lower case letters too.
+fn value_of(c: char) -> Option<u8> {
+    match c {
+        '1'..='9' => Some(c as u8 - b'0'),
+        'A'..='P' => Some(c as u8 - b'A' + 10),
+        'a'..='p' => Some(c as u8 - b'a' + 10),
+        _ => None,
+    }
+}
+
+// Cells are always stored in `Size::index_of` order.
+impl core::ops::Index<Pos> for Sudoku {
+    type Output = Cell;
+
+    fn index(&self, pos: Pos) -> &Cell {
+        &self.cells[self.size.index_of(pos)]
+    }
+}
+
+// The cell may come back with another value, so the masks have to go.
+impl core::ops::IndexMut<Pos> for Sudoku {
+    fn index_mut(&mut self, pos: Pos) -> &mut Cell {
+        self.masks = None;
+        &mut self.cells[self.size.index_of(pos)]
+    }
+}
+
+impl core::ops::Index<(u8, u8)> for Sudoku {
+    type Output = Cell;
+
+    fn index(&self, (x, y): (u8, u8)) -> &Cell {
+        &self[Pos::new_unchecked(x, y)]
+    }
+}
+
+impl core::ops::IndexMut<(u8, u8)> for Sudoku {
+    fn index_mut(&mut self, (x, y): (u8, u8)) -> &mut Cell {
+        &mut self[Pos::new_unchecked(x, y)]
+    }
+}
+
+impl core::fmt::Display for Sudoku {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let n = self.size.box_size() as usize;
+        let mut separator = String::from("+");
+        for _ in 0..n {
+            separator.push_str(&"-".repeat(n));
+            separator.push('+');
         }
-        if str.trim().contains(|c: char| {
-            if c.is_ascii_digit() {
-                let c = c.to_digit(10).unwrap();
-                c == 0 || c > 9
-            } else {
-                c != '.'
+        let line = self.to_line_string();
+        let rows = line.as_bytes().chunks(n * n);
+        for (y, row) in rows.enumerate() {
+            if y % n == 0 {
+                writeln!(f, "{separator}")?;
             }
-        }) {
-            return Err("Sudoku str contains invalid characters.");
+            for band in row.chunks(n) {
+                write!(f, "|{}", core::str::from_utf8(band).unwrap())?;
+            }
+            writeln!(f, "|")?;
         }
-        Ok(Self {
-            cells: str
-                .trim()
-                .chars()
-                .enumerate()
-                .map(|c| match c {
-                    (i, '1') => Cell::new(Some(1u8), Pos::from_index(i)),
-                    (i, '2') => Cell::new(Some(2), Pos::from_index(i)),
-                    (i, '3') => Cell::new(Some(3), Pos::from_index(i)),
-                    (i, '4') => Cell::new(Some(4), Pos::from_index(i)),
-                    (i, '5') => Cell::new(Some(5), Pos::from_index(i)),
-                    (i, '6') => Cell::new(Some(6), Pos::from_index(i)),
-                    (i, '7') => Cell::new(Some(7), Pos::from_index(i)),
-                    (i, '8') => Cell::new(Some(8), Pos::from_index(i)),
-                    (i, '9') => Cell::new(Some(9), Pos::from_index(i)),
-                    (i, '.') => Cell::new(None, Pos::from_index(i)),
-                    _ => unreachable!(),
-                })
-                .collect(),
-        })
+        write!(f, "{separator}")
+    }
+}
+
+/// Which characters [`Sudoku::parse_with`] accepts besides values and `.`.
+/// The default is what `FromStr` accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    /// `0` is an empty cell.
+    pub zero_as_empty: bool,
+    /// `_` is an empty cell.
+    pub underscore_as_empty: bool,
+    /// Whitespace anywhere is skipped.
+    pub whitespace: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            zero_as_empty: true,
+            underscore_as_empty: false,
+            whitespace: true,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Only values and `.`, with nothing in between.
+    pub fn strict() -> Self {
+        Self {
+            zero_as_empty: false,
+            underscore_as_empty: false,
+            whitespace: false,
+        }
+    }
+}
+
+impl Sudoku {
+    /// Reads one character per cell, the cell count picking the size: 16
+    /// cells for a 4x4 board, 81 for 9x9, 256 for 16x16 and 625 for 25x25.
+    /// Values above 9 are letters, `A` for 10 to `G` for 16 and on up to `P`.
+    pub fn parse_with(str: &str, options: &ParseOptions) -> Result<Self, SudokuError> {
+        let mut values = Vec::with_capacity(81);
+        for (i, c) in str.chars().enumerate() {
+            match c {
+                c if value_of(c).is_some() => values.push((i, value_of(c))),
+                '.' => values.push((i, None)),
+                '0' if options.zero_as_empty => values.push((i, None)),
+                '_' if options.underscore_as_empty => values.push((i, None)),
+                c if c.is_whitespace() && options.whitespace => {}
+                _ => return Err(ParseError::at(str, i).into()),
+            }
+        }
+        let size = Size::from_cell_count(values.len()).ok_or(ParseError::WrongLength {
+            cells: values.len(),
+        })?;
+        if let Some(&(i, _)) = values
+            .iter()
+            .find(|(_, v)| v.is_some_and(|v| !size.is_valid_value(v)))
+        {
+            return Err(ParseError::at(str, i).into());
+        }
+        Ok(Self::from_values(size, values.into_iter().map(|(_, v)| v)))
+    }
+}
+
+impl core::str::FromStr for Sudoku {
+    type Err = SudokuError;
+
+    /// Like [`Sudoku::parse_with`] with the default options: `.` or `0` for
+    /// blanks, and whitespace anywhere ignored.
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(str, &ParseOptions::default())
     }
 }
 
@@ -172,12 +701,60 @@ mod tests {
 
     #[test]
     fn pos_converts_to_index() {
-        assert_eq!(Pos::new(5, 3).to_index(), 32);
+        assert_eq!(Pos::new_unchecked(5, 3).to_index(), 32);
     }
 
     #[test]
     fn pos_from_index_is_correct() {
-        assert_eq!(Pos::from_index(32), Pos::new(5, 3));
+        assert_eq!(Pos::from_index_unchecked(32), Pos::new_unchecked(5, 3));
+    }
+
+    #[test]
+    fn pos_knows_its_neighbourhood() {
+        let pos = Pos::new_unchecked(5, 3);
+        assert_eq!((pos.row(), pos.col(), pos.box_index()), (3, 5, 4));
+        let peers = pos.peers();
+        assert_eq!(peers.len(), 20);
+        assert!(!peers.contains(&pos));
+        assert!(peers.contains(&Pos::new_unchecked(3, 5)));
+        assert!(pos.same_box(&Pos::new_unchecked(3, 5)));
+        assert!(!pos.same_row(&Pos::new_unchecked(3, 5)));
+        assert!(pos.same_col(&Pos::new_unchecked(5, 8)));
+        assert_eq!(Pos::all().count(), 81);
+        assert!(Pos::row_iter(3).all(|p| p.same_row(&pos)));
+    }
+
+    #[test]
+    fn constructors_reject_bad_input() {
+        assert_eq!(Pos::try_new(25, 0), Err(SudokuError::OutOfBounds));
+        assert_eq!(Pos::try_from_index(81), Err(SudokuError::OutOfBounds));
+        assert_eq!(
+            Cell::try_new(Some(26), Pos::new_unchecked(0, 0)),
+            Err(SudokuError::InvalidValue(26))
+        );
+        assert!(Cell::try_new(None, Pos::new_unchecked(0, 0)).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "Position out of bounds.")]
+    fn unchecked_pos_panics() {
+        Pos::new_unchecked(0, 25);
+    }
+
+    #[test]
+    fn house_cells_include_empties() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let row = s.row_cells(0).collect::<Vec<_>>();
+        assert_eq!(row.len(), 9);
+        assert_eq!(row[0].value(), None);
+        assert_eq!(row[1].position(), Pos::new_unchecked(1, 0));
+        assert_eq!(s.col_cells(8).filter(|c| c.value().is_none()).count(), 4);
+        let b = s.box_cells(4).map(|c| c.position()).collect::<Vec<_>>();
+        assert_eq!(b[0], Pos::new_unchecked(3, 3));
+        assert_eq!(b[8], Pos::new_unchecked(5, 5));
     }
 
     #[test]
@@ -187,7 +764,8 @@ mod tests {
         )
         .unwrap();
         assert_eq!(
-            s.get_rest_of_row(Pos::new(5, 4)).collect::<Vec<_>>(),
+            s.get_rest_of_row(Pos::new_unchecked(5, 4))
+                .collect::<Vec<_>>(),
             vec![9u8, 8, 2, 5]
         );
     }
@@ -199,7 +777,8 @@ mod tests {
         )
         .unwrap();
         assert_eq!(
-            s.get_rest_of_column(Pos::new(5, 2)).collect::<Vec<_>>(),
+            s.get_rest_of_column(Pos::new_unchecked(5, 2))
+                .collect::<Vec<_>>(),
             vec![3u8, 4, 7]
         );
     }
@@ -211,11 +790,25 @@ mod tests {
         )
         .unwrap();
         assert_eq!(
-            s.get_rest_of_box(Pos::new(7, 1)).collect::<Vec<_>>(),
+            s.get_rest_of_box(Pos::new_unchecked(7, 1))
+                .collect::<Vec<_>>(),
             vec![1u8, 7, 4, 6, 8]
         );
     }
 
+    #[test]
+    fn sudoku_can_get_rest_of_box_at_edge() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert_eq!(
+            s.get_rest_of_box(Pos::new_unchecked(0, 0))
+                .collect::<Vec<_>>(),
+            vec![5u8, 3, 4]
+        );
+    }
+
     #[test]
     fn correct_constraints_from_position() {
         let s = Sudoku::from_str(
@@ -223,13 +816,293 @@ mod tests {
         )
         .unwrap();
         let mut constraints = s
-            .get_cell_at_pos(Pos::new(7, 1))
+            .get_cell_at_pos(Pos::new_unchecked(7, 1))
             .get_constraints(&s)
             .collect::<Vec<_>>();
         constraints.sort();
         assert_eq!(constraints, vec![1u8, 4, 5, 6, 7, 8]);
     }
 
+    #[test]
+    fn candidates_from_position() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let candidates = s
+            .get_cell_at_pos(Pos::new_unchecked(7, 1))
+            .get_candidates(&s);
+        assert_eq!(candidates.iter().collect::<Vec<_>>(), vec![2u8, 3, 9]);
+    }
+
+    #[test]
+    fn line_string_round_trips() {
+        let line =
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+        let s = Sudoku::from_str(line).unwrap();
+        assert_eq!(s.to_line_string(), line);
+        assert_eq!(Sudoku::from_str(&s.to_line_string()).unwrap(), s);
+    }
+
+    #[test]
+    fn display_renders_grid() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let expected = "\
++---+---+---+
+|.5.|.83|.17|
+|...|1..|4..|
+|3.4|..5|6.8|
++---+---+---+
+|...|.3.|..9|
+|.9.|824|5..|
+|..6|...|.7.|
++---+---+---+
+|..9|...|.5.|
+|..7|29.|.86|
+|1.3|6.7|2.4|
++---+---+---+";
+        assert_eq!(s.to_string(), expected);
+    }
+
+    #[test]
+    fn set_validates_values() {
+        let mut s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let pos = Pos::new_unchecked(0, 0);
+        assert_eq!(s.set(pos, Some(0)), Err(SudokuError::InvalidValue(0)));
+        assert_eq!(s.set_checked(pos, Some(5)), Err(SudokuError::Conflict(5)));
+        assert_eq!(s.set_checked(pos, Some(9)), Ok(()));
+        assert_eq!(s.get_cell_at_pos(pos).value(), Some(9));
+        s.get_mut(pos).set_value(None).unwrap();
+        assert_eq!(s.get_cell_at_pos(pos).value(), None);
+    }
+
+    #[test]
+    fn index_by_position() {
+        let mut s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert_eq!(s[Pos::new_unchecked(1, 0)].value(), Some(5));
+        assert_eq!(s[(4, 4)].position(), Pos::new_unchecked(4, 4));
+        s[(0, 0)].set_value(Some(9)).unwrap();
+        assert_eq!(s[Pos::new_unchecked(0, 0)].value(), Some(9));
+    }
+
+    #[test]
+    #[should_panic(expected = "Position out of bounds.")]
+    fn index_out_of_bounds_panics() {
+        let s = Sudoku::from_str(&".".repeat(81)).unwrap();
+        let _ = &s[(9, 0)];
+    }
+
+    #[test]
+    fn pencil_marks_follow_placements() {
+        let mut s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        s.fill_all_candidates();
+        let pos = Pos::new_unchecked(7, 1);
+        assert_eq!(
+            s[pos].pencil_marks().iter().collect::<Vec<_>>(),
+            vec![2, 3, 9]
+        );
+        assert!(s[(0, 1)].pencil_marks().contains(9));
+        s.set(Pos::new_unchecked(0, 0), Some(9)).unwrap();
+        assert!(s[(0, 0)].pencil_marks().is_empty());
+        assert!(!s[(0, 1)].pencil_marks().contains(9));
+        s.toggle_candidate(pos, 9).unwrap();
+        s.toggle_candidate(pos, 2).unwrap();
+        assert_eq!(s[pos].pencil_marks().iter().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(
+            s.toggle_candidate(pos, 10),
+            Err(SudokuError::InvalidValue(10))
+        );
+    }
+
+    #[test]
+    fn parses_letters_on_larger_boards() {
+        let mut line = ".".repeat(256);
+        line.replace_range(0..3, "9AG");
+        let s = Sudoku::from_str(&line).unwrap();
+        assert_eq!(s.size(), Size::new(4).unwrap());
+        assert_eq!(s[(1, 0)].value(), Some(10));
+        assert_eq!(s[(2, 0)].value(), Some(16));
+        assert_eq!(s.to_line_string(), line);
+        assert_eq!(Sudoku::from_str(&line.to_lowercase()), Ok(s));
+        line.replace_range(3..4, "H");
+        assert!(matches!(
+            Sudoku::from_str(&line),
+            Err(SudokuError::ParseError(ParseError::InvalidCharacter {
+                character: 'H',
+                index: 3,
+                ..
+            }))
+        ));
+        let classic = format!("A{}", ".".repeat(80));
+        assert!(matches!(
+            crate::formats::parse(&classic),
+            Err(SudokuError::ParseError(ParseError::InvalidCharacter {
+                character: 'A',
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn candidate_modes_choose_what_follows_a_placement() {
+        let line =
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+        let mut manual = Sudoku::from_str(line).unwrap();
+        manual.fill_all_candidates();
+        manual.set_candidate_mode(CandidateMode::Manual);
+        manual.set(Pos::new_unchecked(0, 0), Some(9)).unwrap();
+        assert!(manual[(0, 1)].pencil_marks().contains(9));
+
+        let mut auto = Sudoku::from_str(line)
+            .unwrap()
+            .with_candidate_mode(CandidateMode::AutoFill);
+        assert_eq!(auto.candidate_mode(), CandidateMode::AutoFill);
+        let pos = Pos::new_unchecked(7, 1);
+        assert_eq!(auto[pos].pencil_marks(), auto[pos].get_candidates(&auto));
+        auto.set(Pos::new_unchecked(0, 0), Some(9)).unwrap();
+        assert!(!auto[(0, 1)].pencil_marks().contains(9));
+        // Clearing the value brings its candidates back, which `AutoRemove`
+        // leaves alone.
+        auto.set(Pos::new_unchecked(0, 0), None).unwrap();
+        assert!(auto[(0, 1)].pencil_marks().contains(9));
+        assert!(auto[(0, 0)].pencil_marks().contains(9));
+        assert_eq!(auto, Sudoku::from_str(line).unwrap());
+    }
+
+    #[test]
+    fn givens_are_protected() {
+        let mut s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let given = Pos::new_unchecked(1, 0);
+        assert!(s[given].is_given());
+        assert_eq!(s.set(given, Some(2)), Err(SudokuError::GivenCell));
+        s.set(Pos::new_unchecked(0, 0), Some(9)).unwrap();
+        assert!(!s[(0, 0)].is_given());
+        s.set_forced(given, Some(2)).unwrap();
+        assert_eq!(s[given].value(), Some(2));
+        s.set_forced(given, Some(5)).unwrap();
+        s.reset();
+        assert_eq!(s[(0, 0)].value(), None);
+        assert_eq!(s[given].value(), Some(5));
+    }
+
+    #[test]
+    fn empty_cells_ordered_by_candidate_count() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let cells = s.empty_cells_mrv();
+        assert_eq!(
+            cells.len(),
+            s.iter().filter(|c| c.value().is_none()).count()
+        );
+        let counts = cells
+            .iter()
+            .map(|&p| s[p].get_candidates(&s).count())
+            .collect::<Vec<_>>();
+        assert!(counts.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(counts[0], 1);
+    }
+
+    #[test]
+    fn small_boards_check_their_own_bounds() {
+        let mut s = Sudoku::from_str("1.......2..3...4").unwrap();
+        assert_eq!(s.size().side(), 4);
+        assert_eq!(s[(3, 3)].value(), Some(4));
+        assert_eq!(
+            s.get_cell_at_pos(Pos::new_unchecked(1, 2))
+                .get_candidates(&s)
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1, 4]
+        );
+        let expected = "\
++--+--+
+|1.|..|
+|..|..|
++--+--+
+|2.|.3|
+|..|.4|
++--+--+";
+        assert_eq!(s.to_string(), expected);
+        assert_eq!(
+            s.set(Pos::new_unchecked(4, 0), Some(1)),
+            Err(SudokuError::OutOfBounds)
+        );
+        assert_eq!(
+            s.set(Pos::new_unchecked(1, 0), Some(5)),
+            Err(SudokuError::InvalidValue(5))
+        );
+        assert_eq!(
+            s.set_checked(Pos::new_unchecked(8, 8), Some(1)),
+            Err(SudokuError::OutOfBounds)
+        );
+        assert!(Sudoku::from_str("5...............").is_err());
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_problem() {
+        let line =
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+        let spaced = format!("{}\n{}  \n", &line[..40], &line[40..]);
+        assert_eq!(Sudoku::from_str(&spaced), Sudoku::from_str(line));
+        let bad = spaced.replacen('4', "x", 2);
+        assert_eq!(
+            Sudoku::from_str(&bad),
+            Err(SudokuError::ParseError(ParseError::InvalidCharacter {
+                character: 'x',
+                index: 15,
+                row: 1,
+                column: 16
+            }))
+        );
+        let err = Sudoku::from_str(&bad.replacen('x', "4", 1).replace('x', "_")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid character '_' at row 1, column 21."
+        );
+        assert_eq!(
+            Sudoku::from_str(&line[1..]),
+            Err(SudokuError::ParseError(ParseError::WrongLength {
+                cells: 80
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_options_choose_the_blanks() {
+        let line =
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+        let s = Sudoku::from_str(line).unwrap();
+        assert_eq!(Sudoku::from_str(&line.replace('.', "0")), Ok(s.clone()));
+        let underscores = line.replace('.', "_");
+        assert!(Sudoku::from_str(&underscores).is_err());
+        let loose = ParseOptions {
+            underscore_as_empty: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(Sudoku::parse_with(&underscores, &loose), Ok(s.clone()));
+        let strict = ParseOptions::strict();
+        assert_eq!(Sudoku::parse_with(line, &strict), Ok(s));
+        assert!(Sudoku::parse_with(&line.replace('.', "0"), &strict).is_err());
+        assert!(Sudoku::parse_with(&format!("{line}\n"), &strict).is_err());
+    }
+
     #[test]
     fn mutate_sudoku() {
         let mut mut_s = Sudoku::from_str(
@@ -244,9 +1117,9 @@ mod tests {
             ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
         )
         .unwrap();
-        mut_s.set_value_at(3, Pos::new(0, 0));
+        mut_s.set_value_at(3, Pos::new_unchecked(0, 0));
         assert_eq!(mut_s, s1);
-        mut_s.clear_value_at(Pos::new(0, 0));
+        mut_s.clear_value_at(Pos::new_unchecked(0, 0));
         assert_eq!(mut_s, s2);
     }
 }
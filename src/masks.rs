@@ -0,0 +1,184 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Candidates, House, Pos, Rules, Size, Sudoku};
+
+// How often each value appears in each house of a board, kept in step as
+// values come and go so that what a cell sees costs a lookup per house
+// rather than a scan of the board. Every house has a fixed slot: rows,
+// columns and boxes, then both diagonals and the windows, whether the rules
+// use them or not.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HouseMasks {
+    size: Size,
+    rules: Rules,
+    // The values present at least once, per slot.
+    seen: Vec<Candidates>,
+    // Per slot, one count per value.
+    counts: Vec<u8>,
+    // How many (slot, value) pairs appear more than once.
+    repeats: usize,
+}
+
+impl HouseMasks {
+    pub(crate) fn new(board: &Sudoku) -> Self {
+        let size = board.size();
+        let slots = 3 * size.side() as usize + 2 + size.window_count() as usize;
+        let mut masks = Self {
+            size,
+            rules: board.rules(),
+            seen: vec![Candidates::empty(); slots],
+            counts: vec![0; slots * size.side() as usize],
+            repeats: 0,
+        };
+        for cell in board.iter() {
+            if let Some(v) = cell.value() {
+                masks.add(cell.position(), v);
+            }
+        }
+        masks
+    }
+
+    fn slot(&self, house: House) -> usize {
+        slot(self.size, house)
+    }
+    // The slots of every house `pos` is in under the board's rules. Like
+    // `Rules::houses_containing`, without building a list.
+    fn slots(&self, pos: Pos) -> impl Iterator<Item = usize> {
+        let (size, rules) = (self.size, self.rules);
+        let last = size.side() - 1;
+        let diagonal = |d: u8, on: bool| (rules.diagonals && on).then_some(House::Diagonal(d));
+        let window = size.window_of(pos).filter(|_| rules.windows);
+        [
+            Some(House::Row(pos.y())),
+            Some(House::Column(pos.x())),
+            Some(House::Box(rules.box_of(size, pos))),
+            diagonal(0, pos.x() == pos.y()),
+            diagonal(1, pos.x() + pos.y() == last),
+            window.map(House::Window),
+        ]
+        .into_iter()
+        .flatten()
+        .map(move |h| slot(size, h))
+    }
+
+    fn count(&mut self, slot: usize, value: u8) -> &mut u8 {
+        &mut self.counts[slot * self.size.side() as usize + value as usize - 1]
+    }
+
+    pub(crate) fn add(&mut self, pos: Pos, value: u8) {
+        for slot in self.slots(pos) {
+            let count = self.count(slot, value);
+            *count += 1;
+            if *count == 2 {
+                self.repeats += 1;
+            }
+            self.seen[slot].insert(value);
+        }
+    }
+    pub(crate) fn remove(&mut self, pos: Pos, value: u8) {
+        for slot in self.slots(pos) {
+            let count = self.count(slot, value);
+            *count -= 1;
+            match *count {
+                0 => self.seen[slot].remove(value),
+                1 => self.repeats -= 1,
+                _ => {}
+            }
+        }
+    }
+
+    // The values in `pos`'s houses, leaving out the one at `pos` itself
+    // unless another cell of the house holds it too.
+    pub(crate) fn seen_from(&self, pos: Pos, own: Option<u8>) -> Candidates {
+        self.slots(pos).fold(Candidates::empty(), |out, slot| {
+            out | self.others(slot, own)
+        })
+    }
+    // Like `seen_from`, for one of `pos`'s houses.
+    pub(crate) fn seen_in(&self, house: House, own: Option<u8>) -> Candidates {
+        self.others(self.slot(house), own)
+    }
+    fn others(&self, slot: usize, own: Option<u8>) -> Candidates {
+        let side = self.size.side() as usize;
+        let mut seen = self.seen[slot];
+        if let Some(v) = own.filter(|&v| self.counts[slot * side + v as usize - 1] == 1) {
+            seen.remove(v);
+        }
+        seen
+    }
+    // Diagonals and windows only count under rules that make them houses.
+    pub(crate) fn tracks(&self, house: House) -> bool {
+        match house {
+            House::Diagonal(_) => self.rules.diagonals,
+            House::Window(_) => self.rules.windows,
+            _ => true,
+        }
+    }
+    pub(crate) fn repeats_in(&self, house: House) -> bool {
+        let side = self.size.side() as usize;
+        let slot = self.slot(house);
+        self.counts[slot * side..(slot + 1) * side]
+            .iter()
+            .any(|&n| n > 1)
+    }
+    // True if `value` shows up more than once in one of `pos`'s houses.
+    pub(crate) fn repeats_at(&self, pos: Pos, value: u8) -> bool {
+        let side = self.size.side() as usize;
+        self.slots(pos)
+            .any(|slot| self.counts[slot * side + value as usize - 1] > 1)
+    }
+    pub(crate) fn has_repeats(&self) -> bool {
+        self.repeats > 0
+    }
+}
+
+fn slot(size: Size, house: House) -> usize {
+    let side = size.side() as usize;
+    match house {
+        House::Row(y) => y as usize,
+        House::Column(x) => side + x as usize,
+        House::Box(b) => 2 * side + b as usize,
+        House::Diagonal(d) => 3 * side + d as usize,
+        House::Window(w) => 3 * side + 2 + w as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn stay_in_step_with_the_board() {
+        let rules = Rules {
+            diagonals: true,
+            windows: true,
+            ..Rules::CLASSIC
+        };
+        let mut board = Sudoku::new(Size::CLASSIC).with_rules(rules);
+        let mut rng = Rng::from_seed(11);
+        for _ in 0..400 {
+            let pos = Pos::from_index_unchecked(rng.below(81));
+            // Clashing values included, so counts go above one.
+            let value = (rng.below(4) > 0).then(|| rng.below(9) as u8 + 1);
+            board.set(pos, value).unwrap();
+            assert_eq!(board.masks(), Some(&HouseMasks::new(&board)));
+        }
+        let mut stale = board.clone();
+        let _ = &mut stale[Pos::new_unchecked(0, 0)];
+        assert!(stale.masks().is_none());
+        assert_eq!(stale.is_valid(), board.is_valid());
+        assert_eq!(stale.conflicts(), board.conflicts());
+        for pos in Size::CLASSIC.positions() {
+            assert_eq!(
+                stale[pos].get_candidates(&stale),
+                board[pos].get_candidates(&board)
+            );
+            assert_eq!(stale.conflicts_for(pos), board.conflicts_for(pos));
+        }
+        board.reset();
+        assert_eq!(board.masks(), Some(&HouseMasks::new(&board)));
+        assert!(!board.masks().unwrap().has_repeats());
+    }
+}
@@ -0,0 +1,114 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::{Difficulty, English, HintFormatter, SolveError, SudokuError};
+
+/// Every string the crate shows to people, so an app can ship its own
+/// translation. [`English`] is the built-in one; the `Display` impls of the
+/// error types give the same text.
+pub trait Messages: HintFormatter {
+    fn error(&self, error: &SudokuError) -> String;
+    fn solve_error(&self, error: &SolveError) -> String;
+    fn difficulty(&self, difficulty: Difficulty) -> String;
+    /// What `sudoku check` reports: `None` for a board whose givens clash,
+    /// otherwise how many solutions were found, counting up to two.
+    fn solution_count(&self, count: Option<usize>) -> String;
+}
+
+impl Messages for English {
+    fn error(&self, error: &SudokuError) -> String {
+        format!("{error}")
+    }
+    fn solve_error(&self, error: &SolveError) -> String {
+        format!("{error}")
+    }
+    fn difficulty(&self, difficulty: Difficulty) -> String {
+        format!("{difficulty:?}")
+    }
+    fn solution_count(&self, count: Option<usize>) -> String {
+        match count {
+            None => "invalid",
+            Some(0) => "unsolvable",
+            Some(1) => "unique",
+            Some(_) => "multiple solutions",
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::{Hint, Sudoku, Technique};
+
+    struct Spanish;
+
+    impl HintFormatter for Spanish {
+        fn technique_name(&self, technique: Technique) -> String {
+            match technique {
+                Technique::NakedSingle => "Candidato único".into(),
+                t => English.technique_name(t),
+            }
+        }
+        fn explain(&self, hint: &Hint) -> String {
+            match hint.placement {
+                Some((pos, v)) => format!("Coloca {v} en f{}c{}.", pos.y() + 1, pos.x() + 1),
+                None => English.explain(hint),
+            }
+        }
+    }
+
+    impl Messages for Spanish {
+        fn error(&self, error: &SudokuError) -> String {
+            match error {
+                SudokuError::Conflict(v) => format!("El {v} choca con otra celda."),
+                e => English.error(e),
+            }
+        }
+        fn solve_error(&self, error: &SolveError) -> String {
+            English.solve_error(error)
+        }
+        fn difficulty(&self, difficulty: Difficulty) -> String {
+            match difficulty {
+                Difficulty::Easy => "Fácil",
+                Difficulty::Medium => "Media",
+                Difficulty::Hard => "Difícil",
+                Difficulty::Expert => "Experto",
+            }
+            .into()
+        }
+        fn solution_count(&self, count: Option<usize>) -> String {
+            English.solution_count(count)
+        }
+    }
+
+    #[test]
+    fn english_matches_display() {
+        let e = SudokuError::InvalidValue(12);
+        assert_eq!(English.error(&e), e.to_string());
+        assert_eq!(English.difficulty(Difficulty::Hard), "Hard");
+        assert_eq!(English.solution_count(Some(2)), "multiple solutions");
+    }
+
+    #[test]
+    fn apps_can_plug_in_translations() {
+        let messages: &dyn Messages = &Spanish;
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert_eq!(messages.difficulty(s.grade()), "Fácil");
+        assert!(s
+            .hint()
+            .unwrap()
+            .explain_with(messages)
+            .starts_with("Coloca "));
+        assert_eq!(
+            messages.error(&SudokuError::Conflict(4)),
+            "El 4 choca con otra celda."
+        );
+    }
+}
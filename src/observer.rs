@@ -0,0 +1,138 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::{CellChange, Sudoku};
+
+type Callback = Box<dyn FnMut(&CellChange) + Send + Sync>;
+
+// Callbacks belong to one board value: clones start without any, and they
+// don't take part in comparisons or hashing.
+#[derive(Default)]
+pub(crate) struct Observers(Vec<Callback>);
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for Observers {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Observers {}
+
+impl core::hash::Hash for Observers {
+    fn hash<H: core::hash::Hasher>(&self, _: &mut H) {}
+}
+
+impl core::fmt::Debug for Observers {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
+
+impl Sudoku {
+    /// Calls `f` with every change a mutation makes to a cell's value or
+    /// pencil marks, including the peers `set` prunes and cells restored by
+    /// [`crate::Game::undo`]. Changes made through `get_mut` or indexing
+    /// aren't seen. Clones of the board don't inherit observers.
+    pub fn on_change(&mut self, f: impl FnMut(&CellChange) + Send + Sync + 'static) {
+        self.observers.0.push(Box::new(f));
+    }
+    pub fn clear_observers(&mut self) {
+        self.observers.0.clear();
+    }
+
+    // Runs `mutate` and reports what it changed, in row-major order.
+    pub(crate) fn observed<T>(&mut self, mutate: impl FnOnce(&mut Self) -> T) -> T {
+        if self.observers.0.is_empty() {
+            return mutate(self);
+        }
+        let before = self
+            .cells
+            .iter()
+            .map(|c| (c.value, c.pencil_marks))
+            .collect::<Vec<_>>();
+        let out = mutate(self);
+        let mut changes = Vec::new();
+        for (cell, (value, marks)) in self.cells.iter().zip(before) {
+            let pos = cell.position;
+            changes.extend(match (value, cell.value) {
+                (None, Some(value)) => Some(CellChange::Added { pos, value }),
+                (Some(value), None) => Some(CellChange::Removed { pos, value }),
+                (Some(from), Some(to)) if from != to => Some(CellChange::Changed { pos, from, to }),
+                _ => None,
+            });
+            if marks != cell.pencil_marks {
+                changes.push(CellChange::Candidates {
+                    pos,
+                    from: marks,
+                    to: cell.pencil_marks,
+                });
+            }
+        }
+        for f in &mut self.observers.0 {
+            for change in &changes {
+                f(change);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{Game, Move, Pos};
+
+    #[test]
+    fn observers_see_every_change() {
+        let mut s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        s.on_change(move |c| log.lock().unwrap().push(*c));
+
+        let (pos, peer) = (Pos::new_unchecked(0, 0), Pos::new_unchecked(0, 1));
+        s.toggle_candidate(peer, 9).unwrap();
+        s.set(pos, Some(9)).unwrap();
+        assert!(s.set(Pos::new_unchecked(1, 0), None).is_err());
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                CellChange::Candidates {
+                    pos: peer,
+                    from: Default::default(),
+                    to: [9].into_iter().collect()
+                },
+                CellChange::Added { pos, value: 9 },
+                CellChange::Candidates {
+                    pos: peer,
+                    from: [9].into_iter().collect(),
+                    to: Default::default()
+                },
+            ]
+        );
+        assert_eq!(s.clone().observers.0.len(), 0);
+
+        seen.lock().unwrap().clear();
+        let mut game = Game::new(s);
+        game.apply(Move::Erase { pos }).unwrap();
+        game.undo();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                CellChange::Removed { pos, value: 9 },
+                CellChange::Added { pos, value: 9 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,483 @@
+//! Themed collections of puzzles with per-puzzle difficulty, tags and the
+//! player's progress, saved as JSON or in a compact binary form.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::json::Json;
+use crate::{Difficulty, ParseError, Size, Sudoku, SudokuError};
+
+const MAGIC: &[u8; 4] = b"SDKP";
+/// The newest binary pack version `PuzzlePack::from_bytes` understands.
+pub const PACK_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PackMetadata {
+    pub name: String,
+    pub author: String,
+    pub description: String,
+    /// The pack's own version, for shipping updated packs.
+    pub version: u32,
+}
+
+/// One puzzle of a pack. Only the puzzle's values are saved, all of them as
+/// givens, on a board with classic rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PuzzleEntry {
+    pub id: String,
+    pub puzzle: Sudoku,
+    pub difficulty: Difficulty,
+    pub tags: Vec<String>,
+    pub completed: bool,
+    /// The fastest completion so far.
+    pub best_time: Option<Duration>,
+}
+
+impl PuzzleEntry {
+    /// Keeps just the puzzle's values, the way the pack saves them, and
+    /// grades it for its difficulty.
+    pub fn new(id: impl Into<String>, puzzle: Sudoku) -> Self {
+        let puzzle = Sudoku::from_values(puzzle.size(), puzzle.iter().map(|c| c.value()));
+        Self {
+            id: id.into(),
+            difficulty: puzzle.grade(),
+            puzzle,
+            tags: Vec::new(),
+            completed: false,
+            best_time: None,
+        }
+    }
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackFormat {
+    Json,
+    Binary,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PuzzlePack {
+    pub metadata: PackMetadata,
+    pub puzzles: Vec<PuzzleEntry>,
+}
+
+impl PuzzlePack {
+    pub fn new(metadata: PackMetadata) -> Self {
+        Self {
+            metadata,
+            puzzles: Vec::new(),
+        }
+    }
+    pub fn push(&mut self, entry: PuzzleEntry) {
+        self.puzzles.push(entry);
+    }
+    pub fn get(&self, id: &str) -> Option<&PuzzleEntry> {
+        self.puzzles.iter().find(|e| e.id == id)
+    }
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut PuzzleEntry> {
+        self.puzzles.iter_mut().find(|e| e.id == id)
+    }
+    pub fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a PuzzleEntry> {
+        self.puzzles.iter().filter(move |e| e.has_tag(tag))
+    }
+    pub fn with_difficulty(&self, difficulty: Difficulty) -> impl Iterator<Item = &PuzzleEntry> {
+        self.puzzles
+            .iter()
+            .filter(move |e| e.difficulty == difficulty)
+    }
+    /// Marks the puzzle completed, keeping `time` if it beats the best so
+    /// far. False if there is no puzzle with that id.
+    pub fn complete(&mut self, id: &str, time: Duration) -> bool {
+        let Some(entry) = self.get_mut(id) else {
+            return false;
+        };
+        entry.completed = true;
+        entry.best_time = Some(entry.best_time.map_or(time, |best| best.min(time)));
+        true
+    }
+    pub fn completed_count(&self) -> usize {
+        self.puzzles.iter().filter(|e| e.completed).count()
+    }
+    /// The first puzzle not completed yet, in pack order.
+    pub fn next_unsolved(&self) -> Option<&PuzzleEntry> {
+        self.puzzles.iter().find(|e| !e.completed)
+    }
+    /// Forgets every completion and best time.
+    pub fn reset_progress(&mut self) {
+        for entry in &mut self.puzzles {
+            entry.completed = false;
+            entry.best_time = None;
+        }
+    }
+}
+
+fn invalid() -> SudokuError {
+    SudokuError::ParseError(ParseError::Invalid("Invalid puzzle pack."))
+}
+
+// JSON packs are an object holding `metadata` and a `puzzles` array, each
+// puzzle giving its board as a line string and its difficulty by name.
+impl PuzzlePack {
+    pub fn to_json(&self) -> String {
+        let m = &self.metadata;
+        let metadata = Json::Object(vec![
+            ("name".into(), m.name.as_str().into()),
+            ("author".into(), m.author.as_str().into()),
+            ("description".into(), m.description.as_str().into()),
+            ("version".into(), u64::from(m.version).into()),
+        ]);
+        let puzzles = self.puzzles.iter().map(|e| {
+            let mut fields = vec![
+                ("id".into(), e.id.as_str().into()),
+                ("puzzle".into(), e.puzzle.to_line_string().as_str().into()),
+                (
+                    "difficulty".into(),
+                    alloc::format!("{:?}", e.difficulty).as_str().into(),
+                ),
+                (
+                    "tags".into(),
+                    Json::Array(e.tags.iter().map(|t| t.as_str().into()).collect()),
+                ),
+                ("completed".into(), Json::Bool(e.completed)),
+            ];
+            if let Some(best) = e.best_time {
+                fields.push(("best_time_ms".into(), (best.as_millis() as u64).into()));
+            }
+            Json::Object(fields)
+        });
+        Json::Object(vec![
+            ("metadata".into(), metadata),
+            ("puzzles".into(), Json::Array(puzzles.collect())),
+        ])
+        .to_string()
+    }
+
+    pub fn from_json(input: &str) -> Result<Self, SudokuError> {
+        let json = Json::parse(input)?;
+        let text = |v: &Json, key| v.get(key).and_then(Json::as_str).map(String::from);
+        let m = json.get("metadata").ok_or_else(invalid)?;
+        let metadata = PackMetadata {
+            name: text(m, "name").unwrap_or_default(),
+            author: text(m, "author").unwrap_or_default(),
+            description: text(m, "description").unwrap_or_default(),
+            version: m
+                .get("version")
+                .and_then(Json::as_u64)
+                .map_or(0, |v| v as u32),
+        };
+        let mut pack = Self::new(metadata);
+        let puzzles = json.get("puzzles").and_then(Json::as_array);
+        for p in puzzles.ok_or_else(invalid)? {
+            let puzzle = text(p, "puzzle").ok_or_else(invalid)?.parse::<Sudoku>()?;
+            let tags = p.get("tags").and_then(Json::as_array).unwrap_or_default();
+            pack.push(PuzzleEntry {
+                id: text(p, "id").ok_or_else(invalid)?,
+                difficulty: match text(p, "difficulty") {
+                    Some(d) => d.parse()?,
+                    None => puzzle.grade(),
+                },
+                puzzle,
+                tags: tags
+                    .iter()
+                    .map(|t| t.as_str().map(String::from).ok_or_else(invalid))
+                    .collect::<Result<_, _>>()?,
+                completed: p.get("completed").and_then(Json::as_bool).unwrap_or(false),
+                best_time: p
+                    .get("best_time_ms")
+                    .and_then(Json::as_u64)
+                    .map(Duration::from_millis),
+            });
+        }
+        Ok(pack)
+    }
+}
+
+// Binary packs start with `SDKP` and a version byte. Strings are a
+// little-endian u16 length and UTF-8; boards are their box size and then
+// one value per cell, 0 for blanks, packed two to a byte when they fit in
+// four bits.
+impl PuzzlePack {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(PACK_VERSION);
+        let m = &self.metadata;
+        for s in [&m.name, &m.author, &m.description] {
+            put_str(&mut out, s);
+        }
+        out.extend(m.version.to_le_bytes());
+        out.extend((self.puzzles.len() as u32).to_le_bytes());
+        for e in &self.puzzles {
+            put_str(&mut out, &e.id);
+            let size = e.puzzle.size();
+            out.push(size.box_size());
+            let values = e.puzzle.iter().map(|c| c.value().unwrap_or(0));
+            if size.side() < 16 {
+                let values = values.collect::<Vec<_>>();
+                out.extend(values.chunks(2).map(|p| p[0] << 4 | p.get(1).unwrap_or(&0)));
+            } else {
+                out.extend(values);
+            }
+            out.push(e.difficulty as u8);
+            out.push(u8::from(e.completed) | u8::from(e.best_time.is_some()) << 1);
+            if let Some(best) = e.best_time {
+                out.extend((best.as_millis() as u64).to_le_bytes());
+            }
+            out.push(e.tags.len().min(255) as u8);
+            for tag in e.tags.iter().take(255) {
+                put_str(&mut out, tag);
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SudokuError> {
+        let mut r = Reader { bytes };
+        if r.take(4) != Some(MAGIC) {
+            return Err(invalid());
+        }
+        match r.u8() {
+            Some(v) if v <= PACK_VERSION => {}
+            _ => {
+                return Err(SudokuError::ParseError(ParseError::Invalid(
+                    "Unsupported pack version.",
+                )))
+            }
+        }
+        let mut pack = Self::new(PackMetadata {
+            name: r.str().ok_or_else(invalid)?,
+            author: r.str().ok_or_else(invalid)?,
+            description: r.str().ok_or_else(invalid)?,
+            version: r.u32().ok_or_else(invalid)?,
+        });
+        for _ in 0..r.u32().ok_or_else(invalid)? {
+            pack.push(r.entry().ok_or_else(invalid)??);
+        }
+        if !r.bytes.is_empty() {
+            return Err(invalid());
+        }
+        Ok(pack)
+    }
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) {
+    let len = s.len().min(u16::MAX as usize);
+    // Cuts overlong strings at a character boundary.
+    let len = (0..=len)
+        .rev()
+        .find(|&i| s.is_char_boundary(i))
+        .unwrap_or(0);
+    out.extend((len as u16).to_le_bytes());
+    out.extend(&s.as_bytes()[..len]);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < n {
+            return None;
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Some(head)
+    }
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+    fn str(&mut self) -> Option<String> {
+        let len = u16::from_le_bytes(self.take(2)?.try_into().ok()?);
+        String::from_utf8(self.take(len as usize)?.to_vec()).ok()
+    }
+    // `None` if the bytes run out, an error if they don't make a board.
+    fn entry(&mut self) -> Option<Result<PuzzleEntry, SudokuError>> {
+        let id = self.str()?;
+        let size = match Size::new(self.u8()?) {
+            Ok(size) => size,
+            Err(e) => return Some(Err(e)),
+        };
+        let cells = size.cell_count();
+        let values = if size.side() < 16 {
+            let packed = self.take(cells.div_ceil(2))?;
+            packed
+                .iter()
+                .flat_map(|b| [b >> 4, b & 15])
+                .take(cells)
+                .collect()
+        } else {
+            self.take(cells)?.to_vec()
+        };
+        if values.iter().any(|&v| v > size.side()) {
+            return Some(Err(invalid()));
+        }
+        let puzzle = Sudoku::from_values(
+            size,
+            values.into_iter().map(|v| Some(v).filter(|&v| v != 0)),
+        );
+        let difficulty = [
+            Difficulty::Easy,
+            Difficulty::Medium,
+            Difficulty::Hard,
+            Difficulty::Expert,
+        ]
+        .get(self.u8()? as usize)
+        .copied();
+        let flags = self.u8()?;
+        let best_time = if flags & 2 != 0 {
+            Some(Duration::from_millis(self.u64()?))
+        } else {
+            None
+        };
+        let tags = (0..self.u8()?)
+            .map(|_| self.str())
+            .collect::<Option<Vec<_>>>()?;
+        Some(
+            difficulty
+                .ok_or_else(invalid)
+                .map(|difficulty| PuzzleEntry {
+                    id,
+                    puzzle,
+                    difficulty,
+                    tags,
+                    completed: flags & 1 != 0,
+                    best_time,
+                }),
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl PuzzlePack {
+    pub fn save<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        format: PackFormat,
+    ) -> std::io::Result<()> {
+        match format {
+            PackFormat::Json => writer.write_all(self.to_json().as_bytes()),
+            PackFormat::Binary => writer.write_all(&self.to_bytes()),
+        }
+    }
+    /// Reads either format, telling them apart by the binary header. Bad
+    /// packs fail with `InvalidData`.
+    pub fn load<R: std::io::Read>(mut reader: R) -> std::io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let pack = if bytes.starts_with(MAGIC) {
+            Self::from_bytes(&bytes)
+        } else {
+            core::str::from_utf8(&bytes)
+                .map_err(|_| invalid())
+                .and_then(Self::from_json)
+        };
+        pack.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    fn pack() -> PuzzlePack {
+        let mut pack = PuzzlePack::new(PackMetadata {
+            name: "Autumn \"leaves\"".into(),
+            author: "Sergio".into(),
+            description: "Ten easy ones.\nAnd one more.".into(),
+            version: 3,
+        });
+        let puzzle = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        pack.push(PuzzleEntry::new("leaf-1", puzzle.clone()).with_tag("autumn"));
+        pack.push(
+            PuzzleEntry::new("leaf-2", puzzle.solve().unwrap())
+                .with_tag("autumn")
+                .with_tag("bonus"),
+        );
+        pack.push(PuzzleEntry::new(
+            "small",
+            Sudoku::new(Size::new(2).unwrap()),
+        ));
+        pack
+    }
+
+    #[test]
+    fn tracks_tags_and_progress() {
+        let mut pack = pack();
+        assert_eq!(pack.get("leaf-1").unwrap().difficulty, Difficulty::Easy);
+        assert_eq!(pack.tagged("autumn").count(), 2);
+        assert_eq!(pack.tagged("bonus").next().unwrap().id, "leaf-2");
+        assert!(pack.complete("leaf-1", Duration::from_secs(300)));
+        assert!(pack.complete("leaf-1", Duration::from_secs(400)));
+        assert!(!pack.complete("missing", Duration::ZERO));
+        assert_eq!(
+            pack.get("leaf-1").unwrap().best_time,
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(pack.completed_count(), 1);
+        assert_eq!(pack.next_unsolved().map(|e| e.id.as_str()), Some("leaf-2"));
+        pack.reset_progress();
+        assert_eq!(pack.completed_count(), 0);
+    }
+
+    #[test]
+    fn round_trips_both_formats() {
+        let mut pack = pack();
+        pack.complete("leaf-2", Duration::from_millis(61_250));
+        let json = pack.to_json();
+        assert!(json.starts_with(r#"{"metadata":{"name":"Autumn \"leaves\"","#));
+        assert_eq!(PuzzlePack::from_json(&json), Ok(pack.clone()));
+        let bytes = pack.to_bytes();
+        assert_eq!(&bytes[..5], b"SDKP\x01");
+        assert!(bytes.len() < json.len() / 2);
+        assert_eq!(PuzzlePack::from_bytes(&bytes), Ok(pack.clone()));
+
+        #[cfg(feature = "std")]
+        for format in [PackFormat::Json, PackFormat::Binary] {
+            let mut out = Vec::new();
+            pack.save(&mut out, format).unwrap();
+            assert_eq!(PuzzlePack::load(out.as_slice()).unwrap(), pack);
+        }
+    }
+
+    #[test]
+    fn rejects_broken_packs() {
+        let bytes = pack().to_bytes();
+        assert_eq!(
+            PuzzlePack::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(invalid())
+        );
+        let mut newer = bytes.clone();
+        newer[4] = PACK_VERSION + 1;
+        assert!(PuzzlePack::from_bytes(&newer).is_err());
+        assert_eq!(PuzzlePack::from_json("{\"metadata\":{}}"), Err(invalid()));
+        assert!(PuzzlePack::from_json(
+            "{\"metadata\":{},\"puzzles\":[{\"id\":\"a\",\"puzzle\":\"12\"}]}"
+        )
+        .is_err());
+        #[cfg(feature = "std")]
+        assert_eq!(
+            PuzzlePack::load(&b"not a pack"[..]).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{symbol, ParseError, Pos, Size, Sudoku, SudokuError};
+
+/// Irregular regions that take the place of the boxes, as in jigsaw sudoku.
+/// Regions are numbered from 0; boards up to 9x9 are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegionLayout {
+    size: Size,
+    // The region of every cell, in `Size::index_of` order.
+    region_of: [u8; 81],
+}
+
+impl RegionLayout {
+    /// `regions` gives every cell's region in row-major order. Fails with
+    /// `InvalidRegions` unless there are as many regions as values, each
+    /// made of that many cells joined side to side.
+    pub fn new(size: Size, regions: &[u8]) -> Result<Self, SudokuError> {
+        if size.side() > 9 {
+            return Err(SudokuError::InvalidSize);
+        }
+        let side = size.side() as usize;
+        if regions.len() != size.cell_count() || regions.iter().any(|&r| r as usize >= side) {
+            return Err(SudokuError::InvalidRegions);
+        }
+        let mut region_of = [0; 81];
+        region_of[..regions.len()].copy_from_slice(regions);
+        let layout = Self { size, region_of };
+        for r in 0..side as u8 {
+            let cells = layout.positions(r).collect::<Vec<_>>();
+            if cells.len() != side || !connected(&cells) {
+                return Err(SudokuError::InvalidRegions);
+            }
+        }
+        Ok(layout)
+    }
+    pub fn size(&self) -> Size {
+        self.size
+    }
+    /// Panics if `pos` is off the board.
+    pub fn region_of(&self, pos: Pos) -> u8 {
+        self.region_of[self.size.index_of(pos)]
+    }
+    /// The cells of `region` in row-major order.
+    pub fn positions(self, region: u8) -> impl Iterator<Item = Pos> {
+        self.size
+            .positions()
+            .filter(move |&p| self.region_of(p) == region)
+    }
+}
+
+// Flood fills from the first cell through orthogonal neighbours.
+fn connected(cells: &[Pos]) -> bool {
+    let mut reached = vec![cells[0]];
+    let mut next = 0;
+    while let Some(&p) = reached.get(next) {
+        next += 1;
+        for &q in cells {
+            let near = p.x().abs_diff(q.x()) + p.y().abs_diff(q.y()) == 1;
+            if near && !reached.contains(&q) {
+                reached.push(q);
+            }
+        }
+    }
+    reached.len() == cells.len()
+}
+
+/// A region map with one character per cell and whitespace ignored, e.g.
+/// nine rows like `112222333`. Any characters can name the regions; they
+/// are numbered in the order they first appear.
+impl core::str::FromStr for RegionLayout {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut names = Vec::new();
+        let mut regions = Vec::with_capacity(81);
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            let r = match names.iter().position(|&n| n == c) {
+                Some(r) => r,
+                None => {
+                    names.push(c);
+                    names.len() - 1
+                }
+            };
+            regions.push(r.min(u8::MAX as usize) as u8);
+        }
+        let size = Size::from_cell_count(regions.len()).ok_or(ParseError::WrongLength {
+            cells: regions.len(),
+        })?;
+        Self::new(size, &regions)
+    }
+}
+
+/// One row per line, with regions numbered from 1.
+impl core::fmt::Display for RegionLayout {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let side = self.size.side() as usize;
+        for (i, &r) in self.region_of[..self.size.cell_count()].iter().enumerate() {
+            write!(f, "{}", symbol(r + 1))?;
+            if i % side == side - 1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Sudoku {
+    /// Replaces the boxes with `layout`'s regions for validation,
+    /// candidates, hints and solving; `House::Box(n)` then stands for region
+    /// `n`. The layout is part of the board's [`crate::Rules`], so a later
+    /// `with_rules` call replaces it.
+    pub fn with_regions(mut self, layout: RegionLayout) -> Result<Self, SudokuError> {
+        if layout.size() != self.size {
+            return Err(SudokuError::InvalidSize);
+        }
+        self.rules.regions = Some(layout);
+        self.rebuild_masks();
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::{House, SolverKind};
+
+    // The boxes with r1c3 and r2c4 swapped between the first two.
+    const MAP: &str = "\
+001111222
+000011222
+000111222
+333444555
+333444555
+333444555
+666777888
+666777888
+666777888
+";
+
+    #[test]
+    fn parses_and_checks_region_maps() {
+        let layout = RegionLayout::from_str(MAP).unwrap();
+        assert_eq!(layout.region_of(Pos::new_unchecked(2, 0)), 1);
+        assert_eq!(layout.region_of(Pos::new_unchecked(3, 1)), 0);
+        assert_eq!(layout.positions(8).count(), 9);
+        assert_eq!(
+            RegionLayout::from_str(&MAP.replace('0', "a").replace('8', "z")),
+            Ok(layout)
+        );
+        assert_eq!(layout.to_string().lines().next(), Some("112222333"));
+
+        // Swapping r1c1 and r1c6 keeps the sizes but splits both regions.
+        let split = MAP.replacen("001111222", "101110222", 1);
+        assert_eq!(
+            RegionLayout::from_str(&split),
+            Err(SudokuError::InvalidRegions)
+        );
+        let uneven = MAP.replacen('0', "1", 1);
+        assert_eq!(
+            RegionLayout::from_str(&uneven),
+            Err(SudokuError::InvalidRegions)
+        );
+        assert!(RegionLayout::from_str("0011").is_err());
+    }
+
+    #[test]
+    fn regions_replace_the_boxes() {
+        let layout = RegionLayout::from_str(MAP).unwrap();
+        let board = Sudoku::new(Size::CLASSIC).with_regions(layout).unwrap();
+        assert_eq!(
+            board.houses_containing(Pos::new_unchecked(2, 0))[2],
+            House::Box(1)
+        );
+        assert!(board
+            .house(House::Box(0))
+            .any(|c| c.position() == Pos::new_unchecked(3, 1)));
+        assert_eq!(
+            Sudoku::new(Size::new(2).unwrap()).with_regions(layout),
+            Err(SudokuError::InvalidSize)
+        );
+
+        let solution = board.solve_with(SolverKind::Dlx).unwrap();
+        assert!(solution.is_valid());
+        let line = solution
+            .to_line_string()
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i % 2 == 0 { c } else { '.' })
+            .collect::<alloc::string::String>();
+        let puzzle = Sudoku::from_str(&line)
+            .unwrap()
+            .with_regions(layout)
+            .unwrap();
+        for kind in [
+            SolverKind::Backtracking,
+            SolverKind::Propagation,
+            SolverKind::Dlx,
+        ] {
+            let solved = puzzle.solve_with(kind).unwrap();
+            assert!(solved.is_valid() && solved.is_solved());
+        }
+        let mut s = puzzle.clone();
+        while let Some(hint) = s.hint() {
+            s.apply_hint(&hint).unwrap();
+        }
+        assert!(s.is_valid());
+        puzzle.grade();
+    }
+}
@@ -0,0 +1,270 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::variants::Sandwich;
+use crate::{symbol, House, Pos, Sudoku};
+
+/// The eight basic terminal colors, plus the terminal's own default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Default,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    // SGR foreground code.
+    fn code(self) -> u8 {
+        match self {
+            Color::Default => 39,
+            c => 30 + c as u8 - 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnsiOptions {
+    /// Givens are also drawn bold.
+    pub given: Color,
+    pub entry: Color,
+    /// Used for every value that clashes with another in one of its houses.
+    pub conflict: Color,
+    /// Draws each cell as a small grid with the pencil marks of empty cells,
+    /// dimmed, in place of blanks.
+    pub candidates: bool,
+}
+
+impl Default for AnsiOptions {
+    fn default() -> Self {
+        Self {
+            given: Color::Default,
+            entry: Color::Blue,
+            conflict: Color::Red,
+            candidates: false,
+        }
+    }
+}
+
+impl Sudoku {
+    /// The `Display` grid with escape codes for a color terminal.
+    pub fn render_ansi(&self, options: AnsiOptions) -> String {
+        let n = self.size().box_size();
+        let side = self.size().side();
+        let conflicts = self
+            .conflicts()
+            .into_iter()
+            .flat_map(|(a, b)| [a, b])
+            .collect::<Vec<_>>();
+        // With candidates, each cell is `n` characters wide and `n` lines tall
+        // and neighbouring cells are a space apart.
+        let (width, gap) = if options.candidates { (n, 1) } else { (1, 0) };
+        let mut separator = String::from("+");
+        for _ in 0..n {
+            for _ in 0..(n * width + (n - 1) * gap) {
+                separator.push('-');
+            }
+            separator.push('+');
+        }
+        let mut out = String::new();
+        for y in 0..side {
+            if y % n == 0 {
+                out.push_str(&separator);
+                out.push('\n');
+            }
+            for line in 0..width {
+                for x in 0..side {
+                    if x % n == 0 {
+                        out.push('|');
+                    } else if gap == 1 {
+                        out.push(' ');
+                    }
+                    let pos = Pos::new_unchecked(x, y);
+                    for column in 0..width {
+                        self.render_spot(&mut out, pos, (line, column), &options, &conflicts);
+                    }
+                }
+                out.push_str("|\n");
+            }
+        }
+        out.push_str(&separator);
+        out
+    }
+
+    // One character of a cell: its value, or with candidates shown, the
+    // spot for value `line * n + column + 1`.
+    fn render_spot(
+        &self,
+        out: &mut String,
+        pos: Pos,
+        (line, column): (u8, u8),
+        options: &AnsiOptions,
+        conflicts: &[Pos],
+    ) {
+        let cell = &self[pos];
+        let n = self.size().box_size();
+        let middle = !options.candidates || (line == n / 2 && column == n / 2);
+        match cell.value() {
+            Some(v) if middle => {
+                let (color, bold) = if conflicts.contains(&pos) {
+                    (options.conflict, cell.is_given())
+                } else if cell.is_given() {
+                    (options.given, true)
+                } else {
+                    (options.entry, false)
+                };
+                let bold = if bold { "1;" } else { "" };
+                let _ = write!(out, "\x1b[{bold}{}m{}\x1b[0m", color.code(), symbol(v));
+            }
+            None if options.candidates && cell.pencil_marks().contains(line * n + column + 1) => {
+                let _ = write!(out, "\x1b[2m{}\x1b[0m", symbol(line * n + column + 1));
+            }
+            None if !options.candidates => out.push('.'),
+            _ => out.push(' '),
+        }
+    }
+}
+
+impl Sandwich {
+    /// The board's ANSI grid with the column clues stacked digit by digit
+    /// above it and the row clues to its left.
+    pub fn render_ansi(&self, options: AnsiOptions) -> String {
+        let board = self.board();
+        let n = board.size().box_size() as usize;
+        let side = board.size().side();
+        let clue = |house| self.clue_for(house).map(|sum| alloc::format!("{sum}"));
+        let rows = (0..side).map(|y| clue(House::Row(y))).collect::<Vec<_>>();
+        let columns = (0..side)
+            .map(|x| clue(House::Column(x)))
+            .collect::<Vec<_>>();
+        let left = rows
+            .iter()
+            .flatten()
+            .map(String::len)
+            .max()
+            .map_or(0, |w| w + 1);
+        let tall = columns.iter().flatten().map(String::len).max().unwrap_or(0);
+        let (width, gap) = if options.candidates { (n, 1) } else { (1, 0) };
+        // Characters from the start of one box to the next.
+        let stack = n * width + (n - 1) * gap + 1;
+        let mut out = String::new();
+        for digit in 0..tall {
+            let mut line = " ".repeat(left + 1 + n * stack);
+            for (x, column) in columns.iter().enumerate() {
+                // Clues sit over the middle of their column, bottom aligned.
+                let Some(c) = column.as_ref().and_then(|c| {
+                    (digit + c.len())
+                        .checked_sub(tall)
+                        .and_then(|i| c.chars().nth(i))
+                }) else {
+                    continue;
+                };
+                let at = left + 1 + x / n * stack + x % n * (width + gap) + width / 2;
+                line.replace_range(at..at + 1, c.encode_utf8(&mut [0; 4]));
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        let grid = board.render_ansi(options);
+        let mut y = 0;
+        let mut line_in_row = 0;
+        for line in grid.lines() {
+            if line.starts_with('+') {
+                let _ = write!(out, "{:left$}", "");
+            } else {
+                let middle = line_in_row == width / 2;
+                let label = rows[y].as_deref().filter(|_| middle).unwrap_or("");
+                let _ = write!(out, "{label:>0$}", left.saturating_sub(1));
+                out.push_str(if left > 0 { " " } else { "" });
+                line_in_row += 1;
+                if line_in_row == width {
+                    line_in_row = 0;
+                    y += 1;
+                }
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.pop();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn strip(s: &str) -> String {
+        let mut out = String::new();
+        let mut escape = false;
+        for c in s.chars() {
+            match c {
+                '\x1b' => escape = true,
+                'm' if escape => escape = false,
+                _ if !escape => out.push(c),
+                _ => {}
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn colors_givens_entries_and_conflicts() {
+        let mut s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let plain = s.render_ansi(AnsiOptions::default());
+        assert_eq!(strip(&plain), s.to_string());
+        assert!(plain.contains("\x1b[1;39m5\x1b[0m"));
+        s.set(Pos::new_unchecked(0, 0), Some(6)).unwrap();
+        s.set(Pos::new_unchecked(2, 0), Some(5)).unwrap();
+        let colored = s.render_ansi(AnsiOptions::default());
+        assert!(colored.contains("\x1b[34m6\x1b[0m"));
+        assert!(colored.contains("\x1b[31m5\x1b[0m"));
+        assert!(colored.contains("\x1b[1;31m5\x1b[0m"));
+    }
+
+    #[test]
+    fn puts_sandwich_clues_around_the_grid() {
+        let s = Sandwich::from_str("r1 35\nr9 4\nc2 0\nc9 12").unwrap();
+        let text = strip(&s.render_ansi(AnsiOptions::default()));
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "              1");
+        assert_eq!(lines[1], "     0        2");
+        assert_eq!(lines[2], "   +---+---+---+");
+        assert_eq!(lines[3], "35 |...|...|...|");
+        assert_eq!(lines[4], "   |...|...|...|");
+        assert_eq!(lines[13], " 4 |...|...|...|");
+        assert_eq!(lines.len(), 15);
+    }
+
+    #[test]
+    fn draws_candidates_in_small_grids() {
+        let mut s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        s.fill_all_candidates();
+        let options = AnsiOptions {
+            candidates: true,
+            ..AnsiOptions::default()
+        };
+        let text = strip(&s.render_ansi(options));
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 9 * 3 + 4);
+        assert_eq!(lines[0].len(), 1 + 3 * (3 * 3 + 2 + 1));
+        // r1c1 can be 2, 6 or 9; r1c2 is the given 5.
+        assert_eq!(&lines[1][..8], "| 2     ");
+        assert_eq!(&lines[2][..8], "|  6  5 ");
+        assert_eq!(&lines[3][..8], "|  9    ");
+    }
+}
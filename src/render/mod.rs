@@ -0,0 +1,8 @@
+//! Ways of drawing a board beyond its plain `Display` grid.
+
+mod ansi;
+pub mod pdf;
+mod svg;
+
+pub use ansi::{AnsiOptions, Color};
+pub use svg::SvgOptions;
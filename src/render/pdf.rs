@@ -0,0 +1,283 @@
+//! Printable puzzle books. [`PuzzleSheet`] lays out puzzles on A4 pages and
+//! writes a self-contained PDF using only the standard Helvetica font.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::generator::Difficulty;
+use crate::{symbol, Sudoku};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 40.0;
+const HEADER: f32 = 40.0;
+const LABEL: f32 = 18.0;
+
+#[derive(Debug, Clone)]
+struct Entry {
+    title: String,
+    puzzle: Sudoku,
+    solution: Option<Sudoku>,
+    difficulty: Option<Difficulty>,
+}
+
+/// Puzzles laid out several to a page, each with a numbered title and its
+/// difficulty, followed by pages of answers.
+#[derive(Debug, Clone)]
+pub struct PuzzleSheet {
+    title: String,
+    per_page: usize,
+    answers: bool,
+    entries: Vec<Entry>,
+}
+
+impl PuzzleSheet {
+    /// Four puzzles per page with answers.
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.into(),
+            per_page: 4,
+            answers: true,
+            entries: Vec::new(),
+        }
+    }
+    /// Clamped to at least one.
+    pub fn per_page(mut self, n: usize) -> Self {
+        self.per_page = n.max(1);
+        self
+    }
+    pub fn answers(mut self, answers: bool) -> Self {
+        self.answers = answers;
+        self
+    }
+    /// Adds a puzzle, grading it and solving it for the answers pages.
+    /// Puzzles without a solution are printed with no answer.
+    pub fn add(&mut self, puzzle: Sudoku) -> &mut Self {
+        let title = format!("Puzzle {}", self.entries.len() + 1);
+        let difficulty = puzzle.grade();
+        self.add_titled(&title, puzzle, Some(difficulty))
+    }
+    pub fn add_titled(
+        &mut self,
+        title: &str,
+        puzzle: Sudoku,
+        difficulty: Option<Difficulty>,
+    ) -> &mut Self {
+        let solution = puzzle.solve().ok();
+        self.entries.push(Entry {
+            title: title.into(),
+            puzzle,
+            solution,
+            difficulty,
+        });
+        self
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    pub fn page_count(&self) -> usize {
+        let pages = self.entries.len().div_ceil(self.per_page).max(1);
+        if self.answers && !self.entries.is_empty() {
+            pages * 2
+        } else {
+            pages
+        }
+    }
+
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut pages = Vec::new();
+        let chunks = self.entries.chunks(self.per_page);
+        if self.entries.is_empty() {
+            pages.push(self.page(&self.title, &[], false));
+        }
+        for chunk in chunks.clone() {
+            pages.push(self.page(&self.title, chunk, false));
+        }
+        if self.answers {
+            let title = format!("{} - Answers", self.title);
+            for chunk in chunks {
+                pages.push(self.page(&title, chunk, true));
+            }
+        }
+        write_document(&pages)
+    }
+
+    // The content stream of one page.
+    fn page(&self, title: &str, entries: &[Entry], answers: bool) -> String {
+        let mut out = String::new();
+        text(&mut out, MARGIN, PAGE_HEIGHT - MARGIN - 18.0, 18.0, title);
+        let columns = if self.per_page == 1 { 1 } else { 2 };
+        let rows = self.per_page.div_ceil(columns);
+        let width = (PAGE_WIDTH - 2.0 * MARGIN) / columns as f32;
+        let height = (PAGE_HEIGHT - 2.0 * MARGIN - HEADER) / rows as f32;
+        let side = (width - 20.0).min(height - LABEL - 20.0);
+        for (i, entry) in entries.iter().enumerate() {
+            let left = MARGIN + (i % columns) as f32 * width + (width - side) / 2.0;
+            let top = PAGE_HEIGHT - MARGIN - HEADER - (i / columns) as f32 * height;
+            let label = match entry.difficulty {
+                Some(d) => format!("{} ({:?})", entry.title, d),
+                None => entry.title.clone(),
+            };
+            text(&mut out, left, top - 12.0, 11.0, &label);
+            let board = if answers {
+                match &entry.solution {
+                    Some(solution) => solution,
+                    None => {
+                        text(&mut out, left, top - 30.0, 11.0, "No solution.");
+                        continue;
+                    }
+                }
+            } else {
+                &entry.puzzle
+            };
+            grid(&mut out, board, left, top - LABEL, side);
+        }
+        out
+    }
+}
+
+// Draws `board` with its top left corner at (`left`, `top`).
+fn grid(out: &mut String, board: &Sudoku, left: f32, top: f32, side: f32) {
+    let n = board.size().box_size() as usize;
+    let count = board.size().side() as usize;
+    let cell = side / count as f32;
+    for i in 0..=count {
+        let width = if i % n == 0 { 1.5 } else { 0.5 };
+        let at = i as f32 * cell;
+        let _ = writeln!(
+            out,
+            "{width} w {:.2} {:.2} m {:.2} {:.2} l S {:.2} {:.2} m {:.2} {:.2} l S",
+            left + at,
+            top,
+            left + at,
+            top - side,
+            left,
+            top - at,
+            left + side,
+            top - at
+        );
+    }
+    let size = cell * 0.6;
+    for c in board.iter() {
+        if let Some(v) = c.value() {
+            let pos = c.position();
+            // Helvetica digits and capitals are a little over half an em wide.
+            let x = left + (pos.x() as f32 + 0.5) * cell - size * 0.28;
+            let y = top - (pos.y() as f32 + 0.5) * cell - size * 0.35;
+            let mut s = [0; 4];
+            text(out, x, y, size, symbol(v).encode_utf8(&mut s));
+        }
+    }
+}
+
+fn text(out: &mut String, x: f32, y: f32, size: f32, s: &str) {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+    let _ = writeln!(
+        out,
+        "BT /F1 {size:.1} Tf {x:.2} {y:.2} Td ({escaped}) Tj ET"
+    );
+}
+
+// Objects 1 to 3 are the catalog, the page tree and the font; each page is
+// then a page object followed by its content stream.
+fn write_document(pages: &[String]) -> Vec<u8> {
+    let mut objects = Vec::new();
+    objects.push(String::from("<< /Type /Catalog /Pages 2 0 R >>"));
+    let kids = (0..pages.len())
+        .map(|i| format!("{} 0 R", 4 + 2 * i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    objects.push(format!(
+        "<< /Type /Pages /Kids [{kids}] /Count {} >>",
+        pages.len()
+    ));
+    objects.push(String::from(
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>",
+    ));
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+             /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            5 + 2 * i
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{content}endstream",
+            content.len()
+        ));
+    }
+
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        let _ = write!(out, "{} 0 obj\n{object}\nendobj\n", i + 1);
+    }
+    let xref = out.len();
+    let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(out, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        out,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    );
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn writes_a_page_per_chunk_plus_answers() {
+        let puzzle = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let mut sheet = PuzzleSheet::new("Weekly (no. 1)").per_page(2);
+        for _ in 0..3 {
+            sheet.add(puzzle.clone());
+        }
+        assert_eq!(sheet.page_count(), 4);
+        let pdf = String::from_utf8(sheet.to_pdf()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n") && pdf.ends_with("%%EOF\n"));
+        assert_eq!(pdf.matches("/Type /Page ").count(), 4);
+        assert!(pdf.contains("(Weekly \\(no. 1\\))"));
+        assert!(pdf.contains("(Puzzle 3 \\(Easy\\))"));
+
+        // Every xref entry points at the start of its object.
+        let start = pdf.find("xref\n").unwrap();
+        for (i, line) in pdf[start..].lines().skip(3).take(11).enumerate() {
+            let offset = line[..10].parse::<usize>().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn answers_can_be_left_out() {
+        let mut sheet = PuzzleSheet::new("Empty").answers(false);
+        assert_eq!(sheet.page_count(), 1);
+        sheet.add_titled("Blank", Sudoku::new(crate::Size::CLASSIC), None);
+        let pdf = String::from_utf8(sheet.to_pdf()).unwrap();
+        assert_eq!(pdf.matches("/Type /Page ").count(), 1);
+        assert!(!pdf.contains("Answers"));
+    }
+}
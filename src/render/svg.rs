@@ -0,0 +1,340 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::variants::{Cage, GreaterThan, Inequality, Killer, Sandwich, SandwichClue};
+use crate::{symbol, House, Sudoku};
+
+/// Sizes are in SVG user units; colors are any SVG color.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SvgOptions {
+    pub cell_size: u32,
+    pub margin: u32,
+    pub font_family: String,
+    pub given_color: String,
+    pub entry_color: String,
+    pub candidate_color: String,
+    pub line_color: String,
+    pub cage_color: String,
+    pub background: String,
+    /// Draws the pencil marks of empty cells.
+    pub candidates: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 40,
+            margin: 10,
+            font_family: "sans-serif".into(),
+            given_color: "black".into(),
+            entry_color: "#1f5fbf".into(),
+            candidate_color: "#666".into(),
+            line_color: "black".into(),
+            cage_color: "#444".into(),
+            background: "white".into(),
+            candidates: false,
+        }
+    }
+}
+
+impl Sudoku {
+    pub fn render_svg(&self, options: &SvgOptions) -> String {
+        render(self, &Marks::default(), options)
+    }
+}
+
+impl Killer {
+    /// The board with each cage outlined by a dashed line and its sum in
+    /// the corner of its top-left cell.
+    pub fn render_svg(&self, options: &SvgOptions) -> String {
+        let marks = Marks {
+            cages: self.cages(),
+            ..Marks::default()
+        };
+        render(self.board(), &marks, options)
+    }
+}
+
+impl Sandwich {
+    /// The board with a spare cell's width above and to its left, holding
+    /// the column and row clues.
+    pub fn render_svg(&self, options: &SvgOptions) -> String {
+        let marks = Marks {
+            clues: self.clues(),
+            ..Marks::default()
+        };
+        render(self.board(), &marks, options)
+    }
+}
+
+impl GreaterThan {
+    /// The board with a chevron on each inequality's edge, pointing at the
+    /// smaller value.
+    pub fn render_svg(&self, options: &SvgOptions) -> String {
+        let marks = Marks {
+            inequalities: self.inequalities(),
+            ..Marks::default()
+        };
+        render(self.board(), &marks, options)
+    }
+}
+
+// What a variant draws on top of the plain board.
+#[derive(Default)]
+struct Marks<'a> {
+    cages: &'a [Cage],
+    clues: &'a [SandwichClue],
+    inequalities: &'a [Inequality],
+}
+
+// `write!` into a `String` can't fail.
+fn render(board: &Sudoku, marks: &Marks, options: &SvgOptions) -> String {
+    let clues = marks.clues;
+    let n = board.size().box_size() as u32;
+    let side = board.size().side() as u32;
+    let cell = options.cell_size;
+    // The grid's top left corner, past the margin and any clues.
+    let m = options.margin + if clues.is_empty() { 0 } else { cell };
+    let total = side * cell + options.margin + m;
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{total}" height="{total}" viewBox="0 0 {total} {total}">"#
+    );
+    let _ = writeln!(
+        out,
+        r#"<rect width="{total}" height="{total}" fill="{}"/>"#,
+        options.background
+    );
+
+    for cage in marks.cages {
+        draw_cage(&mut out, board, cage, m, options);
+    }
+    for clue in clues {
+        let (x, y) = match clue.house {
+            House::Column(x) => (m + x as u32 * cell + cell / 2, m - cell / 2),
+            House::Row(y) => (m - cell / 2, m + y as u32 * cell + cell / 2),
+            _ => continue,
+        };
+        let _ = writeln!(
+            out,
+            r#"<text x="{x}" y="{y}" font-family="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            options.font_family,
+            cell * 2 / 5,
+            options.given_color,
+            clue.sum
+        );
+    }
+
+    for c in board.iter() {
+        let pos = c.position();
+        let (x, y) = (m + pos.x() as u32 * cell, m + pos.y() as u32 * cell);
+        match c.value() {
+            Some(v) => {
+                let color = if c.is_given() {
+                    &options.given_color
+                } else {
+                    &options.entry_color
+                };
+                let _ = writeln!(
+                    out,
+                    r#"<text x="{}" y="{}" font-family="{}" font-size="{}" fill="{color}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                    x + cell / 2,
+                    y + cell / 2,
+                    options.font_family,
+                    cell * 3 / 5,
+                    symbol(v)
+                );
+            }
+            None if options.candidates => {
+                let small = cell / n;
+                for v in c.pencil_marks().iter() {
+                    let i = v as u32 - 1;
+                    let _ = writeln!(
+                        out,
+                        r#"<text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                        x + (i % n) * small + small / 2,
+                        y + (i / n) * small + small / 2,
+                        options.font_family,
+                        small * 3 / 4,
+                        options.candidate_color,
+                        symbol(v)
+                    );
+                }
+            }
+            None => {}
+        }
+    }
+
+    for i in 0..=side {
+        let width = if i % n == 0 { 3 } else { 1 };
+        let at = m + i * cell;
+        let (start, end) = (m, m + side * cell);
+        let _ = writeln!(
+            out,
+            r#"<line x1="{at}" y1="{start}" x2="{at}" y2="{end}" stroke="{}" stroke-width="{width}"/>"#,
+            options.line_color
+        );
+        let _ = writeln!(
+            out,
+            r#"<line x1="{start}" y1="{at}" x2="{end}" y2="{at}" stroke="{}" stroke-width="{width}"/>"#,
+            options.line_color
+        );
+    }
+    for i in marks.inequalities {
+        draw_inequality(&mut out, i, m, options);
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+// A chevron across the middle of the edge between the two cells, its
+// point towards `less`.
+fn draw_inequality(out: &mut String, i: &Inequality, m: u32, options: &SvgOptions) {
+    let cell = options.cell_size as i32;
+    let center = |p: crate::Pos| {
+        (
+            m as i32 + p.x() as i32 * cell + cell / 2,
+            m as i32 + p.y() as i32 * cell + cell / 2,
+        )
+    };
+    let (g, l) = (center(i.greater), center(i.less));
+    // A unit step towards the smaller cell.
+    let (dx, dy) = ((l.0 - g.0).signum(), (l.1 - g.1).signum());
+    let (mx, my) = ((g.0 + l.0) / 2, (g.1 + l.1) / 2);
+    let arm = cell / 8;
+    let tip = (mx + dx * arm / 2, my + dy * arm / 2);
+    let back = (mx - dx * arm / 2, my - dy * arm / 2);
+    let _ = writeln!(
+        out,
+        r#"<polyline points="{},{} {},{} {},{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+        back.0 - dy * arm,
+        back.1 - dx * arm,
+        tip.0,
+        tip.1,
+        back.0 + dy * arm,
+        back.1 + dx * arm,
+        options.line_color
+    );
+}
+
+// Dashed lines a little inside every cell edge that borders another cage.
+fn draw_cage(out: &mut String, board: &Sudoku, cage: &Cage, m: u32, options: &SvgOptions) {
+    let cell = options.cell_size;
+    let inset = cell / 10;
+    let inside = |x: i32, y: i32| {
+        x >= 0
+            && y >= 0
+            && cage
+                .cells
+                .iter()
+                .any(|p| (p.x() as i32, p.y() as i32) == (x, y))
+    };
+    for p in &cage.cells {
+        let (x, y) = (p.x() as i32, p.y() as i32);
+        let left = m + p.x() as u32 * cell;
+        let top = m + p.y() as u32 * cell;
+        let (l, t, r, b) = (
+            left + inset,
+            top + inset,
+            left + cell - inset,
+            top + cell - inset,
+        );
+        let mut edges = Vec::new();
+        if !inside(x, y - 1) {
+            edges.push((l, t, r, t));
+        }
+        if !inside(x, y + 1) {
+            edges.push((l, b, r, b));
+        }
+        if !inside(x - 1, y) {
+            edges.push((l, t, l, b));
+        }
+        if !inside(x + 1, y) {
+            edges.push((r, t, r, b));
+        }
+        for (x1, y1, x2, y2) in edges {
+            let _ = writeln!(
+                out,
+                r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{}" stroke-width="1" stroke-dasharray="3,2"/>"#,
+                options.cage_color
+            );
+        }
+    }
+    let size = board.size();
+    if let Some(first) = cage.cells.iter().min_by_key(|&&p| size.index_of(p)) {
+        let _ = writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}">{}</text>"#,
+            m + first.x() as u32 * cell + inset + 1,
+            m + first.y() as u32 * cell + inset + cell / 4,
+            options.font_family,
+            cell / 4,
+            options.cage_color,
+            cage.sum
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn draws_values_and_grid() {
+        let mut s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let givens = s.iter().filter(|c| c.is_given()).count();
+        let svg = s.render_svg(&SvgOptions::default());
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert!(svg.contains(r#"width="380""#));
+        assert_eq!(svg.matches("<line").count(), 20);
+        assert_eq!(svg.matches("<text").count(), givens);
+        s.fill_all_candidates();
+        let options = SvgOptions {
+            candidates: true,
+            ..SvgOptions::default()
+        };
+        let marks = s.iter().map(|c| c.pencil_marks().count()).sum::<usize>();
+        let svg = s.render_svg(&options);
+        assert_eq!(svg.matches("<text").count(), givens + marks);
+    }
+
+    #[test]
+    fn outlines_killer_cages() {
+        let k = Killer::from_str("3 r1c1 r1c2\n12 r2c1 r3c1 r3c2").unwrap();
+        let svg = k.render_svg(&SvgOptions::default());
+        // Two cells with three open sides each, plus an L of three cells.
+        assert_eq!(svg.matches("stroke-dasharray").count(), 6 + 8);
+        assert!(svg.contains(">3</text>") && svg.contains(">12</text>"));
+    }
+
+    #[test]
+    fn points_inequalities_at_the_smaller_cell() {
+        let g = GreaterThan::from_str("r1c1 > r1c2\nr2c1 < r1c1").unwrap();
+        let svg = g.render_svg(&SvgOptions::default());
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        // Between r1c1 and r1c2 pointing right, and below r1c1 pointing down.
+        assert!(svg.contains(r#"points="48,25 52,30 48,35""#));
+        assert!(svg.contains(r#"points="25,48 30,52 35,48""#));
+    }
+
+    #[test]
+    fn draws_sandwich_clues_outside_the_grid() {
+        let s = Sandwich::from_str(
+            "r1 35
+c2 0",
+        )
+        .unwrap();
+        let svg = s.render_svg(&SvgOptions::default());
+        assert!(svg.contains(r#"width="420""#));
+        assert!(svg.contains(r#"<text x="30" y="70" "#) && svg.contains(">35</text>"));
+        assert!(svg.contains(r#"<text x="110" y="30" "#) && svg.contains(">0</text>"));
+        assert!(svg.contains(r#"<line x1="50" y1="50" x2="50" y2="410""#));
+    }
+}
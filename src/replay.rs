@@ -0,0 +1,323 @@
+//! Logical solves recorded step by step, saved as JSON and played back onto
+//! a board one deduction at a time.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::hints::CandidateGrid;
+use crate::json::Json;
+use crate::{CandidateMode, Hint, LogicalSolver, ParseError, Pos, Sudoku, SudokuError, Technique};
+
+/// What one deduction of a solve changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayStep {
+    pub technique: Technique,
+    pub placement: Option<(Pos, u8)>,
+    pub eliminations: Vec<(Pos, u8)>,
+}
+
+impl From<&Hint> for ReplayStep {
+    fn from(hint: &Hint) -> Self {
+        Self {
+            technique: hint.technique,
+            placement: hint.placement,
+            eliminations: hint.eliminations.clone(),
+        }
+    }
+}
+
+/// The steps of a logical solve, from the puzzle they start on. Every path
+/// can be played back, so paths only come from a solver or from JSON that
+/// checks out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolvePath {
+    puzzle: Sudoku,
+    steps: Vec<ReplayStep>,
+    solved: bool,
+}
+
+impl SolvePath {
+    pub fn puzzle(&self) -> &Sudoku {
+        &self.puzzle
+    }
+    pub fn steps(&self) -> &[ReplayStep] {
+        &self.steps
+    }
+    /// False if the solver got stuck before the board was complete.
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+    pub fn replay(&self) -> Replay {
+        Replay::new(self.clone())
+    }
+
+    /// Only the puzzle's values are saved, so a path read back plays on a
+    /// board with classic rules.
+    pub fn to_json(&self) -> String {
+        let steps = self.steps.iter().map(|step| {
+            let mut fields = vec![(
+                "technique".into(),
+                alloc::format!("{:?}", step.technique).as_str().into(),
+            )];
+            if let Some(placement) = step.placement {
+                fields.push(("placement".into(), cell_json(placement)));
+            }
+            fields.push((
+                "eliminations".into(),
+                Json::Array(step.eliminations.iter().copied().map(cell_json).collect()),
+            ));
+            Json::Object(fields)
+        });
+        Json::Object(vec![
+            (
+                "puzzle".into(),
+                self.puzzle.to_line_string().as_str().into(),
+            ),
+            ("solved".into(), Json::Bool(self.solved)),
+            ("steps".into(), Json::Array(steps.collect())),
+        ])
+        .to_string()
+    }
+    /// Fails on malformed JSON and on steps that can't be played on the
+    /// puzzle, like a placement on a filled cell.
+    pub fn from_json(input: &str) -> Result<Self, SudokuError> {
+        let json = Json::parse(input)?;
+        let puzzle = json
+            .get("puzzle")
+            .and_then(Json::as_str)
+            .ok_or_else(invalid)?
+            .parse::<Sudoku>()?;
+        let mut steps = Vec::new();
+        for step in json
+            .get("steps")
+            .and_then(Json::as_array)
+            .ok_or_else(invalid)?
+        {
+            let name = step.get("technique").and_then(Json::as_str);
+            let technique = Technique::ALL
+                .into_iter()
+                .find(|t| Some(alloc::format!("{t:?}").as_str()) == name)
+                .ok_or_else(invalid)?;
+            let placement = step.get("placement").map(parse_cell).transpose()?;
+            let eliminations = step
+                .get("eliminations")
+                .and_then(Json::as_array)
+                .unwrap_or_default()
+                .iter()
+                .map(parse_cell)
+                .collect::<Result<_, _>>()?;
+            steps.push(ReplayStep {
+                technique,
+                placement,
+                eliminations,
+            });
+        }
+        let path = Self {
+            puzzle,
+            steps,
+            solved: json.get("solved").and_then(Json::as_bool).unwrap_or(false),
+        };
+        let mut replay = path.replay();
+        while replay.try_step()?.is_some() {}
+        Ok(path)
+    }
+}
+
+fn invalid() -> SudokuError {
+    SudokuError::ParseError(ParseError::Invalid("Invalid solve path."))
+}
+
+// Cells are `{"x": 0, "y": 0, "value": 5}`, counted from 0 like `Pos`.
+fn cell_json((pos, value): (Pos, u8)) -> Json {
+    Json::Object(vec![
+        ("x".into(), u64::from(pos.x()).into()),
+        ("y".into(), u64::from(pos.y()).into()),
+        ("value".into(), u64::from(value).into()),
+    ])
+}
+
+fn parse_cell(json: &Json) -> Result<(Pos, u8), SudokuError> {
+    let field = |key| {
+        json.get(key)
+            .and_then(Json::as_u64)
+            .and_then(|n| u8::try_from(n).ok())
+            .ok_or_else(invalid)
+    };
+    Ok((
+        Pos::new_unchecked(field("x")?, field("y")?),
+        field("value")?,
+    ))
+}
+
+/// Plays a [`SolvePath`] back onto its puzzle. The board keeps its pencil
+/// marks in step, so eliminations show up as marks disappearing.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    path: SolvePath,
+    board: Sudoku,
+    next: usize,
+}
+
+impl Replay {
+    pub fn new(path: SolvePath) -> Self {
+        Self {
+            board: start(&path.puzzle),
+            path,
+            next: 0,
+        }
+    }
+    pub fn board(&self) -> &Sudoku {
+        &self.board
+    }
+    pub fn path(&self) -> &SolvePath {
+        &self.path
+    }
+    /// How many steps have been played so far.
+    pub fn position(&self) -> usize {
+        self.next
+    }
+    pub fn is_finished(&self) -> bool {
+        self.next == self.path.steps.len()
+    }
+    /// Plays the next step and returns it, or `None` once every step has
+    /// been played.
+    pub fn step(&mut self) -> Option<&ReplayStep> {
+        self.try_step()
+            .expect("solve paths are checked when they are made")
+    }
+    /// Back to the puzzle as it was before the first step.
+    pub fn rewind(&mut self) {
+        self.board = start(&self.path.puzzle);
+        self.next = 0;
+    }
+
+    fn try_step(&mut self) -> Result<Option<&ReplayStep>, SudokuError> {
+        let Some(step) = self.path.steps.get(self.next) else {
+            return Ok(None);
+        };
+        for &(pos, v) in &step.eliminations {
+            self.board.check(pos, Some(v))?;
+            if self.board[pos].pencil_marks().contains(v) {
+                self.board.toggle_candidate(pos, v)?;
+            }
+        }
+        if let Some((pos, v)) = step.placement {
+            self.board.check(pos, Some(v))?;
+            if self.board[pos].value().is_some() {
+                return Err(invalid());
+            }
+            self.board.set_checked(pos, Some(v))?;
+        }
+        self.next += 1;
+        Ok(Some(step))
+    }
+}
+
+// The puzzle with every candidate pencilled in, clearing them from a
+// placement's houses as the replay goes.
+fn start(puzzle: &Sudoku) -> Sudoku {
+    let mut board = puzzle.clone();
+    board.set_candidate_mode(CandidateMode::AutoRemove);
+    board.fill_all_candidates();
+    board
+}
+
+impl LogicalSolver {
+    /// Solves as far as the solver's techniques allow, recording what each
+    /// step placed and eliminated.
+    pub fn solve_path(&self, sudoku: &Sudoku) -> SolvePath {
+        let mut grid = CandidateGrid::from_sudoku(sudoku);
+        let mut steps = Vec::new();
+        while let Some(hint) = grid.next_hint(self.techniques()) {
+            steps.push(ReplayStep::from(&hint));
+            grid.apply(&hint);
+        }
+        SolvePath {
+            puzzle: sudoku.clone(),
+            steps,
+            solved: grid.is_complete(),
+        }
+    }
+}
+
+impl Sudoku {
+    /// Solves with every technique the crate knows. See
+    /// [`LogicalSolver::solve_path`].
+    pub fn solve_path(&self) -> SolvePath {
+        LogicalSolver::default().solve_path(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn replays_a_solve() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let path = s.solve_path();
+        assert!(path.is_solved());
+        assert_eq!(
+            path.steps().len(),
+            LogicalSolver::default().solve_log(&s).techniques.len()
+        );
+        let mut replay = path.replay();
+        let first = replay.step().unwrap().clone();
+        let (pos, v) = first.placement.unwrap();
+        assert_eq!(replay.board()[pos].value(), Some(v));
+        assert!(pos
+            .peers()
+            .iter()
+            .all(|&p| !replay.board()[p].pencil_marks().contains(v)));
+        while replay.step().is_some() {}
+        assert!(replay.is_finished());
+        assert_eq!(replay.position(), path.steps().len());
+        assert_eq!(replay.board(), &s.solve().unwrap());
+        replay.rewind();
+        assert_eq!(replay.position(), 0);
+        assert_eq!(replay.board().to_line_string(), s.to_line_string());
+    }
+
+    #[test]
+    fn eliminations_clear_pencil_marks() {
+        // Box 0 only allows 1 in row 0, so a pointing pair clears it from
+        // the rest of the row.
+        let mut line = String::from(".........234......567");
+        line.push_str(&".".repeat(60));
+        let s = Sudoku::from_str(&line).unwrap();
+        let path = crate::SolverBuilder::new()
+            .with(Technique::PointingPair)
+            .build()
+            .solve_path(&s);
+        assert!(!path.is_solved());
+        let mut replay = path.replay();
+        let target = Pos::new_unchecked(5, 0);
+        assert!(replay.board()[target].pencil_marks().contains(1));
+        while let Some(step) = replay.step() {
+            assert!(step.placement.is_none());
+        }
+        assert!(!replay.board()[target].pencil_marks().contains(1));
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let s = Sudoku::generate_with_seed(9, crate::Difficulty::Hard);
+        let path = s.solve_path();
+        let json = path.to_json();
+        assert!(json.starts_with(r#"{"puzzle":""#));
+        assert_eq!(SolvePath::from_json(&json), Ok(path));
+
+        let placed = r#"{"puzzle":"1","steps":[{"technique":"NakedSingle","placement":{"x":0,"y":0,"value":1},"eliminations":[]}]}"#;
+        let placed = placed.replace(r#""1""#, &alloc::format!("\"1{}\"", ".".repeat(80)));
+        assert_eq!(SolvePath::from_json(&placed), Err(invalid()));
+        let unknown = r#"{"puzzle":"","steps":[{"technique":"Guess"}]}"#;
+        assert!(SolvePath::from_json(unknown).is_err());
+        assert!(SolvePath::from_json("[]").is_err());
+    }
+}
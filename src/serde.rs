@@ -0,0 +1,185 @@
+//! `Serialize` and `Deserialize` for `Pos`, `Cell` and `Sudoku`. A board is
+//! its line string, as `to_line_string` writes it; put
+//! `#[serde(with = "sudoku::serde::grid")]` on a field to store it as rows
+//! of `Option<u8>` instead. Either way only the values are kept, and filled
+//! cells come back as givens.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Candidates, Cell, Pos, Size, Sudoku};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Pos")]
+struct PosRepr {
+    x: u8,
+    y: u8,
+}
+
+impl Serialize for Pos {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PosRepr {
+            x: self.x(),
+            y: self.y(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pos {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let PosRepr { x, y } = PosRepr::deserialize(deserializer)?;
+        Pos::try_new(x, y).map_err(D::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Cell")]
+struct CellRepr {
+    position: Pos,
+    value: Option<u8>,
+    given: bool,
+    pencil_marks: Vec<u8>,
+}
+
+impl Serialize for Cell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CellRepr {
+            position: self.position(),
+            value: self.value(),
+            given: self.is_given(),
+            pencil_marks: self.pencil_marks().iter().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cell {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = CellRepr::deserialize(deserializer)?;
+        let mut cell = Cell::try_new(repr.value, repr.position).map_err(D::Error::custom)?;
+        cell.given = repr.given && repr.value.is_some();
+        let mut marks = Candidates::empty();
+        for v in repr.pencil_marks {
+            if !(1..=25).contains(&v) {
+                return Err(D::Error::custom(crate::SudokuError::InvalidValue(v)));
+            }
+            marks.insert(v);
+        }
+        cell.set_pencil_marks(marks);
+        Ok(cell)
+    }
+}
+
+impl Serialize for Sudoku {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_line_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Sudoku {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// A board as rows of `Option<u8>`, `null` for an empty cell, for use with
+/// `#[serde(with = "sudoku::serde::grid")]`.
+pub mod grid {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(board: &Sudoku, serializer: S) -> Result<S::Ok, S::Error> {
+        let side = board.size().side() as usize;
+        let cells = board.iter().map(Cell::value).collect::<Vec<_>>();
+        cells.chunks(side).collect::<Vec<_>>().serialize(serializer)
+    }
+
+    /// Fails unless there are as many rows as columns, making a board size
+    /// the crate supports, and every value fits it.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Sudoku, D::Error> {
+        let rows = Vec::<Vec<Option<u8>>>::deserialize(deserializer)?;
+        let size = Size::from_cell_count(rows.len() * rows.len())
+            .filter(|size| rows.iter().all(|r| r.len() == size.side() as usize))
+            .ok_or_else(|| D::Error::custom(crate::SudokuError::InvalidSize))?;
+        let values = rows.into_iter().flatten().collect::<Vec<_>>();
+        if let Some(v) = values.iter().flatten().find(|&&v| !size.is_valid_value(v)) {
+            return Err(D::Error::custom(crate::SudokuError::InvalidValue(*v)));
+        }
+        Ok(Sudoku::from_values(size, values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    const PUZZLE: &str =
+        ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+
+    #[test]
+    fn round_trips_through_json() {
+        let board = Sudoku::from_str(PUZZLE).unwrap();
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(json, format!("\"{PUZZLE}\""));
+        assert_eq!(serde_json::from_str::<Sudoku>(&json).unwrap(), board);
+        assert!(serde_json::from_str::<Sudoku>("\"12\"").is_err());
+
+        let pos = Pos::new_unchecked(3, 7);
+        let json = serde_json::to_string(&pos).unwrap();
+        assert_eq!(json, r#"{"x":3,"y":7}"#);
+        assert_eq!(serde_json::from_str::<Pos>(&json).unwrap(), pos);
+        assert!(serde_json::from_str::<Pos>(r#"{"x":30,"y":0}"#).is_err());
+
+        let mut cell = board[Pos::new_unchecked(2, 0)].clone();
+        cell.set_pencil_marks(Candidates::from_bits(0b1010));
+        let json = serde_json::to_string(&cell).unwrap();
+        assert_eq!(
+            json,
+            r#"{"position":{"x":2,"y":0},"value":null,"given":false,"pencil_marks":[1,3]}"#
+        );
+        let back = serde_json::from_str::<Cell>(&json).unwrap();
+        assert_eq!(back.pencil_marks(), cell.pencil_marks());
+        assert_eq!(back, cell);
+        let given = &board[Pos::new_unchecked(1, 0)];
+        let back = serde_json::from_str::<Cell>(&serde_json::to_string(given).unwrap()).unwrap();
+        assert!(back.is_given() && back.value() == Some(5));
+    }
+
+    #[test]
+    fn grids_as_rows() {
+        #[derive(Serialize, Deserialize)]
+        struct Saved {
+            #[serde(with = "crate::serde::grid")]
+            board: Sudoku,
+        }
+        let board = Sudoku::from_str(PUZZLE).unwrap();
+        let json = serde_json::to_string(&Saved {
+            board: board.clone(),
+        })
+        .unwrap();
+        assert!(json.starts_with(r#"{"board":[[null,5,null,null,8,3,null,1,7],"#));
+        let back = serde_json::from_str::<Saved>(&json).unwrap();
+        assert_eq!(back.board, board);
+        assert!(back.board[Pos::new_unchecked(1, 0)].is_given());
+
+        let small = r#"{"board":[[1,null,null,null],[null,null,null,null],[null,null,null,null],[null,null,null,4]]}"#;
+        let back = serde_json::from_str::<Saved>(small).unwrap();
+        assert_eq!(back.board.size(), Size::new(2).unwrap());
+        for bad in [
+            r#"{"board":[[1,2],[3]]}"#,
+            r#"{"board":[[1,null,null],[null,null,null],[null,null,null]]}"#,
+            r#"{"board":[[5,null,null,null],[null,null,null,null],[null,null,null,null],[null,null,null,null]]}"#,
+        ] {
+            assert!(serde_json::from_str::<Saved>(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{Pos, Sudoku, SudokuError};
+
+/// A board several threads can hold at once, e.g. a solver thread writing
+/// while a render thread takes snapshots. Clones share the same board.
+#[derive(Debug, Clone)]
+pub struct SharedSudoku {
+    inner: Arc<RwLock<Sudoku>>,
+}
+
+impl SharedSudoku {
+    pub fn new(board: Sudoku) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(board)),
+        }
+    }
+    // A panic while holding the lock can't leave a board half-written in a
+    // way that breaks it, so poisoning is ignored.
+    pub fn read(&self) -> RwLockReadGuard<'_, Sudoku> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+    pub fn write(&self) -> RwLockWriteGuard<'_, Sudoku> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// A copy of the board as it is now.
+    pub fn snapshot(&self) -> Sudoku {
+        self.read().clone()
+    }
+    /// Runs `f` with the write lock held, so several changes land together.
+    pub fn update<T>(&self, f: impl FnOnce(&mut Sudoku) -> T) -> T {
+        f(&mut self.write())
+    }
+    pub fn set(&self, pos: Pos, value: Option<u8>) -> Result<(), SudokuError> {
+        self.write().set(pos, value)
+    }
+    pub fn set_forced(&self, pos: Pos, value: Option<u8>) -> Result<(), SudokuError> {
+        self.write().set_forced(pos, value)
+    }
+    pub fn toggle_candidate(&self, pos: Pos, value: u8) -> Result<(), SudokuError> {
+        self.write().toggle_candidate(pos, value)
+    }
+    pub fn fill_all_candidates(&self) {
+        self.write().fill_all_candidates()
+    }
+    pub fn reset(&self) {
+        self.write().reset()
+    }
+    pub fn is_solved(&self) -> bool {
+        self.read().is_solved()
+    }
+}
+
+impl From<Sudoku> for SharedSudoku {
+    fn from(board: Sudoku) -> Self {
+        Self::new(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::thread;
+
+    use super::*;
+    use crate::{Game, Size};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn boards_can_cross_threads() {
+        assert_send_sync::<Sudoku>();
+        assert_send_sync::<Game>();
+        assert_send_sync::<SharedSudoku>();
+    }
+
+    #[test]
+    fn writer_and_reader_threads_share_a_board() {
+        let puzzle = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let solution = puzzle.solve().unwrap();
+        let shared = SharedSudoku::new(puzzle);
+        let writer = {
+            let shared = shared.clone();
+            let solution = solution.clone();
+            thread::spawn(move || {
+                for pos in Size::CLASSIC.positions() {
+                    if shared.read()[pos].value().is_none() {
+                        shared.set(pos, solution[pos].value()).unwrap();
+                    }
+                }
+            })
+        };
+        let reader = {
+            let shared = shared.clone();
+            thread::spawn(move || (0..50).all(|_| shared.snapshot().is_valid()))
+        };
+        writer.join().unwrap();
+        assert!(reader.join().unwrap());
+        assert!(shared.is_solved());
+        assert_eq!(shared.snapshot(), solution);
+    }
+}
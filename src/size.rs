@@ -0,0 +1,137 @@
+use crate::{House, Pos, SudokuError};
+
+/// Largest side length any board can have.
+pub(crate) const MAX_SIDE: u8 = 25;
+
+/// The shape of a board made of `box_size` × `box_size` boxes, each holding
+/// `box_size²` cells. The classic 9x9 board has a box size of 3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Size {
+    box_size: u8,
+}
+
+impl Size {
+    pub const CLASSIC: Size = Size { box_size: 3 };
+
+    /// Box sizes 2 through 5 are supported (4x4 up to 25x25 boards).
+    pub fn new(box_size: u8) -> Result<Self, SudokuError> {
+        if !(2..=5).contains(&box_size) {
+            return Err(SudokuError::InvalidSize);
+        }
+        Ok(Self { box_size })
+    }
+    /// The size whose board has exactly `cells` cells.
+    pub fn from_cell_count(cells: usize) -> Option<Self> {
+        (2..=5u8)
+            .map(|box_size| Self { box_size })
+            .find(|s| s.cell_count() == cells)
+    }
+    pub fn box_size(self) -> u8 {
+        self.box_size
+    }
+    /// Cells per row, column and box; also the largest value a cell can hold.
+    pub fn side(self) -> u8 {
+        self.box_size * self.box_size
+    }
+    pub fn cell_count(self) -> usize {
+        self.side() as usize * self.side() as usize
+    }
+    pub fn contains(self, pos: Pos) -> bool {
+        pos.x() < self.side() && pos.y() < self.side()
+    }
+    pub fn is_valid_value(self, value: u8) -> bool {
+        (1..=self.side()).contains(&value)
+    }
+    /// Row-major index of `pos`. Panics if the position is off the board.
+    pub fn index_of(self, pos: Pos) -> usize {
+        if !self.contains(pos) {
+            panic!("{}", SudokuError::OutOfBounds);
+        }
+        pos.y() as usize * self.side() as usize + pos.x() as usize
+    }
+    pub fn pos_at(self, i: usize) -> Pos {
+        let side = self.side() as usize;
+        Pos::new_unchecked((i % side) as u8, (i / side) as u8)
+    }
+    /// Every position in row-major order.
+    pub fn positions(self) -> impl Iterator<Item = Pos> {
+        (0..self.cell_count()).map(move |i| self.pos_at(i))
+    }
+    pub fn box_of(self, pos: Pos) -> u8 {
+        (pos.y() / self.box_size) * self.box_size + pos.x() / self.box_size
+    }
+    /// Rows, then columns, then boxes.
+    pub fn houses(self) -> impl Iterator<Item = House> {
+        let side = self.side();
+        (0..side)
+            .map(House::Row)
+            .chain((0..side).map(House::Column))
+            .chain((0..side).map(House::Box))
+    }
+    pub fn houses_containing(self, pos: Pos) -> [House; 3] {
+        [
+            House::Row(pos.y()),
+            House::Column(pos.x()),
+            House::Box(self.box_of(pos)),
+        ]
+    }
+    pub fn house_positions(self, house: House) -> impl Iterator<Item = Pos> {
+        let n = self.box_size;
+        let last = self.side() - 1;
+        (0..self.side()).map(move |i| match house {
+            House::Row(y) => Pos::new_unchecked(i, y),
+            House::Column(x) => Pos::new_unchecked(x, i),
+            House::Box(b) => Pos::new_unchecked((b % n) * n + i % n, (b / n) * n + i / n),
+            House::Diagonal(0) => Pos::new_unchecked(i, i),
+            House::Diagonal(_) => Pos::new_unchecked(last - i, i),
+            House::Window(w) => {
+                let (wx, wy) = self.window_origin(w);
+                Pos::new_unchecked(wx + i % n, wy + i / n)
+            }
+        })
+    }
+    /// Windoku windows: box-sized regions one cell in from the edges of the
+    /// boxes, with a gap of one cell between them.
+    pub fn window_count(self) -> u8 {
+        (self.box_size - 1) * (self.box_size - 1)
+    }
+    fn window_origin(self, w: u8) -> (u8, u8) {
+        let per_side = self.box_size - 1;
+        let step = self.box_size + 1;
+        (1 + (w % per_side) * step, 1 + (w / per_side) * step)
+    }
+    pub fn window_of(self, pos: Pos) -> Option<u8> {
+        let step = self.box_size + 1;
+        let along = |c: u8| {
+            let c = c.checked_sub(1)?;
+            (c % step < self.box_size && c / step < self.box_size - 1).then_some(c / step)
+        };
+        Some(along(pos.y())? * (self.box_size - 1) + along(pos.x())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn supported_sizes() {
+        assert_eq!(Size::new(1), Err(SudokuError::InvalidSize));
+        assert_eq!(Size::new(6), Err(SudokuError::InvalidSize));
+        assert_eq!(Size::from_cell_count(16).map(Size::side), Some(4));
+        assert_eq!(Size::from_cell_count(625).map(Size::side), Some(25));
+        assert_eq!(Size::from_cell_count(80), None);
+    }
+
+    #[test]
+    fn houses_scale_with_box_size() {
+        let size = Size::new(4).unwrap();
+        assert_eq!(size.houses().count(), 48);
+        let pos = Pos::new_unchecked(13, 6);
+        assert_eq!(size.houses_containing(pos)[2], House::Box(7));
+        let cells = size.house_positions(House::Box(7)).collect::<Vec<_>>();
+        assert_eq!(cells.len(), 16);
+        assert!(cells.contains(&pos));
+        assert_eq!(size.pos_at(size.index_of(pos)), pos);
+    }
+}
@@ -0,0 +1,1020 @@
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+use crate::dlx::Dlx;
+use crate::rng::Rng;
+use crate::{Candidates, Pos, Rules, Size, Sudoku};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveError {
+    InvalidPuzzle,
+    NoSolution,
+    /// The cancel token of [`SolveOptions`] was set.
+    Cancelled,
+    /// The search hit the node or time limit of [`SolveOptions`].
+    TimedOut,
+}
+
+impl core::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SolveError::InvalidPuzzle => write!(f, "Puzzle givens contradict each other."),
+            SolveError::NoSolution => write!(f, "Puzzle has no solution."),
+            SolveError::Cancelled => write!(f, "Solve was cancelled."),
+            SolveError::TimedOut => write!(f, "Solver ran out of time."),
+        }
+    }
+}
+
+impl core::error::Error for SolveError {}
+
+// Which cells make up each house, and which houses each cell is in. Shared
+// between the clones a search makes. The first `full` units are houses that
+// hold every value; the rest are pairs of linked cells that merely can't
+// share one.
+struct Layout {
+    size: Size,
+    rules: Rules,
+    units: Vec<Vec<usize>>,
+    full: usize,
+    houses_of: Vec<Vec<usize>>,
+}
+
+impl Layout {
+    fn new(size: Size, rules: Rules) -> Self {
+        let mut units = rules
+            .houses(size)
+            .map(|h| {
+                rules
+                    .house_positions(size, h)
+                    .map(|p| size.index_of(p))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let full = units.len();
+        units.extend(rules.link_pairs(size).into_iter().map(|(a, b)| vec![a, b]));
+        let mut houses_of = vec![Vec::new(); size.cell_count()];
+        for (u, unit) in units.iter().enumerate() {
+            for &i in unit {
+                houses_of[i].push(u);
+            }
+        }
+        Self {
+            size,
+            rules,
+            units,
+            full,
+            houses_of,
+        }
+    }
+}
+
+// Values are stored in `Size::index_of` order, 0 meaning empty. Each house
+// keeps a bitmask of the values it already holds.
+#[derive(Clone)]
+struct Grid {
+    layout: Arc<Layout>,
+    all: u32,
+    values: Vec<u8>,
+    masks: Vec<u32>,
+}
+
+impl Grid {
+    fn empty(size: Size, rules: Rules) -> Self {
+        let layout = Layout::new(size, rules);
+        Self {
+            all: Candidates::up_to(size.side()).bits(),
+            values: vec![0; size.cell_count()],
+            masks: vec![0; layout.units.len()],
+            layout: Arc::new(layout),
+        }
+    }
+
+    fn from_sudoku(s: &Sudoku) -> Result<Self, SolveError> {
+        let mut grid = Self::empty(s.size(), s.rules());
+        for cell in s.iter() {
+            if let Some(v) = cell.value() {
+                let i = s.size().index_of(cell.position);
+                if grid.used(i) & (1 << v) != 0 {
+                    return Err(SolveError::InvalidPuzzle);
+                }
+                grid.place(i, v);
+            }
+        }
+        Ok(grid)
+    }
+
+    fn side(&self) -> u8 {
+        self.layout.size.side()
+    }
+
+    fn pos_at(&self, i: usize) -> Pos {
+        self.layout.size.pos_at(i)
+    }
+
+    fn used(&self, i: usize) -> u32 {
+        self.layout.houses_of[i]
+            .iter()
+            .fold(0, |used, &h| used | self.masks[h])
+    }
+
+    fn place(&mut self, i: usize, v: u8) {
+        self.values[i] = v;
+        for &h in &self.layout.houses_of[i] {
+            self.masks[h] |= 1 << v;
+        }
+    }
+
+    fn unplace(&mut self, i: usize) {
+        let bit = !(1 << self.values[i]);
+        self.values[i] = 0;
+        for &h in &self.layout.houses_of[i] {
+            self.masks[h] &= bit;
+        }
+    }
+
+    fn most_constrained(&self) -> Option<usize> {
+        (0..self.values.len())
+            .filter(|&i| self.values[i] == 0)
+            .min_by_key(|&i| self.candidates(i).count_ones())
+    }
+
+    fn backtrack(&mut self) -> bool {
+        let i = match self.most_constrained() {
+            Some(i) => i,
+            None => return true,
+        };
+        let free = self.candidates(i);
+        for v in 1..=self.side() {
+            if free & (1 << v) != 0 {
+                self.place(i, v);
+                if self.backtrack() {
+                    return true;
+                }
+                self.unplace(i);
+            }
+        }
+        false
+    }
+
+    fn candidates(&self, i: usize) -> u32 {
+        self.all & !self.used(i)
+    }
+
+    // Fills in naked and hidden singles until none are left. Returns false if
+    // some cell or unit runs out of options.
+    fn propagate(&mut self, stats: &mut Counters) -> bool {
+        loop {
+            stats.passes += 1;
+            let mut progress = false;
+            for i in 0..self.values.len() {
+                if self.values[i] != 0 {
+                    continue;
+                }
+                let c = self.candidates(i);
+                match c.count_ones() {
+                    0 => return false,
+                    1 => {
+                        self.place(i, c.trailing_zeros() as u8);
+                        progress = true;
+                    }
+                    _ => {}
+                }
+            }
+            let layout = Arc::clone(&self.layout);
+            for unit in &layout.units[..layout.full] {
+                for v in 1..=self.side() {
+                    let mut spot = None;
+                    let mut count = 0;
+                    for &i in unit.iter() {
+                        if self.values[i] == v {
+                            count = 2;
+                            break;
+                        }
+                        if self.values[i] == 0 && self.candidates(i) & (1 << v) != 0 {
+                            spot = Some(i);
+                            count += 1;
+                        }
+                    }
+                    match (count, spot) {
+                        (0, _) => return false,
+                        (1, Some(i)) => {
+                            self.place(i, v);
+                            progress = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if !progress {
+                return true;
+            }
+        }
+    }
+
+    fn search(&mut self, stats: &mut Counters, budget: &mut Budget) -> bool {
+        if budget.exhausted() || !self.propagate(stats) {
+            return false;
+        }
+        let i = match self.most_constrained() {
+            Some(i) => i,
+            None => return true,
+        };
+        let free = self.candidates(i);
+        for v in 1..=self.side() {
+            if free & (1 << v) != 0 {
+                if free.count_ones() > 1 {
+                    stats.guesses += 1;
+                }
+                let mut next = self.clone();
+                next.place(i, v);
+                if next.search(stats, budget) {
+                    *self = next;
+                    return true;
+                }
+                stats.backtracks += 1;
+                if budget.stopped.is_some() {
+                    return false;
+                }
+            }
+        }
+        false
+    }
+
+    fn fill_random(&mut self, rng: &mut Rng) -> bool {
+        let i = match self.values.iter().position(|&v| v == 0) {
+            Some(i) => i,
+            None => return true,
+        };
+        let free = self.candidates(i);
+        let mut digits = (1..=self.side()).collect::<Vec<_>>();
+        rng.shuffle(&mut digits);
+        for v in digits {
+            if free & (1 << v) != 0 {
+                self.place(i, v);
+                if self.fill_random(rng) {
+                    return true;
+                }
+                self.unplace(i);
+            }
+        }
+        false
+    }
+
+    fn count(&mut self, limit: usize) -> usize {
+        if !self.propagate(&mut Counters::default()) {
+            return 0;
+        }
+        let i = match self.most_constrained() {
+            Some(i) => i,
+            None => return 1,
+        };
+        let free = self.candidates(i);
+        let mut total = 0;
+        for v in 1..=self.side() {
+            if total >= limit {
+                break;
+            }
+            if free & (1 << v) != 0 {
+                let mut next = self.clone();
+                next.place(i, v);
+                total += next.count(limit - total);
+            }
+        }
+        total
+    }
+
+    fn to_sudoku(&self) -> Sudoku {
+        Sudoku::from_values(
+            self.layout.size,
+            self.values.iter().map(|&v| Some(v).filter(|&v| v != 0)),
+        )
+        .with_rules(self.layout.rules)
+    }
+}
+
+/// Limits on how long [`Sudoku::solve_with_options`] may search, for
+/// untrusted input where brute force could otherwise run for a very long
+/// time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SolveOptions<'a> {
+    /// Search nodes to visit before giving up with `TimedOut`.
+    pub max_nodes: Option<usize>,
+    /// Wall-clock time allowed before giving up with `TimedOut`. Ignored
+    /// without the `std` feature.
+    pub timeout: Option<Duration>,
+    /// Checked at every node; setting it from another thread stops the
+    /// search with `Cancelled`.
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+// The running state of a `SolveOptions`. `stopped` records why the search
+// was cut short, once it has been.
+struct Budget<'a> {
+    nodes_left: Option<usize>,
+    #[cfg(feature = "std")]
+    deadline: Option<std::time::Instant>,
+    cancel: Option<&'a AtomicBool>,
+    stopped: Option<SolveError>,
+}
+
+impl<'a> Budget<'a> {
+    fn new(options: &SolveOptions<'a>) -> Self {
+        Self {
+            nodes_left: options.max_nodes,
+            #[cfg(feature = "std")]
+            deadline: options.timeout.map(|t| std::time::Instant::now() + t),
+            cancel: options.cancel,
+            stopped: None,
+        }
+    }
+
+    fn unlimited() -> Self {
+        Self::new(&SolveOptions::default())
+    }
+
+    // Spends one node. True once the search should stop.
+    fn exhausted(&mut self) -> bool {
+        if self.stopped.is_some() {
+            return true;
+        }
+        if self.cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            self.stopped = Some(SolveError::Cancelled);
+        } else if self.nodes_left == Some(0) {
+            self.stopped = Some(SolveError::TimedOut);
+        }
+        #[cfg(feature = "std")]
+        if self
+            .deadline
+            .is_some_and(|d| std::time::Instant::now() >= d)
+        {
+            self.stopped.get_or_insert(SolveError::TimedOut);
+        }
+        if let Some(n) = self.nodes_left.as_mut() {
+            *n = n.saturating_sub(1);
+        }
+        self.stopped.is_some()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    guesses: usize,
+    backtracks: usize,
+    passes: usize,
+}
+
+/// A solved board plus how much work the solver did to find it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub grid: Sudoku,
+    /// Values tried in cells that had more than one candidate.
+    pub guesses: usize,
+    /// Tried values that led to a dead end.
+    pub backtracks: usize,
+    /// Sweeps over the board filling in naked and hidden singles.
+    pub propagation_passes: usize,
+    /// Always zero without the `std` feature.
+    pub elapsed: Duration,
+}
+
+pub(crate) fn random_solution(rng: &mut Rng) -> Sudoku {
+    let mut grid = Grid::empty(Size::CLASSIC, Rules::CLASSIC);
+    grid.fill_random(rng);
+    grid.to_sudoku()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveStep {
+    /// A guess at a cell with several candidates.
+    TryValue { pos: Pos, value: u8 },
+    /// A cell with a single candidate being filled.
+    Place { pos: Pos, value: u8 },
+    /// A guess that led to a dead end, ruled out for the current branch.
+    EliminateCandidate { pos: Pos, value: u8 },
+    /// A cell being emptied again while unwinding.
+    Backtrack { pos: Pos },
+}
+
+struct Frame {
+    i: usize,
+    untried: u32,
+}
+
+/// Lazily runs the backtracking solver, yielding each decision it makes.
+pub struct SolveSteps {
+    grid: Grid,
+    stack: Vec<Frame>,
+    pending: VecDeque<SolveStep>,
+    done: bool,
+}
+
+impl SolveSteps {
+    /// The board as the solver currently sees it.
+    pub fn current(&self) -> Sudoku {
+        self.grid.to_sudoku()
+    }
+
+    fn choose(&mut self, i: usize, free: u32) {
+        let pos = self.grid.pos_at(i);
+        let value = free.trailing_zeros() as u8;
+        let untried = free & !(1 << value);
+        self.grid.place(i, value);
+        self.stack.push(Frame { i, untried });
+        self.pending.push_back(if free.count_ones() == 1 {
+            SolveStep::Place { pos, value }
+        } else {
+            SolveStep::TryValue { pos, value }
+        });
+    }
+
+    fn backtrack(&mut self) {
+        while let Some(frame) = self.stack.pop() {
+            let pos = self.grid.pos_at(frame.i);
+            let value = self.grid.values[frame.i];
+            self.grid.unplace(frame.i);
+            self.pending.push_back(SolveStep::Backtrack { pos });
+            if frame.untried != 0 {
+                self.pending
+                    .push_back(SolveStep::EliminateCandidate { pos, value });
+                let v = frame.untried.trailing_zeros() as u8;
+                self.grid.place(frame.i, v);
+                self.stack.push(Frame {
+                    i: frame.i,
+                    untried: frame.untried & !(1 << v),
+                });
+                self.pending
+                    .push_back(SolveStep::TryValue { pos, value: v });
+                return;
+            }
+        }
+        self.done = true;
+    }
+}
+
+impl Iterator for SolveSteps {
+    type Item = SolveStep;
+
+    fn next(&mut self) -> Option<SolveStep> {
+        loop {
+            if let Some(step) = self.pending.pop_front() {
+                return Some(step);
+            }
+            if self.done {
+                return None;
+            }
+            match self.grid.most_constrained() {
+                None => self.done = true,
+                Some(i) => match self.grid.candidates(i) {
+                    0 => self.backtrack(),
+                    free => self.choose(i, free),
+                },
+            }
+        }
+    }
+}
+
+/// Every solution of a board, found one at a time by backtracking over the
+/// most constrained cell. See [`Sudoku::solutions`].
+pub struct Solutions {
+    puzzle: Sudoku,
+    // `None` once the search is over.
+    grid: Option<Grid>,
+    stack: Vec<Frame>,
+}
+
+impl Solutions {
+    // Undoes guesses back to the latest one with values left to try and
+    // tries the next of them.
+    fn retreat(&mut self) {
+        let Some(grid) = self.grid.as_mut() else {
+            return;
+        };
+        while let Some(frame) = self.stack.pop() {
+            grid.unplace(frame.i);
+            if frame.untried != 0 {
+                let v = frame.untried.trailing_zeros() as u8;
+                grid.place(frame.i, v);
+                self.stack.push(Frame {
+                    i: frame.i,
+                    untried: frame.untried & !(1 << v),
+                });
+                return;
+            }
+        }
+        self.grid = None;
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Sudoku;
+
+    fn next(&mut self) -> Option<Sudoku> {
+        loop {
+            let grid = self.grid.as_mut()?;
+            match grid.most_constrained() {
+                None => {
+                    let solution = self.puzzle.solution_from(grid.clone());
+                    self.retreat();
+                    return Some(solution);
+                }
+                Some(i) => match grid.candidates(i) {
+                    0 => self.retreat(),
+                    free => {
+                        let v = free.trailing_zeros() as u8;
+                        grid.place(i, v);
+                        self.stack.push(Frame {
+                            i,
+                            untried: free & !(1 << v),
+                        });
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Which algorithm [`Sudoku::solve_with`] and
+/// [`Sudoku::count_solutions_with`] use. All of them find the same solutions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SolverKind {
+    /// Plain backtracking over the most constrained cell.
+    #[default]
+    Backtracking,
+    /// Backtracking with naked and hidden singles propagated between guesses.
+    Propagation,
+    /// Knuth's dancing links over the exact cover form of the board. The
+    /// fastest for counting solutions and for large boards.
+    Dlx,
+}
+
+impl core::str::FromStr for SolverKind {
+    type Err = crate::SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "backtracking" => Ok(SolverKind::Backtracking),
+            "propagation" => Ok(SolverKind::Propagation),
+            "dlx" => Ok(SolverKind::Dlx),
+            _ => Err(crate::SudokuError::ParseError(crate::ParseError::Invalid(
+                "Unknown solver.",
+            ))),
+        }
+    }
+}
+
+impl Sudoku {
+    pub fn solve_with(&self, kind: SolverKind) -> Result<Sudoku, SolveError> {
+        match kind {
+            SolverKind::Backtracking => self.solve(),
+            SolverKind::Propagation => self.solve_with_propagation(),
+            SolverKind::Dlx => {
+                let mut grid = Grid::from_sudoku(self)?;
+                let mut first = None;
+                self.dlx()?.count(1, &mut first);
+                let values = first.ok_or(SolveError::NoSolution)?;
+                for (i, &v) in values.iter().enumerate() {
+                    if grid.values[i] == 0 {
+                        grid.place(i, v);
+                    }
+                }
+                Ok(self.solution_from(grid))
+            }
+        }
+    }
+    /// Like [`Sudoku::count_solutions`], with a choice of algorithm.
+    pub fn count_solutions_with(&self, kind: SolverKind, limit: usize) -> usize {
+        match kind {
+            SolverKind::Dlx if limit > 0 => match self.dlx() {
+                Ok(mut dlx) => dlx.count(limit, &mut None),
+                Err(_) => 0,
+            },
+            SolverKind::Dlx => 0,
+            _ => self.count_solutions(limit),
+        }
+    }
+    fn dlx(&self) -> Result<Dlx, SolveError> {
+        let size = self.size();
+        let mut dlx = Dlx::new(size, self.rules());
+        for cell in self.iter() {
+            if let Some(v) = cell.value() {
+                if !dlx.place(size.index_of(cell.position), v) {
+                    return Err(SolveError::InvalidPuzzle);
+                }
+            }
+        }
+        Ok(dlx)
+    }
+    pub fn solve_steps(&self) -> Result<SolveSteps, SolveError> {
+        Ok(SolveSteps {
+            grid: Grid::from_sudoku(self)?,
+            stack: Vec::new(),
+            pending: VecDeque::new(),
+            done: false,
+        })
+    }
+    /// Every solution, lazily: take the first few to see how a board with
+    /// too few clues can be finished. The first is the one [`Sudoku::solve`]
+    /// finds. Empty for a board that breaks the rules.
+    pub fn solutions(&self) -> Solutions {
+        Solutions {
+            puzzle: self.clone(),
+            grid: Grid::from_sudoku(self).ok(),
+            stack: Vec::new(),
+        }
+    }
+    pub(crate) fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+    /// Counts solutions, stopping once `limit` of them have been found.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        match Grid::from_sudoku(self) {
+            Ok(mut grid) if limit > 0 => grid.count(limit),
+            _ => 0,
+        }
+    }
+    fn solution_from(&self, grid: Grid) -> Sudoku {
+        let mut solution = grid.to_sudoku();
+        for (cell, original) in solution.cells.iter_mut().zip(self.iter()) {
+            cell.given = original.is_given();
+        }
+        solution
+    }
+    pub fn solve(&self) -> Result<Sudoku, SolveError> {
+        let mut grid = Grid::from_sudoku(self)?;
+        if grid.backtrack() {
+            Ok(self.solution_from(grid))
+        } else {
+            Err(SolveError::NoSolution)
+        }
+    }
+    pub fn solve_with_propagation(&self) -> Result<Sudoku, SolveError> {
+        self.solve_with_stats().map(|s| s.grid)
+    }
+    /// Like `solve_with_propagation`, but also reports search statistics.
+    pub fn solve_with_stats(&self) -> Result<Solution, SolveError> {
+        self.search_within(&mut Budget::unlimited())
+    }
+    /// Solves with the propagation solver, giving up once any of the limits
+    /// in `options` is reached.
+    pub fn solve_with_options(&self, options: &SolveOptions) -> Result<Sudoku, SolveError> {
+        self.search_within(&mut Budget::new(options))
+            .map(|s| s.grid)
+    }
+    /// Solves until `cancel` is set, perhaps by another thread.
+    pub fn solve_cancellable(&self, cancel: &AtomicBool) -> Result<Sudoku, SolveError> {
+        self.solve_with_options(&SolveOptions {
+            cancel: Some(cancel),
+            ..SolveOptions::default()
+        })
+    }
+    fn search_within(&self, budget: &mut Budget) -> Result<Solution, SolveError> {
+        #[cfg(feature = "std")]
+        let start = std::time::Instant::now();
+        let mut grid = Grid::from_sudoku(self)?;
+        let mut stats = Counters::default();
+        if !grid.search(&mut stats, budget) {
+            return Err(budget.stopped.unwrap_or(SolveError::NoSolution));
+        }
+        #[cfg(feature = "std")]
+        let elapsed = start.elapsed();
+        #[cfg(not(feature = "std"))]
+        let elapsed = Duration::ZERO;
+        Ok(Solution {
+            grid: self.solution_from(grid),
+            guesses: stats.guesses,
+            backtracks: stats.backtracks,
+            propagation_passes: stats.passes,
+            elapsed,
+        })
+    }
+    pub fn is_solved(&self) -> bool {
+        match Grid::from_sudoku(self) {
+            Ok(grid) => grid.values.iter().all(|&v| v != 0),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::House;
+
+    #[test]
+    fn solves_puzzle() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let solved = s.solve().unwrap();
+        assert!(solved.is_solved());
+        assert!(!s.is_solved());
+        assert!(s
+            .iter()
+            .zip(solved.iter())
+            .all(|(a, b)| a.value().is_none() || a.value() == b.value()));
+    }
+
+    #[test]
+    fn limits_and_cancellation_stop_the_search() {
+        let empty = Sudoku::new(Size::CLASSIC);
+        let few = SolveOptions {
+            max_nodes: Some(5),
+            ..SolveOptions::default()
+        };
+        assert_eq!(empty.solve_with_options(&few), Err(SolveError::TimedOut));
+        let plenty = SolveOptions {
+            max_nodes: Some(10_000),
+            timeout: Some(Duration::from_secs(60)),
+            ..SolveOptions::default()
+        };
+        assert!(empty.solve_with_options(&plenty).unwrap().is_solved());
+
+        let cancel = AtomicBool::new(true);
+        assert_eq!(empty.solve_cancellable(&cancel), Err(SolveError::Cancelled));
+        cancel.store(false, Ordering::Relaxed);
+        assert!(empty.solve_cancellable(&cancel).is_ok());
+    }
+
+    #[test]
+    fn propagation_matches_backtracking() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert_eq!(s.solve_with_propagation(), s.solve());
+    }
+
+    #[test]
+    fn propagation_solves_hard_puzzle() {
+        let s = Sudoku::from_str(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        )
+        .unwrap();
+        assert!(s.solve_with_propagation().unwrap().is_solved());
+    }
+
+    #[test]
+    fn stats_reflect_search_effort() {
+        let easy = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap()
+        .solve_with_stats()
+        .unwrap();
+        assert_eq!((easy.guesses, easy.backtracks), (0, 0));
+        assert!(easy.propagation_passes > 0);
+        let hard = Sudoku::from_str(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        )
+        .unwrap()
+        .solve_with_stats()
+        .unwrap();
+        assert!(hard.grid.is_solved());
+        assert!(hard.guesses > 0 && hard.backtracks > 0);
+        assert!(hard.propagation_passes > easy.propagation_passes);
+    }
+
+    #[test]
+    fn counts_solutions_up_to_limit() {
+        let s = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert_eq!(s.count_solutions(10), 1);
+        let empty = Sudoku::from_str(&".".repeat(81)).unwrap();
+        assert_eq!(empty.count_solutions(5), 5);
+        assert_eq!(empty.count_solutions(0), 0);
+    }
+
+    #[test]
+    fn enumerates_solutions_lazily() {
+        let unique = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let all = unique.solutions().collect::<Vec<_>>();
+        assert_eq!(all, vec![unique.solve().unwrap()]);
+
+        let mut open = unique.clone();
+        for pos in Size::CLASSIC.positions().take(30) {
+            open.set_forced(pos, None).unwrap();
+        }
+        let count = open.count_solutions(100);
+        assert!(count > 2);
+        let all = open.solutions().take(100).collect::<Vec<_>>();
+        assert_eq!(all.len(), count);
+        assert_eq!(all.first(), open.solve().ok().as_ref());
+        for (i, solution) in all.iter().enumerate() {
+            assert!(solution.is_solved());
+            assert!(open
+                .iter()
+                .filter(|c| c.value().is_some())
+                .all(|c| solution[c.position()].value() == c.value()));
+            assert!(!all[..i].contains(solution));
+        }
+        assert_eq!(Sudoku::new(Size::CLASSIC).solutions().take(3).count(), 3);
+        let clash = Sudoku::from_str(&format!("11{}", ".".repeat(79))).unwrap();
+        assert_eq!(clash.solutions().next(), None);
+    }
+
+    #[test]
+    fn steps_end_in_solution() {
+        let s = Sudoku::from_str(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        )
+        .unwrap();
+        let mut steps = s.solve_steps().unwrap();
+        let events = steps.by_ref().collect::<Vec<_>>();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, SolveStep::Backtrack { .. })));
+        assert_eq!(steps.current(), s.solve().unwrap());
+        // Replaying the events gives the same board.
+        let mut board = s.clone();
+        for e in events {
+            match e {
+                SolveStep::TryValue { pos, value } | SolveStep::Place { pos, value } => {
+                    board.set(pos, Some(value)).unwrap()
+                }
+                SolveStep::Backtrack { pos } => board.set(pos, None).unwrap(),
+                SolveStep::EliminateCandidate { .. } => {}
+            }
+        }
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn steps_exhaust_unsolvable_puzzle() {
+        let s = Sudoku::from_str(&format!(".12345678{}{}", "9........", ".".repeat(63))).unwrap();
+        assert_eq!(s.solve_steps().unwrap().count(), 0);
+        let s = Sudoku::from_str(&format!("..2345678{}{}", "9........", ".".repeat(63))).unwrap();
+        let steps = s.solve_steps().unwrap().collect::<Vec<_>>();
+        assert!(matches!(steps.last(), Some(SolveStep::Backtrack { .. })));
+    }
+
+    #[test]
+    fn solves_other_board_sizes() {
+        let s = Sudoku::from_str("1...  ..2.  .3..  ...4".replace(' ', "").as_str()).unwrap();
+        let solved = s.solve_with_propagation().unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(solved.size(), s.size());
+        assert_eq!(s.solve(), Ok(solved));
+        let hexadoku = Sudoku::new(Size::new(4).unwrap());
+        let solved = hexadoku.solve().unwrap();
+        assert!(solved.is_solved());
+        assert!(solved.iter().all(|c| c.value().is_some_and(|v| v <= 16)));
+    }
+
+    #[test]
+    fn solves_hexadoku_puzzles() {
+        let full = Sudoku::new(Size::new(4).unwrap())
+            .solve_with(SolverKind::Dlx)
+            .unwrap();
+        // A third of the cells as clues, written with letters for 10 to 16.
+        let line = full
+            .to_line_string()
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i % 3 == 0 { c } else { '.' })
+            .collect::<String>();
+        assert!(line.contains('G'));
+        let puzzle = Sudoku::from_str(&line).unwrap();
+        assert_eq!(puzzle.size(), Size::new(4).unwrap());
+        for kind in [
+            SolverKind::Backtracking,
+            SolverKind::Propagation,
+            SolverKind::Dlx,
+        ] {
+            let solved = puzzle.solve_with(kind).unwrap();
+            assert!(solved.is_solved() && solved.is_valid());
+            assert!(puzzle
+                .iter()
+                .filter_map(|c| c.value().map(|v| (c.position(), v)))
+                .all(|(p, v)| solved[p].value() == Some(v)));
+        }
+    }
+
+    #[test]
+    fn dlx_agrees_with_other_solvers() {
+        let s = Sudoku::from_str(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        )
+        .unwrap();
+        let solved = s.solve_with(SolverKind::Dlx).unwrap();
+        assert_eq!(Ok(solved.clone()), s.solve_with(SolverKind::Backtracking));
+        assert_eq!(Ok(solved.clone()), s.solve_with(SolverKind::Propagation));
+        assert!(solved[(0, 0)].is_given());
+        assert_eq!(s.count_solutions_with(SolverKind::Dlx, 5), 1);
+
+        let mut two = solved.clone();
+        for pos in [(0, 1), (1, 1)] {
+            two.set_forced(Pos::new_unchecked(pos.0, pos.1), None)
+                .unwrap();
+        }
+        assert_eq!(
+            two.count_solutions_with(SolverKind::Dlx, 5),
+            two.count_solutions(5)
+        );
+        assert_eq!(
+            Sudoku::new(Size::CLASSIC).count_solutions_with(SolverKind::Dlx, 100),
+            100
+        );
+
+        let hexadoku = Sudoku::new(Size::new(4).unwrap()).with_rules(Rules::X);
+        let solved = hexadoku.solve_with(SolverKind::Dlx).unwrap();
+        assert!(solved.is_solved() && solved.is_valid());
+
+        let mut clash = Sudoku::new(Size::CLASSIC);
+        clash.set(Pos::new_unchecked(0, 0), Some(1)).unwrap();
+        clash.set_forced(Pos::new_unchecked(8, 0), Some(1)).unwrap();
+        assert_eq!(
+            clash.solve_with(SolverKind::Dlx),
+            Err(SolveError::InvalidPuzzle)
+        );
+        assert_eq!(clash.count_solutions_with(SolverKind::Dlx, 1), 0);
+    }
+
+    #[test]
+    fn solves_with_diagonal_rules() {
+        let x = Sudoku::new(Size::CLASSIC).with_rules(Rules::X);
+        let solved = x.solve_with_propagation().unwrap();
+        assert!(solved.is_solved() && solved.is_valid());
+        assert_eq!(solved.rules(), Rules::X);
+        assert_eq!(x.solve().map(|s| s.is_valid()), Ok(true));
+        // Classic solutions generally break the diagonals.
+        let classic = Sudoku::new(Size::CLASSIC).solve().unwrap();
+        assert!(!classic.with_rules(Rules::X).is_valid());
+    }
+
+    #[test]
+    fn solves_under_anti_knight_and_anti_king() {
+        let rules = Rules {
+            anti_knight: true,
+            anti_king: true,
+            ..Rules::CLASSIC
+        };
+        let board = Sudoku::new(Size::CLASSIC).with_rules(rules);
+        for kind in [
+            SolverKind::Backtracking,
+            SolverKind::Propagation,
+            SolverKind::Dlx,
+        ] {
+            let solved = board.solve_with(kind).unwrap();
+            assert!(solved.is_solved() && solved.is_valid());
+            assert!(solved.conflicts().is_empty());
+        }
+        assert!(!Sudoku::new(Size::CLASSIC)
+            .solve()
+            .unwrap()
+            .with_rules(rules)
+            .is_valid());
+        let mut clash = board.clone();
+        clash.set(Pos::new_unchecked(4, 4), Some(1)).unwrap();
+        clash.set(Pos::new_unchecked(5, 6), Some(1)).unwrap();
+        assert_eq!(clash.solve(), Err(SolveError::InvalidPuzzle));
+        assert_eq!(clash.count_solutions_with(SolverKind::Dlx, 1), 0);
+    }
+
+    #[test]
+    fn solves_windoku() {
+        let s = Sudoku::from_str(&format!("{}{}", ".1.......2........3", ".".repeat(62)))
+            .unwrap()
+            .with_rules(Rules::WINDOKU);
+        let solved = s.solve_with_propagation().unwrap();
+        assert!(solved.is_solved() && solved.is_valid());
+        assert_eq!(s.solve().map(|s| s.is_valid()), Ok(true));
+        for w in 0..4 {
+            let values = solved
+                .house(House::Window(w))
+                .filter_map(|c| c.value())
+                .collect::<Candidates>();
+            assert_eq!(values.count(), 9);
+        }
+    }
+
+    #[test]
+    fn rejects_contradictory_givens() {
+        let s = Sudoku::from_str(
+            "55..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        assert_eq!(s.solve(), Err(SolveError::InvalidPuzzle));
+        assert_eq!(s.count_solutions(2), 0);
+    }
+
+    #[test]
+    fn reports_unsolvable_puzzle() {
+        // r0c0 can't hold 1-8 because of its row and can't hold 9 because of its column.
+        let s = Sudoku::from_str(&format!(".12345678{}{}", "9........", ".".repeat(63))).unwrap();
+        assert_eq!(s.solve(), Err(SolveError::NoSolution));
+        assert_eq!(s.solve_with_propagation(), Err(SolveError::NoSolution));
+    }
+}
@@ -0,0 +1,254 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Size, Sudoku};
+
+// Maps a position to the position it is read from, given the largest
+// coordinate on the board.
+type Geometry = fn(usize, usize, usize) -> (usize, usize);
+
+// The eight rotations and reflections of the square.
+const GEOMETRIES: [Geometry; 8] = [
+    |x, y, _| (x, y),
+    |x, y, m| (m - y, x),
+    |x, y, m| (m - x, m - y),
+    |x, y, m| (y, m - x),
+    |x, y, m| (m - x, y),
+    |x, y, m| (x, m - y),
+    |x, y, _| (y, x),
+    |x, y, m| (m - y, m - x),
+];
+
+// Renames digits in order of first appearance so that relabelled boards match.
+fn relabel(values: &mut [u8]) {
+    let mut map = [0u8; 26];
+    let mut next = 1;
+    for v in values.iter_mut().filter(|v| **v != 0) {
+        if map[*v as usize] == 0 {
+            map[*v as usize] = next;
+            next += 1;
+        }
+        *v = map[*v as usize];
+    }
+}
+
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+// Every column order that keeps stacks together: an order of the stacks,
+// then an order of the columns within each of them.
+fn line_orders() -> Vec<[usize; 9]> {
+    let mut orders = Vec::with_capacity(1296);
+    for stacks in PERMUTATIONS {
+        for a in PERMUTATIONS {
+            for b in PERMUTATIONS {
+                for c in PERMUTATIONS {
+                    let mut order = [0; 9];
+                    for (k, within) in [a, b, c].into_iter().enumerate() {
+                        for j in 0..3 {
+                            order[k * 3 + j] = stacks[k] * 3 + within[j];
+                        }
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+// Depth-first search over row orders for one fixed column order, keeping the
+// smallest relabelled grid in `best`. Branches whose prefix is already larger
+// than `best` are cut.
+struct RowSearch<'a> {
+    grid: &'a [u8; 81],
+    best: [u8; 81],
+    out: [u8; 81],
+    used: [bool; 9],
+}
+
+impl RowSearch<'_> {
+    fn run(&mut self, row: usize, band: usize, map: [u8; 10], next: u8) {
+        if row == 9 {
+            if self.out < self.best {
+                self.best = self.out;
+            }
+            return;
+        }
+        // A new band starts every three rows; otherwise stay in the current one.
+        let new_band = row.is_multiple_of(3);
+        let bands = if new_band { 0..3 } else { band..band + 1 };
+        for band in bands {
+            if new_band && self.used[band * 3..band * 3 + 3].contains(&true) {
+                continue;
+            }
+            for source in band * 3..band * 3 + 3 {
+                if self.used[source] {
+                    continue;
+                }
+                let (mut map, mut next) = (map, next);
+                for x in 0..9 {
+                    let v = self.grid[source * 9 + x];
+                    if v != 0 && map[v as usize] == 0 {
+                        map[v as usize] = next;
+                        next += 1;
+                    }
+                    self.out[row * 9 + x] = map[v as usize];
+                }
+                let end = (row + 1) * 9;
+                if self.out[..end] > self.best[..end] {
+                    continue;
+                }
+                self.used[source] = true;
+                self.run(row + 1, band, map, next);
+                self.used[source] = false;
+            }
+        }
+    }
+}
+
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+impl Sudoku {
+    /// The smallest board, read in index order, that can be reached by
+    /// relabelling digits, transposing, and reordering bands, stacks and the
+    /// rows and columns within them. Those moves keep a puzzle valid, so two
+    /// boards have the same canonical form exactly when one is a disguised
+    /// copy of the other. Only the classic rules are considered.
+    ///
+    /// Panics if the board isn't 9x9.
+    pub fn canonicalize(&self) -> Sudoku {
+        let values = self.to_flat_array();
+        let mut transposed = [0; 81];
+        for (i, slot) in transposed.iter_mut().enumerate() {
+            *slot = values[(i % 9) * 9 + i / 9];
+        }
+        let mut best = [u8::MAX; 81];
+        for source in [values, transposed] {
+            for order in line_orders() {
+                let mut grid = [0; 81];
+                for (i, slot) in grid.iter_mut().enumerate() {
+                    *slot = source[(i / 9) * 9 + order[i % 9]];
+                }
+                let mut search = RowSearch {
+                    grid: &grid,
+                    best,
+                    out: [0; 81],
+                    used: [false; 9],
+                };
+                search.run(0, 0, [0; 10], 1);
+                best = search.best;
+            }
+        }
+        Sudoku::from_values(
+            Size::CLASSIC,
+            best.iter().map(|&v| Some(v).filter(|&v| v != 0)),
+        )
+    }
+    /// True if `other` is the same puzzle up to the moves `canonicalize`
+    /// allows.
+    pub fn is_isomorphic_to(&self, other: &Sudoku) -> bool {
+        self.canonicalize() == other.canonicalize()
+    }
+    /// A hash shared by boards that only differ by rotation, reflection or
+    /// relabelling of digits. Stable across runs and platforms.
+    pub fn canonical_hash(&self) -> u64 {
+        let side = self.size().side() as usize;
+        let values = self
+            .iter()
+            .map(|c| c.value().unwrap_or(0))
+            .collect::<Vec<_>>();
+        let form = GEOMETRIES
+            .iter()
+            .map(|g| {
+                let mut out = vec![0u8; values.len()];
+                for (i, slot) in out.iter_mut().enumerate() {
+                    let (x, y) = g(i % side, i / side, side - 1);
+                    *slot = values[y * side + x];
+                }
+                relabel(&mut out);
+                out
+            })
+            .min()
+            .unwrap();
+        fnv1a(&form)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use super::*;
+
+    const LINE: &str =
+        ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+
+    #[test]
+    fn equal_boards_dedupe_in_hash_set() {
+        let s = Sudoku::from_str(LINE).unwrap();
+        let mut copy = s.clone();
+        copy.fill_all_candidates();
+        let set = [s, copy].into_iter().collect::<HashSet<_>>();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn canonical_form_sees_through_disguises() {
+        let s = Sudoku::from_str(LINE).unwrap();
+        // Swap the first two bands, transpose and relabel 1 <-> 2.
+        let rows = LINE.as_bytes().chunks(9).collect::<Vec<_>>();
+        let swapped = [3, 4, 5, 0, 1, 2, 6, 7, 8].map(|r| rows[r]).concat();
+        let disguised = (0..81)
+            .map(|i| match swapped[(i % 9) * 9 + i / 9] {
+                b'1' => '2',
+                b'2' => '1',
+                c => c as char,
+            })
+            .collect::<String>();
+        let disguised = Sudoku::from_str(&disguised).unwrap();
+        assert_ne!(disguised, s);
+        assert!(disguised.is_isomorphic_to(&s));
+        let canonical = s.canonicalize();
+        assert_eq!(canonical.canonicalize(), canonical);
+        assert_eq!(
+            canonical.iter().filter(|c| c.value().is_some()).count(),
+            s.iter().filter(|c| c.value().is_some()).count()
+        );
+        // Reorder stacks and the columns inside the first one.
+        let columns = [8, 6, 7, 2, 1, 0, 3, 4, 5];
+        let shuffled = (0..81)
+            .map(|i| LINE.as_bytes()[(i / 9) * 9 + columns[i % 9]] as char)
+            .collect::<String>();
+        assert!(Sudoku::from_str(&shuffled).unwrap().is_isomorphic_to(&s));
+        let other = Sudoku::from_str(&LINE.replacen('5', ".", 1)).unwrap();
+        assert!(!other.is_isomorphic_to(&s));
+    }
+
+    #[test]
+    fn canonical_hash_ignores_symmetry_and_relabelling() {
+        let s = Sudoku::from_str(LINE).unwrap();
+        let rotated = (0..81)
+            .map(|i| LINE.as_bytes()[(8 - i % 9) * 9 + i / 9] as char)
+            .collect::<String>();
+        let relabelled = LINE.replace('5', "x").replace('9', "5").replace('x', "9");
+        let rotated = Sudoku::from_str(&rotated).unwrap();
+        let relabelled = Sudoku::from_str(&relabelled).unwrap();
+        assert_ne!(rotated, s);
+        assert_eq!(rotated.canonical_hash(), s.canonical_hash());
+        assert_eq!(relabelled.canonical_hash(), s.canonical_hash());
+        let other = Sudoku::from_str(&LINE.replacen('5', ".", 1)).unwrap();
+        assert_ne!(other.canonical_hash(), s.canonical_hash());
+    }
+}
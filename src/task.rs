@@ -0,0 +1,249 @@
+//! Solving on a background thread, for async code that mustn't block its
+//! executor. Nothing here depends on a particular runtime: the solver gets a
+//! thread of its own and wakes the polling task when it has something.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::{SolveError, SolveOptions, SolveStep, Sudoku};
+
+// Steps the solver thread may run ahead of the consumer.
+const BUFFERED_STEPS: usize = 64;
+
+struct State<T> {
+    queue: VecDeque<T>,
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+struct Channel<T> {
+    state: Mutex<State<T>>,
+    space: Condvar,
+    cancel: AtomicBool,
+}
+
+impl<T> Channel<T> {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                finished: false,
+                waker: None,
+            }),
+            space: Condvar::new(),
+            cancel: AtomicBool::new(false),
+        })
+    }
+
+    // A panicking solver thread can't leave the state half updated, so a
+    // poisoned lock is still usable.
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // Waits for room in the queue. False if the receiver has gone away.
+    fn send(&self, item: T) -> bool {
+        let mut state = self.lock();
+        while state.queue.len() >= BUFFERED_STEPS && !self.cancel.load(Ordering::Relaxed) {
+            state = self.space.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        if self.cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        state.queue.push_back(item);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        true
+    }
+
+    fn finish(&self) {
+        let mut state = self.lock();
+        state.finished = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut state = self.lock();
+        if let Some(item) = state.queue.pop_front() {
+            self.space.notify_one();
+            return Poll::Ready(Some(item));
+        }
+        if state.finished {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    fn close(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        let _state = self.lock();
+        self.space.notify_all();
+    }
+}
+
+/// Resolves to the solution found on a background thread. Dropping it
+/// cancels the search.
+pub struct SolveFuture {
+    channel: Arc<Channel<Result<Sudoku, SolveError>>>,
+}
+
+impl Future for SolveFuture {
+    type Output = Result<Sudoku, SolveError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.channel.poll_recv(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(result),
+            // The thread only finishes after sending, unless it panicked.
+            Poll::Ready(None) => panic!("solver thread panicked"),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for SolveFuture {
+    fn drop(&mut self) {
+        self.channel.close();
+    }
+}
+
+/// The steps of [`Sudoku::solve_steps`], produced on a background thread.
+/// `poll_next` has the signature of the `Stream` trait from the `futures`
+/// crate, so `futures::stream::poll_fn` can wrap it. Dropping the stream
+/// stops the solver.
+pub struct SolveStepStream {
+    channel: Arc<Channel<SolveStep>>,
+}
+
+impl SolveStepStream {
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<SolveStep>> {
+        self.channel.poll_recv(cx)
+    }
+    /// The next step, or `None` once the solver is done.
+    pub fn next_step(&mut self) -> impl Future<Output = Option<SolveStep>> + '_ {
+        std::future::poll_fn(move |cx| Pin::new(&mut *self).poll_next(cx))
+    }
+}
+
+impl Drop for SolveStepStream {
+    fn drop(&mut self) {
+        self.channel.close();
+    }
+}
+
+// Marks the channel finished even if the solver panics, so the receiver
+// isn't left waiting forever.
+struct FinishOnDrop<T>(Arc<Channel<T>>);
+
+impl<T> Drop for FinishOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
+impl Sudoku {
+    /// Like [`Sudoku::solve_with_options`], run on a thread of its own. The
+    /// options' cancel token is ignored; drop the future instead.
+    pub fn solve_async(&self, options: SolveOptions) -> SolveFuture {
+        let channel = Channel::new();
+        let sender = FinishOnDrop(Arc::clone(&channel));
+        let board = self.clone();
+        let (max_nodes, timeout) = (options.max_nodes, options.timeout);
+        thread::spawn(move || {
+            let options = SolveOptions {
+                max_nodes,
+                timeout,
+                cancel: Some(&sender.0.cancel),
+            };
+            let result = board.solve_with_options(&options);
+            sender.0.send(result);
+        });
+        SolveFuture { channel }
+    }
+    pub fn solve_steps_async(&self) -> Result<SolveStepStream, SolveError> {
+        let steps = self.solve_steps()?;
+        let channel = Channel::new();
+        let sender = FinishOnDrop(Arc::clone(&channel));
+        thread::spawn(move || {
+            for step in steps {
+                if !sender.0.send(step) {
+                    break;
+                }
+            }
+        });
+        Ok(SolveStepStream { channel })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::task::Wake;
+    use std::thread::Thread;
+
+    use super::*;
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    const LINE: &str =
+        ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+
+    #[test]
+    fn solves_in_the_background() {
+        let s = Sudoku::from_str(LINE).unwrap();
+        let solved = block_on(s.solve_async(SolveOptions::default()));
+        assert_eq!(solved, s.solve_with_propagation());
+        let limited = SolveOptions {
+            max_nodes: Some(1),
+            ..SolveOptions::default()
+        };
+        let empty = Sudoku::new(crate::Size::CLASSIC);
+        assert_eq!(
+            block_on(empty.solve_async(limited)),
+            Err(SolveError::TimedOut)
+        );
+    }
+
+    #[test]
+    fn streams_the_same_steps() {
+        let s = Sudoku::from_str(LINE).unwrap();
+        let mut stream = s.solve_steps_async().unwrap();
+        let mut steps = Vec::new();
+        while let Some(step) = block_on(stream.next_step()) {
+            steps.push(step);
+        }
+        assert_eq!(steps, s.solve_steps().unwrap().collect::<Vec<_>>());
+        // Dropping a stream part way through stops its thread.
+        let mut stream = Sudoku::new(crate::Size::CLASSIC)
+            .solve_steps_async()
+            .unwrap();
+        assert!(block_on(stream.next_step()).is_some());
+        drop(stream);
+    }
+}
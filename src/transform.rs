@@ -0,0 +1,169 @@
+use crate::{Candidates, Pos, Sudoku, SudokuError};
+
+// Each operation keeps the board's size and rules and moves cells along with
+// their givens and pencil marks. Diagonals and windows aren't preserved by
+// every move, so variant boards may stop being valid.
+impl Sudoku {
+    // Builds the board whose cell at `pos` comes from `from(pos)`.
+    fn remap(&self, from: impl Fn(Pos) -> Pos) -> Sudoku {
+        let mut out = self.clone();
+        for pos in self.size().positions() {
+            let mut cell = self[from(pos)].clone();
+            cell.position = pos;
+            out[pos] = cell;
+        }
+        out.rebuild_masks();
+        out
+    }
+    fn last(&self) -> u8 {
+        self.size().side() - 1
+    }
+    pub fn transpose(&self) -> Sudoku {
+        self.remap(|p| Pos::new_unchecked(p.y(), p.x()))
+    }
+    /// A quarter turn clockwise.
+    pub fn rotate90(&self) -> Sudoku {
+        let m = self.last();
+        self.remap(|p| Pos::new_unchecked(p.y(), m - p.x()))
+    }
+    /// Flips left to right.
+    pub fn mirror_horizontal(&self) -> Sudoku {
+        let m = self.last();
+        self.remap(|p| Pos::new_unchecked(m - p.x(), p.y()))
+    }
+    /// Flips top to bottom.
+    pub fn mirror_vertical(&self) -> Sudoku {
+        let m = self.last();
+        self.remap(|p| Pos::new_unchecked(p.x(), m - p.y()))
+    }
+    /// Replaces each value `v` with `map[v - 1]`. `map` must hold every value
+    /// of the board exactly once.
+    pub fn permute_digits(&self, map: &[u8]) -> Result<Sudoku, SudokuError> {
+        let size = self.size();
+        if map.len() != size.side() as usize {
+            return Err(SudokuError::InvalidSize);
+        }
+        let mut seen = Candidates::empty();
+        for &v in map {
+            if !size.is_valid_value(v) || seen.contains(v) {
+                return Err(SudokuError::InvalidValue(v));
+            }
+            seen.insert(v);
+        }
+        let mut out = self.clone();
+        for cell in out.cells.iter_mut() {
+            cell.value = cell.value.map(|v| map[v as usize - 1]);
+            cell.pencil_marks = cell
+                .pencil_marks
+                .iter()
+                .map(|v| map[v as usize - 1])
+                .collect();
+        }
+        out.rebuild_masks();
+        Ok(out)
+    }
+    /// Swaps rows `a` and `b` of `band`, counting both from 0.
+    pub fn swap_rows_within_band(&self, band: u8, a: u8, b: u8) -> Result<Sudoku, SudokuError> {
+        let [a, b] = self.lines_within(band, a, b)?;
+        Ok(self.remap(|p| Pos::new_unchecked(p.x(), swapped(p.y(), a, b))))
+    }
+    /// Swaps columns `a` and `b` of `stack`, counting both from 0.
+    pub fn swap_columns_within_stack(
+        &self,
+        stack: u8,
+        a: u8,
+        b: u8,
+    ) -> Result<Sudoku, SudokuError> {
+        let [a, b] = self.lines_within(stack, a, b)?;
+        Ok(self.remap(|p| Pos::new_unchecked(swapped(p.x(), a, b), p.y())))
+    }
+    pub fn swap_bands(&self, a: u8, b: u8) -> Result<Sudoku, SudokuError> {
+        let n = self.size().box_size();
+        let [a, b] = self.lines_within(0, a, b)?;
+        Ok(self.remap(|p| Pos::new_unchecked(p.x(), swapped(p.y() / n, a, b) * n + p.y() % n)))
+    }
+    pub fn swap_stacks(&self, a: u8, b: u8) -> Result<Sudoku, SudokuError> {
+        let n = self.size().box_size();
+        let [a, b] = self.lines_within(0, a, b)?;
+        Ok(self.remap(|p| Pos::new_unchecked(swapped(p.x() / n, a, b) * n + p.x() % n, p.y())))
+    }
+
+    // Absolute indices of lines `a` and `b` inside band or stack `group`.
+    fn lines_within(&self, group: u8, a: u8, b: u8) -> Result<[u8; 2], SudokuError> {
+        let n = self.size().box_size();
+        if group >= n || a >= n || b >= n {
+            return Err(SudokuError::OutOfBounds);
+        }
+        Ok([group * n + a, group * n + b])
+    }
+}
+
+fn swapped(i: u8, a: u8, b: u8) -> u8 {
+    match i {
+        _ if i == a => b,
+        _ if i == b => a,
+        _ => i,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const LINE: &str =
+        ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4";
+
+    #[test]
+    fn geometric_moves_compose() {
+        let s = Sudoku::from_str(LINE).unwrap();
+        assert_eq!(s.transpose().transpose(), s);
+        assert_eq!(s.rotate90().rotate90().rotate90().rotate90(), s);
+        assert_eq!(s.rotate90(), s.transpose().mirror_horizontal());
+        assert_eq!(s.mirror_vertical()[(1, 8)].value(), Some(5));
+        assert!(s.transpose()[(0, 1)].is_given());
+        let solved = s.solve().unwrap();
+        assert_eq!(s.rotate90().solve(), Ok(solved.rotate90()));
+    }
+
+    #[test]
+    fn digit_permutations_are_checked() {
+        let s = Sudoku::from_str(LINE).unwrap();
+        let swapped = s.permute_digits(&[2, 1, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+        assert_eq!(swapped[(7, 0)].value(), Some(2));
+        assert_eq!(
+            swapped.permute_digits(&[2, 1, 3, 4, 5, 6, 7, 8, 9]),
+            Ok(s.clone())
+        );
+        assert_eq!(
+            s.permute_digits(&[1, 1, 3, 4, 5, 6, 7, 8, 9]),
+            Err(SudokuError::InvalidValue(1))
+        );
+        assert_eq!(s.permute_digits(&[1, 2]), Err(SudokuError::InvalidSize));
+    }
+
+    #[test]
+    fn line_swaps_keep_puzzles_valid() {
+        let s = Sudoku::from_str(LINE).unwrap();
+        let moved = s.swap_rows_within_band(1, 0, 2).unwrap();
+        assert_eq!(moved[(1, 5)].value(), s[(1, 3)].value());
+        assert_eq!(
+            s.swap_bands(0, 2).unwrap()[(0, 8)].value(),
+            s[(0, 2)].value()
+        );
+        assert_eq!(s.swap_stacks(0, 3), Err(SudokuError::OutOfBounds));
+        let variations = [
+            moved,
+            s.swap_columns_within_stack(2, 1, 0).unwrap(),
+            s.swap_bands(1, 2).unwrap(),
+            s.swap_stacks(0, 1).unwrap(),
+            s.mirror_vertical(),
+        ];
+        for v in variations {
+            assert!(v.is_valid());
+            assert!(v.is_isomorphic_to(&s));
+            assert_eq!(v.count_solutions(2), 1);
+        }
+    }
+}
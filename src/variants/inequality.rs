@@ -0,0 +1,284 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::killer::parse_cell;
+use crate::{
+    Candidates, Constraint, ConstraintSet, ParseError, Pos, Size, SolveError, Sudoku, SudokuError,
+};
+
+/// The value in `greater` is larger than the one in `less`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Inequality {
+    pub greater: Pos,
+    pub less: Pos,
+}
+
+impl Inequality {
+    pub fn new(greater: Pos, less: Pos) -> Self {
+        Self { greater, less }
+    }
+}
+
+impl Constraint for Inequality {
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        let side = board.size().side();
+        let (low, high) = if pos == self.greater {
+            (board[self.less].value().unwrap_or(1) + 1, side)
+        } else if pos == self.less {
+            (
+                1,
+                board[self.greater]
+                    .value()
+                    .unwrap_or(side)
+                    .saturating_sub(1),
+            )
+        } else {
+            return Candidates::empty();
+        };
+        Candidates::up_to(side)
+            .iter()
+            .filter(|&v| v < low || v > high)
+            .collect()
+    }
+    fn is_satisfied(&self, board: &Sudoku) -> bool {
+        match (board[self.greater].value(), board[self.less].value()) {
+            (Some(a), Some(b)) => a > b,
+            _ => true,
+        }
+    }
+}
+
+// Every inequality of a puzzle at once, so bounds carry along chains: in
+// `a > b > c`, `a` is at least 3.
+struct Chains(Vec<Inequality>);
+
+impl Chains {
+    // The lowest and highest value of every cell, in `Size::index_of`
+    // order. Runs until nothing changes; a cycle pushes the bounds past
+    // each other within `side` rounds.
+    fn bounds(&self, board: &Sudoku) -> Vec<(u8, u8)> {
+        let size = board.size();
+        let side = size.side();
+        let mut bounds = vec![(1, side); size.cell_count()];
+        for cell in board.iter() {
+            if let Some(v) = cell.value() {
+                bounds[size.index_of(cell.position())] = (v, v);
+            }
+        }
+        for _ in 0..=side {
+            let mut changed = false;
+            for i in &self.0 {
+                let (g, l) = (size.index_of(i.greater), size.index_of(i.less));
+                let low = bounds[l].0.saturating_add(1);
+                let high = bounds[g].1.saturating_sub(1);
+                if low > bounds[g].0 {
+                    bounds[g].0 = low;
+                    changed = true;
+                }
+                if high < bounds[l].1 {
+                    bounds[l].1 = high;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        bounds
+    }
+}
+
+impl Constraint for Chains {
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        if !self.0.iter().any(|i| i.greater == pos || i.less == pos) {
+            return Candidates::empty();
+        }
+        let (low, high) = self.bounds(board)[board.size().index_of(pos)];
+        Candidates::up_to(board.size().side())
+            .iter()
+            .filter(|&v| v < low || v > high)
+            .collect()
+    }
+    fn is_satisfied(&self, board: &Sudoku) -> bool {
+        self.bounds(board).iter().all(|&(low, high)| low <= high)
+    }
+}
+
+/// A board plus inequality signs between neighbouring cells, as in
+/// greater-than sudoku.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GreaterThan {
+    board: Sudoku,
+    inequalities: Vec<Inequality>,
+}
+
+impl GreaterThan {
+    /// Every inequality must be between two cells of the board sharing an
+    /// edge, with at most one per pair.
+    pub fn new(board: Sudoku, inequalities: Vec<Inequality>) -> Result<Self, SudokuError> {
+        let size = board.size();
+        for (n, i) in inequalities.iter().enumerate() {
+            if !size.contains(i.greater) || !size.contains(i.less) {
+                return Err(SudokuError::OutOfBounds);
+            }
+            let (a, b) = (i.greater, i.less);
+            let apart = a.x().abs_diff(b.x()) + a.y().abs_diff(b.y());
+            let pair =
+                |j: &Inequality| (j.greater == a && j.less == b) || (j.greater == b && j.less == a);
+            if apart != 1 || inequalities[..n].iter().any(pair) {
+                return Err(SudokuError::InvalidClue);
+            }
+        }
+        Ok(Self {
+            board,
+            inequalities,
+        })
+    }
+    pub fn board(&self) -> &Sudoku {
+        &self.board
+    }
+    pub fn inequalities(&self) -> &[Inequality] {
+        &self.inequalities
+    }
+    /// The cell's candidates, narrowed down by the chains of inequalities it
+    /// is part of.
+    pub fn candidates(&self, pos: Pos) -> Candidates {
+        self.constraints().candidates(&self.board, pos)
+    }
+    /// The board's houses plus one constraint covering every inequality.
+    pub fn constraints(&self) -> ConstraintSet {
+        ConstraintSet::standard(&self.board).with(Chains(self.inequalities.clone()))
+    }
+    pub fn is_valid(&self) -> bool {
+        self.constraints().is_satisfied(&self.board)
+    }
+    pub fn is_solved(&self) -> bool {
+        self.board.is_solved() && self.is_valid()
+    }
+    pub fn solve(&self) -> Result<Sudoku, SolveError> {
+        self.constraints().solve(&self.board)
+    }
+}
+
+/// One inequality per line between two neighbouring cells, either way
+/// round: `r1c1 > r1c2` or `r2c1 < r3c1`. Blank lines and lines starting
+/// with `#` are skipped. The board is an empty 9x9 one; use
+/// [`GreaterThan::new`] for puzzles with givens.
+impl core::str::FromStr for GreaterThan {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut inequalities = Vec::new();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let cells = |a: &str, b: &str| Some((parse_cell(a.trim())?, parse_cell(b.trim())?));
+            let parsed = match (line.split_once('>'), line.split_once('<')) {
+                (Some((a, b)), None) => cells(a, b),
+                (None, Some((a, b))) => cells(b, a),
+                _ => None,
+            };
+            match parsed {
+                Some((greater, less)) => inequalities.push(Inequality::new(greater, less)),
+                None => {
+                    return Err(SudokuError::ParseError(ParseError::Invalid(
+                        "Invalid inequality.",
+                    )))
+                }
+            }
+        }
+        GreaterThan::new(Sudoku::new(Size::CLASSIC), inequalities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn bounds_follow_chains() {
+        let g =
+            GreaterThan::from_str("# a staircase\nr1c1 > r1c2\nr1c2>r1c3\nr2c3 < r1c3\n").unwrap();
+        let values = |x, y| {
+            g.candidates(Pos::new_unchecked(x, y))
+                .iter()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(0, 0), (4..=9).collect::<Vec<_>>());
+        assert_eq!(values(1, 0), (3..=8).collect::<Vec<_>>());
+        assert_eq!(values(2, 1), (1..=6).collect::<Vec<_>>());
+        assert_eq!(values(5, 5).len(), 9);
+
+        let mut board = Sudoku::new(Size::CLASSIC);
+        board.set(Pos::new_unchecked(1, 0), Some(4)).unwrap();
+        let g = GreaterThan::new(board, g.inequalities().to_vec()).unwrap();
+        let values = |x, y| {
+            g.candidates(Pos::new_unchecked(x, y))
+                .iter()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(0, 0), (5..=9).collect::<Vec<_>>());
+        assert_eq!(values(2, 0), vec![2, 3]);
+        assert_eq!(values(2, 1), vec![1, 2]);
+        assert!(
+            Inequality::new(Pos::new_unchecked(0, 0), Pos::new_unchecked(1, 0))
+                .is_satisfied(g.board())
+        );
+    }
+
+    #[test]
+    fn rejects_bad_inequalities() {
+        assert_eq!(
+            GreaterThan::from_str("r1c1 > r1c3"),
+            Err(SudokuError::InvalidClue)
+        );
+        assert_eq!(
+            GreaterThan::from_str("r1c1 > r1c2\nr1c1 < r1c2"),
+            Err(SudokuError::InvalidClue)
+        );
+        assert_eq!(
+            GreaterThan::from_str("r1c1 = r1c2"),
+            Err(SudokuError::ParseError(ParseError::Invalid(
+                "Invalid inequality."
+            )))
+        );
+        let cycle = GreaterThan::from_str("r1c1 > r1c2\nr1c2 > r2c2\nr2c2 > r2c1\nr2c1 > r1c1");
+        let cycle = cycle.unwrap();
+        assert!(!cycle.is_valid());
+        assert_eq!(cycle.solve(), Err(SolveError::InvalidPuzzle));
+    }
+
+    #[test]
+    fn solves_greater_than_puzzle() {
+        let solution = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap()
+        .solve()
+        .unwrap();
+        // Every horizontal neighbour pair within a box.
+        let mut inequalities = Vec::new();
+        for pos in Size::CLASSIC.positions().filter(|p| p.x() % 3 != 2) {
+            let next = Pos::new_unchecked(pos.x() + 1, pos.y());
+            let (a, b) = (solution[pos].value(), solution[next].value());
+            inequalities.push(if a > b {
+                Inequality::new(pos, next)
+            } else {
+                Inequality::new(next, pos)
+            });
+        }
+        let givens = solution
+            .to_line_string()
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i % 4 == 0 { c } else { '.' })
+            .collect::<alloc::string::String>();
+        let g = GreaterThan::new(Sudoku::from_str(&givens).unwrap(), inequalities).unwrap();
+        let solved = g.solve().unwrap();
+        assert!(solved.is_solved());
+        assert!(g.inequalities().iter().all(|i| i.is_satisfied(&solved)));
+    }
+}
@@ -0,0 +1,255 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{
+    Candidates, Constraint, ConstraintSet, ParseError, Pos, Size, SolveError, Sudoku, SudokuError,
+};
+
+/// Cells whose values must be distinct and add up to `sum`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cage {
+    pub cells: Vec<Pos>,
+    pub sum: u8,
+}
+
+impl Cage {
+    pub fn new(cells: Vec<Pos>, sum: u8) -> Self {
+        Self { cells, sum }
+    }
+
+    fn values<'a>(&'a self, board: &'a Sudoku) -> impl Iterator<Item = u8> + 'a {
+        self.cells.iter().filter_map(|&p| board[p].value())
+    }
+
+    // Values that could still fill the empty cells, or `None` if the sum can't
+    // be reached any more.
+    fn options(&self, board: &Sudoku) -> Option<Candidates> {
+        let used = self.values(board).collect::<Candidates>();
+        let placed = self.values(board).map(u32::from).sum::<u32>();
+        let empty = self
+            .cells
+            .iter()
+            .filter(|&&p| board[p].value().is_none())
+            .count();
+        let allowed = Candidates::up_to(board.size().side()).difference(used);
+        combinations(u32::from(self.sum).checked_sub(placed)?, empty, 1, allowed)
+    }
+
+    /// Values that complete the sum together with distinct values not yet
+    /// used in the cage.
+    pub fn candidates(&self, board: &Sudoku) -> Candidates {
+        self.options(board).unwrap_or_default()
+    }
+
+    fn has_repeats(&self, board: &Sudoku) -> bool {
+        self.values(board).collect::<Candidates>().count() != self.values(board).count()
+    }
+}
+
+// A partly filled cage is fine as long as its sum can still be reached.
+impl Constraint for Cage {
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        if !self.cells.contains(&pos) {
+            return Candidates::empty();
+        }
+        Candidates::up_to(board.size().side()).difference(self.candidates(board))
+    }
+    fn is_satisfied(&self, board: &Sudoku) -> bool {
+        !self.has_repeats(board) && self.options(board).is_some()
+    }
+}
+
+// Union of every set of `count` distinct allowed values, all at least `min`,
+// that adds up to `sum`. `None` if there is no such set.
+pub(super) fn combinations(
+    sum: u32,
+    count: usize,
+    min: u8,
+    allowed: Candidates,
+) -> Option<Candidates> {
+    if count == 0 {
+        return (sum == 0).then(Candidates::empty);
+    }
+    let mut union = None;
+    for v in allowed.iter().filter(|&v| v >= min) {
+        if u32::from(v) > sum {
+            break;
+        }
+        if let Some(mut rest) = combinations(sum - u32::from(v), count - 1, v + 1, allowed) {
+            rest.insert(v);
+            union = Some(union.map_or(rest, |u| u | rest));
+        }
+    }
+    union
+}
+
+/// A board plus the cages laid over it. Killer puzzles usually have no givens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Killer {
+    board: Sudoku,
+    cages: Vec<Cage>,
+    // Index into `cages` for every cell, in `Size::index_of` order.
+    cage_of: Vec<Option<usize>>,
+}
+
+impl Killer {
+    /// Cages must stay on the board, be non-empty and not share cells.
+    pub fn new(board: Sudoku, cages: Vec<Cage>) -> Result<Self, SudokuError> {
+        let size = board.size();
+        let mut cage_of = vec![None; size.cell_count()];
+        for (i, cage) in cages.iter().enumerate() {
+            if cage.cells.is_empty() || cage.cells.len() > size.side() as usize {
+                return Err(SudokuError::InvalidCage);
+            }
+            for &pos in &cage.cells {
+                if !size.contains(pos) {
+                    return Err(SudokuError::OutOfBounds);
+                }
+                let slot = &mut cage_of[size.index_of(pos)];
+                if slot.is_some() {
+                    return Err(SudokuError::InvalidCage);
+                }
+                *slot = Some(i);
+            }
+        }
+        Ok(Self {
+            board,
+            cages,
+            cage_of,
+        })
+    }
+    pub fn board(&self) -> &Sudoku {
+        &self.board
+    }
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+    pub fn cage_at(&self, pos: Pos) -> Option<&Cage> {
+        self.cage_of[self.board.size().index_of(pos)].map(|i| &self.cages[i])
+    }
+    /// The cell's candidates, narrowed down by its cage's sum.
+    pub fn candidates(&self, pos: Pos) -> Candidates {
+        self.constraints().candidates(&self.board, pos)
+    }
+    /// The board's houses plus one constraint per cage.
+    pub fn constraints(&self) -> ConstraintSet {
+        let mut set = ConstraintSet::standard(&self.board);
+        for cage in &self.cages {
+            set.push(cage.clone());
+        }
+        set
+    }
+    /// No repeated values in any house or cage, and every cage sum still reachable.
+    pub fn is_valid(&self) -> bool {
+        self.constraints().is_satisfied(&self.board)
+    }
+    pub fn is_solved(&self) -> bool {
+        self.board.is_solved() && self.is_valid()
+    }
+    pub fn solve(&self) -> Result<Sudoku, SolveError> {
+        self.constraints().solve(&self.board)
+    }
+}
+
+// `r3c4` style, with rows and columns counted from 1.
+pub(super) fn parse_cell(s: &str) -> Option<Pos> {
+    let (row, col) = s.strip_prefix(['r', 'R'])?.split_once(['c', 'C'])?;
+    let y = row.parse::<u8>().ok()?.checked_sub(1)?;
+    let x = col.parse::<u8>().ok()?.checked_sub(1)?;
+    Pos::try_new(x, y).ok()
+}
+
+/// One cage per line: its sum followed by its cells, e.g. `10 r1c1 r1c2`.
+/// Blank lines and lines starting with `#` are skipped. The board is an
+/// empty 9x9 one; use [`Killer::new`] for puzzles with givens.
+impl core::str::FromStr for Killer {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cages = Vec::new();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let sum = words.next().and_then(|w| w.parse::<u8>().ok());
+            let cells = words.map(parse_cell).collect::<Option<Vec<_>>>();
+            match (sum, cells) {
+                (Some(sum), Some(cells)) => cages.push(Cage::new(cells, sum)),
+                _ => {
+                    return Err(SudokuError::ParseError(ParseError::Invalid(
+                        "Invalid cage description.",
+                    )))
+                }
+            }
+        }
+        Killer::new(Sudoku::new(Size::CLASSIC), cages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn cage_sums_narrow_candidates() {
+        let k = Killer::from_str("# two cells adding to 3\n3 r1c1 r1c2\n\n17 r2c1 r2c2\n").unwrap();
+        assert_eq!(k.cages().len(), 2);
+        let pos = Pos::new_unchecked(1, 0);
+        assert_eq!(k.candidates(pos).iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            k.candidates(Pos::new_unchecked(0, 1))
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![8, 9]
+        );
+        assert_eq!(k.cage_at(Pos::new_unchecked(5, 5)), None);
+    }
+
+    #[test]
+    fn rejects_bad_cages() {
+        assert_eq!(
+            Killer::from_str("3 r1c1 r1c2\n5 r1c2 r1c3"),
+            Err(SudokuError::InvalidCage)
+        );
+        assert_eq!(
+            Killer::from_str("3 r1c1 r0c2"),
+            Err(SudokuError::ParseError(ParseError::Invalid(
+                "Invalid cage description."
+            )))
+        );
+        assert_eq!(Killer::from_str("3"), Err(SudokuError::InvalidCage));
+        let k = Killer::from_str("30 r1c1 r1c2").unwrap();
+        assert!(!k.is_valid());
+        assert_eq!(k.solve(), Err(SolveError::InvalidPuzzle));
+    }
+
+    #[test]
+    fn solves_killer_puzzle() {
+        // Pairs along each row plus a single cell at the end, summed from a
+        // known solution.
+        let solution = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap()
+        .solve()
+        .unwrap();
+        let mut cages = Vec::new();
+        for y in 0..9 {
+            for x in (0..9).step_by(2) {
+                let cells = (x..(x + 2).min(9))
+                    .map(|x| Pos::new_unchecked(x, y))
+                    .collect::<Vec<_>>();
+                let sum = cells.iter().map(|&p| solution[p].value().unwrap()).sum();
+                cages.push(Cage::new(cells, sum));
+            }
+        }
+        let k = Killer::new(Sudoku::new(Size::CLASSIC), cages).unwrap();
+        let solved = k.solve().unwrap();
+        assert!(solved.is_solved());
+        assert!(k.cages().iter().all(|c| c.is_satisfied(&solved)));
+        assert!(!k.is_solved());
+    }
+}
@@ -0,0 +1,347 @@
+use alloc::vec::Vec;
+
+use super::killer::parse_cell;
+use crate::{
+    Candidates, Constraint, ConstraintSet, ParseError, Pos, Size, SolveError, Sudoku, SudokuError,
+};
+
+/// Values strictly increase from the bulb, the first cell, to the tip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Thermometer(pub Vec<Pos>);
+
+impl Thermometer {
+    // The lowest and highest value the cell at `i` can take given its place
+    // on the thermometer and the values already placed along it.
+    fn range(&self, board: &Sudoku, i: usize) -> (u8, u8) {
+        let side = board.size().side();
+        let last = self.0.len() - 1;
+        let mut low = i as u8 + 1;
+        let mut high = side.saturating_sub((last - i) as u8);
+        for (j, &p) in self.0.iter().enumerate() {
+            match (board[p].value(), j.cmp(&i)) {
+                (Some(v), core::cmp::Ordering::Less) => low = low.max(v + (i - j) as u8),
+                (Some(v), core::cmp::Ordering::Greater) => {
+                    high = high.min(v.saturating_sub((j - i) as u8))
+                }
+                _ => {}
+            }
+        }
+        (low, high)
+    }
+}
+
+impl Constraint for Thermometer {
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        let Some(i) = self.0.iter().position(|&p| p == pos) else {
+            return Candidates::empty();
+        };
+        let (low, high) = self.range(board, i);
+        Candidates::up_to(board.size().side())
+            .iter()
+            .filter(|&v| v < low || v > high)
+            .collect()
+    }
+    fn is_satisfied(&self, board: &Sudoku) -> bool {
+        (0..self.0.len()).all(|i| {
+            let (low, high) = self.range(board, i);
+            low <= high
+                && board[self.0[i]]
+                    .value()
+                    .is_none_or(|v| (low..=high).contains(&v))
+        })
+    }
+}
+
+/// The values along `path` add up to the value in `bulb`. Path values may
+/// repeat unless a house forbids it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Arrow {
+    pub bulb: Pos,
+    pub path: Vec<Pos>,
+}
+
+impl Arrow {
+    // The smallest and largest sums the path's empty cells other than
+    // `skip` can add to the placed ones.
+    fn sums(&self, board: &Sudoku, skip: Option<Pos>) -> (u32, u32) {
+        let side = u32::from(board.size().side());
+        self.path
+            .iter()
+            .filter(|&&p| Some(p) != skip)
+            .fold((0, 0), |(low, high), &p| match board[p].value() {
+                Some(v) => (low + u32::from(v), high + u32::from(v)),
+                None => (low + 1, high + side),
+            })
+    }
+    // The range of values for the cell at `pos`, which is on the arrow.
+    fn range(&self, board: &Sudoku, pos: Pos) -> (u32, u32) {
+        let side = u32::from(board.size().side());
+        if pos == self.bulb {
+            let (low, high) = self.sums(board, None);
+            return (low, high.min(side));
+        }
+        let (low, high) = self.sums(board, Some(pos));
+        match board[self.bulb].value() {
+            Some(bulb) => (
+                u32::from(bulb).saturating_sub(high).max(1),
+                u32::from(bulb).saturating_sub(low),
+            ),
+            None => (1, side.saturating_sub(low)),
+        }
+    }
+}
+
+impl Constraint for Arrow {
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        if pos != self.bulb && !self.path.contains(&pos) {
+            return Candidates::empty();
+        }
+        let (low, high) = self.range(board, pos);
+        Candidates::up_to(board.size().side())
+            .iter()
+            .filter(|&v| u32::from(v) < low || u32::from(v) > high)
+            .collect()
+    }
+    fn is_satisfied(&self, board: &Sudoku) -> bool {
+        let (low, high) = self.sums(board, None);
+        match board[self.bulb].value() {
+            Some(bulb) => (low..=high).contains(&u32::from(bulb)),
+            None => low <= u32::from(board.size().side()),
+        }
+    }
+}
+
+/// A board plus the thermometers and arrows drawn on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinePuzzle {
+    board: Sudoku,
+    thermometers: Vec<Thermometer>,
+    arrows: Vec<Arrow>,
+}
+
+impl LinePuzzle {
+    /// Every line needs at least two cells, all on the board and none
+    /// repeated; thermometers can't be longer than the board's side.
+    pub fn new(
+        board: Sudoku,
+        thermometers: Vec<Thermometer>,
+        arrows: Vec<Arrow>,
+    ) -> Result<Self, SudokuError> {
+        let size = board.size();
+        let lines = thermometers
+            .iter()
+            .map(|t| t.0.clone())
+            .chain(arrows.iter().map(|a| [&[a.bulb][..], &a.path].concat()));
+        for cells in lines {
+            if cells.len() < 2 {
+                return Err(SudokuError::InvalidLine);
+            }
+            if cells.iter().any(|&p| !size.contains(p)) {
+                return Err(SudokuError::OutOfBounds);
+            }
+            if (1..cells.len()).any(|i| cells[..i].contains(&cells[i])) {
+                return Err(SudokuError::InvalidLine);
+            }
+        }
+        if thermometers
+            .iter()
+            .any(|t| t.0.len() > size.side() as usize)
+        {
+            return Err(SudokuError::InvalidLine);
+        }
+        Ok(Self {
+            board,
+            thermometers,
+            arrows,
+        })
+    }
+    pub fn board(&self) -> &Sudoku {
+        &self.board
+    }
+    pub fn thermometers(&self) -> &[Thermometer] {
+        &self.thermometers
+    }
+    pub fn arrows(&self) -> &[Arrow] {
+        &self.arrows
+    }
+    /// The cell's candidates, narrowed down by the lines through it.
+    pub fn candidates(&self, pos: Pos) -> Candidates {
+        self.constraints().candidates(&self.board, pos)
+    }
+    /// The board's houses plus one constraint per line.
+    pub fn constraints(&self) -> ConstraintSet {
+        let mut set = ConstraintSet::standard(&self.board);
+        for t in &self.thermometers {
+            set.push(t.clone());
+        }
+        for a in &self.arrows {
+            set.push(a.clone());
+        }
+        set
+    }
+    pub fn is_valid(&self) -> bool {
+        self.constraints().is_satisfied(&self.board)
+    }
+    pub fn is_solved(&self) -> bool {
+        self.board.is_solved() && self.is_valid()
+    }
+    pub fn solve(&self) -> Result<Sudoku, SolveError> {
+        self.constraints().solve(&self.board)
+    }
+}
+
+/// One line per thermometer or arrow, listing its cells from the bulb:
+/// `thermo r1c1 r1c2 r1c3` or `arrow r5c5 r5c6 r5c7`. Blank lines and lines
+/// starting with `#` are skipped. The board is an empty 9x9 one; use
+/// [`LinePuzzle::new`] for puzzles with givens.
+impl core::str::FromStr for LinePuzzle {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            SudokuError::ParseError(ParseError::Invalid(
+                "Invalid thermometer or arrow description.",
+            ))
+        };
+        let mut thermometers = Vec::new();
+        let mut arrows = Vec::new();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let kind = words.next().map(str::to_ascii_lowercase);
+            let cells = words
+                .map(parse_cell)
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(invalid)?;
+            match (kind.as_deref(), cells.split_first()) {
+                (Some("thermo"), _) => thermometers.push(Thermometer(cells)),
+                (Some("arrow"), Some((&bulb, path))) => arrows.push(Arrow {
+                    bulb,
+                    path: path.to_vec(),
+                }),
+                (Some("arrow"), None) => return Err(SudokuError::InvalidLine),
+                _ => return Err(invalid()),
+            }
+        }
+        LinePuzzle::new(Sudoku::new(Size::CLASSIC), thermometers, arrows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn lines_narrow_candidates() {
+        let puzzle = LinePuzzle::from_str(
+            "# a thermometer down the first column\nthermo r1c1 r2c1 r3c1 r4c1\n\n\
+             arrow r5c5 r5c6 r5c7\n",
+        )
+        .unwrap();
+        let values = |pos| puzzle.candidates(pos).iter().collect::<Vec<_>>();
+        assert_eq!(values(Pos::new_unchecked(0, 0)), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(values(Pos::new_unchecked(0, 3)), vec![4, 5, 6, 7, 8, 9]);
+        assert_eq!(
+            values(Pos::new_unchecked(4, 4)),
+            vec![2, 3, 4, 5, 6, 7, 8, 9]
+        );
+        assert_eq!(
+            values(Pos::new_unchecked(5, 4)),
+            (1..=8).collect::<Vec<_>>()
+        );
+
+        let mut board = puzzle.board().clone();
+        board.set(Pos::new_unchecked(0, 1), Some(5)).unwrap();
+        board.set(Pos::new_unchecked(4, 4), Some(4)).unwrap();
+        let puzzle = LinePuzzle::new(
+            board,
+            puzzle.thermometers().to_vec(),
+            puzzle.arrows().to_vec(),
+        )
+        .unwrap();
+        let values = |pos| puzzle.candidates(pos).iter().collect::<Vec<_>>();
+        assert_eq!(values(Pos::new_unchecked(0, 0)), vec![1, 2, 3, 4]);
+        assert_eq!(values(Pos::new_unchecked(0, 2)), vec![6, 7, 8]);
+        assert_eq!(values(Pos::new_unchecked(5, 4)), vec![1, 2, 3]);
+        assert!(puzzle.is_valid());
+    }
+
+    #[test]
+    fn rejects_broken_lines() {
+        assert_eq!(
+            LinePuzzle::from_str("thermo r1c1"),
+            Err(SudokuError::InvalidLine)
+        );
+        assert_eq!(
+            LinePuzzle::from_str("arrow r1c1 r1c2 r1c1"),
+            Err(SudokuError::InvalidLine)
+        );
+        assert_eq!(
+            LinePuzzle::from_str("snake r1c1 r1c2"),
+            Err(SudokuError::ParseError(ParseError::Invalid(
+                "Invalid thermometer or arrow description."
+            )))
+        );
+        let mut board = Sudoku::new(Size::CLASSIC);
+        board.set(Pos::new_unchecked(0, 0), Some(3)).unwrap();
+        board.set(Pos::new_unchecked(1, 0), Some(2)).unwrap();
+        let thermo = Thermometer(vec![Pos::new_unchecked(0, 0), Pos::new_unchecked(1, 0)]);
+        let puzzle = LinePuzzle::new(board, vec![thermo], Vec::new()).unwrap();
+        assert!(!puzzle.is_valid());
+        assert_eq!(puzzle.solve(), Err(SolveError::InvalidPuzzle));
+    }
+
+    #[test]
+    fn solves_with_thermometers_and_arrows() {
+        let puzzle = LinePuzzle::from_str(
+            "thermo r1c1 r2c2 r3c3 r4c4 r5c5\n\
+             thermo r9c1 r8c1 r7c1\n\
+             arrow r1c9 r2c9 r3c9\n\
+             arrow r9c9 r9c8 r9c7 r8c7\n",
+        )
+        .unwrap();
+        let solved = puzzle.solve().unwrap();
+        assert!(solved.is_solved() && solved.is_valid());
+        assert!(puzzle
+            .thermometers()
+            .iter()
+            .all(|t| t.is_satisfied(&solved)));
+        assert!(puzzle.arrows().iter().all(|a| a.is_satisfied(&solved)));
+        let tip = |t: &Thermometer| solved[*t.0.last().unwrap()].value();
+        assert!(tip(&puzzle.thermometers()[0]) >= Some(5));
+        let r9c9 = solved[Pos::new_unchecked(8, 8)].value().unwrap();
+        let path = [(7, 8), (6, 8), (6, 7)]
+            .iter()
+            .map(|&(x, y)| solved[Pos::new_unchecked(x, y)].value().unwrap())
+            .sum::<u8>();
+        assert_eq!(r9c9, path);
+    }
+
+    #[test]
+    fn arrow_paths_reach_the_largest_value() {
+        // With the rest of the path at 1 each, a path cell can still take
+        // everything the bulb leaves.
+        let grid = Sudoku::random_solution_with_seed(1);
+        let nine = |y| {
+            (0..9)
+                .map(|x| Pos::new_unchecked(x, y))
+                .find(|&p| grid[p].value() == Some(9))
+                .unwrap()
+        };
+        let (bulb, tip) = (nine(0), nine(4));
+        let mut board = grid.clone();
+        board.set_forced(bulb, None).unwrap();
+        board.set_forced(tip, None).unwrap();
+        let arrow = Arrow {
+            bulb,
+            path: vec![tip],
+        };
+        let puzzle = LinePuzzle::new(board.clone(), Vec::new(), vec![arrow]).unwrap();
+        assert!(puzzle.candidates(tip).contains(9));
+        assert_eq!(puzzle.solve(), Ok(grid));
+    }
+}
@@ -0,0 +1,13 @@
+//! Puzzles that add rules on top of the classic row, column and box ones.
+
+pub mod inequality;
+pub mod killer;
+pub mod lines;
+pub mod samurai;
+pub mod sandwich;
+
+pub use inequality::{GreaterThan, Inequality};
+pub use killer::{Cage, Killer};
+pub use lines::{Arrow, LinePuzzle, Thermometer};
+pub use samurai::Samurai;
+pub use sandwich::{Sandwich, SandwichClue};
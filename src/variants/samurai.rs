@@ -0,0 +1,407 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{symbol, value_of, ParseError, Pos, Size, SolveError, Sudoku, SudokuError};
+
+/// Cells along each side of the area the five grids cover.
+pub const SIDE: u8 = 21;
+
+/// Top left corners of the five grids: the four corners first, row by row,
+/// then the middle one, which shares its corner boxes with them.
+pub const GRIDS: [(u8, u8); 5] = [(0, 0), (12, 0), (0, 12), (12, 12), (6, 6)];
+
+/// Five overlapping 9x9 grids. Positions are on the 21x21 area they cover;
+/// cells in the gaps between the corner grids aren't part of the puzzle.
+/// Each grid follows the classic rules, so a value in a shared box counts
+/// for both grids it is in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Samurai {
+    // Every cell of the area in row-major order, gaps included.
+    values: Vec<Option<u8>>,
+    givens: Vec<bool>,
+}
+
+impl Default for Samurai {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Samurai {
+    pub fn new() -> Self {
+        let area = SIDE as usize * SIDE as usize;
+        Self {
+            values: vec![None; area],
+            givens: vec![false; area],
+        }
+    }
+    /// Puts the five grids together, in the order of [`GRIDS`]. Fails with
+    /// `Conflict` if two grids disagree about a shared cell.
+    pub fn from_grids(grids: [Sudoku; 5]) -> Result<Self, SudokuError> {
+        let mut samurai = Self::new();
+        for (grid, &(left, top)) in grids.iter().zip(&GRIDS) {
+            if grid.size() != Size::CLASSIC {
+                return Err(SudokuError::InvalidSize);
+            }
+            for cell in grid.iter() {
+                let pos = cell.position();
+                let i = index(Pos::new_unchecked(left + pos.x(), top + pos.y()));
+                match (samurai.values[i], cell.value()) {
+                    (Some(a), Some(b)) if a != b => return Err(SudokuError::Conflict(b)),
+                    (None, value) => samurai.values[i] = value,
+                    _ => {}
+                }
+                samurai.givens[i] |= cell.is_given();
+            }
+        }
+        Ok(samurai)
+    }
+    /// Whether `pos` is in one of the grids.
+    pub fn contains(pos: Pos) -> bool {
+        !grids_of(pos).is_empty()
+    }
+    /// Every position of the puzzle in row-major order.
+    pub fn positions() -> impl Iterator<Item = Pos> {
+        (0..SIDE as usize * SIDE as usize)
+            .map(|i| Pos::new_unchecked((i % SIDE as usize) as u8, (i / SIDE as usize) as u8))
+            .filter(|&p| Self::contains(p))
+    }
+    /// `None` for empty cells and positions outside the grids.
+    pub fn get(&self, pos: Pos) -> Option<u8> {
+        if Self::contains(pos) {
+            self.values[index(pos)]
+        } else {
+            None
+        }
+    }
+    pub fn is_given(&self, pos: Pos) -> bool {
+        Self::contains(pos) && self.givens[index(pos)]
+    }
+    /// Givens can't be changed; placing a value doesn't check it against
+    /// its peers.
+    pub fn set(&mut self, pos: Pos, value: Option<u8>) -> Result<(), SudokuError> {
+        if !Self::contains(pos) {
+            return Err(SudokuError::OutOfBounds);
+        }
+        match value {
+            Some(v) if !Size::CLASSIC.is_valid_value(v) => Err(SudokuError::InvalidValue(v)),
+            _ if self.givens[index(pos)] => Err(SudokuError::GivenCell),
+            _ => {
+                self.values[index(pos)] = value;
+                Ok(())
+            }
+        }
+    }
+    /// One of the five grids, numbered as in [`GRIDS`], as a board of its
+    /// own. Panics if `n` is 5 or more.
+    pub fn grid(&self, n: usize) -> Sudoku {
+        let (left, top) = GRIDS[n];
+        let cells = Size::CLASSIC
+            .positions()
+            .map(|p| index(Pos::new_unchecked(left + p.x(), top + p.y())))
+            .collect::<Vec<_>>();
+        let mut grid = Sudoku::from_values(Size::CLASSIC, cells.iter().map(|&i| self.values[i]));
+        for (cell, &i) in grid.cells.iter_mut().zip(&cells) {
+            cell.given = self.givens[i];
+        }
+        grid
+    }
+    pub fn grids(&self) -> [Sudoku; 5] {
+        core::array::from_fn(|n| self.grid(n))
+    }
+    /// No value repeats in any row, column or box of any of the grids.
+    pub fn is_valid(&self) -> bool {
+        (0..5).all(|n| self.grid(n).is_valid())
+    }
+    pub fn is_solved(&self) -> bool {
+        Self::positions().all(|p| self.get(p).is_some()) && self.is_valid()
+    }
+    /// Solves all five grids at once, so the shared boxes satisfy both of
+    /// the grids they belong to.
+    pub fn solve(&self) -> Result<Samurai, SolveError> {
+        if !self.is_valid() {
+            return Err(SolveError::InvalidPuzzle);
+        }
+        let mut search = Search::new(self);
+        if !search.fill() {
+            return Err(SolveError::NoSolution);
+        }
+        let mut solved = self.clone();
+        for (i, &v) in search.values.iter().enumerate() {
+            if v != 0 {
+                solved.values[i] = Some(v);
+            }
+        }
+        Ok(solved)
+    }
+    /// The 21x21 layout [`FromStr`](core::str::FromStr) reads, with spaces
+    /// in the gaps.
+    pub fn to_layout_string(&self) -> String {
+        let mut out = String::new();
+        for y in 0..SIDE {
+            let row = (0..SIDE)
+                .map(|x| {
+                    let pos = Pos::new_unchecked(x, y);
+                    match self.get(pos) {
+                        Some(v) => symbol(v),
+                        None if Self::contains(pos) => '.',
+                        None => ' ',
+                    }
+                })
+                .collect::<String>();
+            out.push_str(row.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The usual text layout: 21 rows of 21 characters, with `.` or `0` for
+/// empty cells and spaces in the gaps between the corner grids. Lines may
+/// stop after their last cell, and blank lines are skipped.
+impl core::str::FromStr for Samurai {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut samurai = Self::new();
+        let mut y = 0;
+        let mut offset = 0;
+        for line in s.split_inclusive('\n') {
+            let start = offset;
+            offset += line.chars().count();
+            if line.trim().is_empty() {
+                continue;
+            }
+            if y == SIDE {
+                return Err(
+                    ParseError::Invalid("Expected the 21 rows of a samurai layout.").into(),
+                );
+            }
+            let mut x = 0;
+            for (i, c) in line.trim_end().chars().enumerate() {
+                let pos = (x < SIDE)
+                    .then(|| Pos::new_unchecked(x, y))
+                    .filter(|&p| Self::contains(p));
+                let err = || SudokuError::from(ParseError::at(s, start + i));
+                match (pos, c) {
+                    (None, ' ') => {}
+                    (Some(_), '.' | '0') => {}
+                    (Some(pos), c) => {
+                        let v = value_of(c)
+                            .filter(|&v| Size::CLASSIC.is_valid_value(v))
+                            .ok_or_else(err)?;
+                        samurai.values[index(pos)] = Some(v);
+                        samurai.givens[index(pos)] = true;
+                    }
+                    (None, _) => return Err(err()),
+                }
+                x = x.saturating_add(1);
+            }
+            if (x..SIDE).any(|x| Self::contains(Pos::new_unchecked(x, y))) {
+                return Err(ParseError::Invalid("Samurai row is missing cells.").into());
+            }
+            y += 1;
+        }
+        if y != SIDE {
+            return Err(ParseError::Invalid("Expected the 21 rows of a samurai layout.").into());
+        }
+        Ok(samurai)
+    }
+}
+
+impl core::fmt::Display for Samurai {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.to_layout_string())
+    }
+}
+
+fn index(pos: Pos) -> usize {
+    pos.y() as usize * SIDE as usize + pos.x() as usize
+}
+
+// The grids `pos` is in, by number.
+fn grids_of(pos: Pos) -> Vec<usize> {
+    (0..5)
+        .filter(|&n| {
+            let (left, top) = GRIDS[n];
+            (left..left + 9).contains(&pos.x()) && (top..top + 9).contains(&pos.y())
+        })
+        .collect()
+}
+
+// Backtracking over the most constrained cell, with the values used in
+// every row, column and box of every grid kept as bit masks.
+struct Search {
+    values: Vec<u8>,
+    // For each cell of the area, the houses it is in: 27 per grid, rows
+    // then columns then boxes.
+    houses: Vec<Vec<usize>>,
+    used: Vec<u16>,
+}
+
+impl Search {
+    fn new(samurai: &Samurai) -> Self {
+        let area = SIDE as usize * SIDE as usize;
+        let mut houses = vec![Vec::new(); area];
+        for pos in Samurai::positions() {
+            for n in grids_of(pos) {
+                let (left, top) = GRIDS[n];
+                let (x, y) = ((pos.x() - left) as usize, (pos.y() - top) as usize);
+                houses[index(pos)].extend([
+                    n * 27 + y,
+                    n * 27 + 9 + x,
+                    n * 27 + 18 + (y / 3) * 3 + x / 3,
+                ]);
+            }
+        }
+        let mut search = Self {
+            values: vec![0; area],
+            houses,
+            used: vec![0; 5 * 27],
+        };
+        for pos in Samurai::positions() {
+            if let Some(v) = samurai.get(pos) {
+                search.place(index(pos), v);
+            }
+        }
+        search
+    }
+
+    fn place(&mut self, i: usize, v: u8) {
+        self.values[i] = v;
+        for &h in &self.houses[i] {
+            self.used[h] |= 1 << v;
+        }
+    }
+
+    fn unplace(&mut self, i: usize) {
+        let v = core::mem::take(&mut self.values[i]);
+        for &h in &self.houses[i] {
+            self.used[h] &= !(1 << v);
+        }
+    }
+
+    fn candidates(&self, i: usize) -> u16 {
+        let used = self.houses[i].iter().fold(0, |u, &h| u | self.used[h]);
+        !used & 0b11_1111_1110
+    }
+
+    fn fill(&mut self) -> bool {
+        let mut best: Option<(usize, u16)> = None;
+        for i in 0..self.values.len() {
+            if self.values[i] != 0 || self.houses[i].is_empty() {
+                continue;
+            }
+            let free = self.candidates(i);
+            // Ties go to the shared boxes, where mistakes are found latest.
+            let better = best.is_none_or(|(j, b)| {
+                (free.count_ones(), usize::MAX - self.houses[i].len())
+                    < (b.count_ones(), usize::MAX - self.houses[j].len())
+            });
+            if better {
+                best = Some((i, free));
+                if free.count_ones() <= 1 {
+                    break;
+                }
+            }
+        }
+        let Some((i, free)) = best else {
+            return true;
+        };
+        for v in 1..=9 {
+            if free & (1 << v) != 0 {
+                self.place(i, v);
+                if self.fill() {
+                    return true;
+                }
+                self.unplace(i);
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn grids_share_their_corner_boxes() {
+        assert_eq!(Samurai::positions().count(), 5 * 81 - 4 * 9);
+        assert!(!Samurai::contains(Pos::new_unchecked(10, 2)));
+        assert!(Samurai::contains(Pos::new_unchecked(10, 7)));
+        assert_eq!(grids_of(Pos::new_unchecked(7, 7)), vec![0, 4]);
+
+        let mut s = Samurai::new();
+        s.set(Pos::new_unchecked(7, 7), Some(4)).unwrap();
+        assert_eq!(s.grid(0)[(7, 7)].value(), Some(4));
+        assert_eq!(s.grid(4)[(1, 1)].value(), Some(4));
+        assert_eq!(
+            s.set(Pos::new_unchecked(10, 2), Some(1)),
+            Err(SudokuError::OutOfBounds)
+        );
+        // The middle grid's row through the shared box sees the 4.
+        s.set(Pos::new_unchecked(12, 7), Some(4)).unwrap();
+        assert!(!s.is_valid());
+        assert_eq!(Samurai::from_grids(s.grids()), Ok(s));
+    }
+
+    #[test]
+    fn solves_across_the_shared_boxes() {
+        // The classic pattern solution carries on across the whole area, as
+        // every grid starts on a multiple of three.
+        let mut full = Samurai::new();
+        for pos in Samurai::positions() {
+            let (x, y) = (pos.x(), pos.y());
+            full.set(pos, Some((3 * (y % 3) + y / 3 + x) % 9 + 1))
+                .unwrap();
+        }
+        assert!(full.is_solved());
+        assert!(full.grids().iter().all(|g| g.is_solved()));
+        assert!(Samurai::new().solve().unwrap().is_solved());
+
+        // Every third cell as a clue.
+        let mut puzzle = Samurai::new();
+        for (n, pos) in Samurai::positions().enumerate() {
+            if n % 3 == 0 {
+                puzzle.set(pos, full.get(pos)).unwrap();
+            }
+        }
+        let puzzle = Samurai::from_str(&puzzle.to_layout_string()).unwrap();
+        assert!(puzzle.is_given(Pos::new_unchecked(0, 0)));
+        let solved = puzzle.solve().unwrap();
+        assert!(solved.is_solved());
+        assert!(Samurai::positions()
+            .filter(|&p| puzzle.is_given(p))
+            .all(|p| solved.get(p) == puzzle.get(p)));
+    }
+
+    #[test]
+    fn parses_the_text_layout() {
+        let layout = Samurai::new().to_layout_string();
+        let lines = layout.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 21);
+        assert_eq!(lines[0], ".........   .........");
+        assert_eq!(lines[9], "      .........");
+        let mut text = layout.replacen('.', "5", 1);
+        let s = Samurai::from_str(&text).unwrap();
+        assert_eq!(s.get(Pos::new_unchecked(0, 0)), Some(5));
+        assert_eq!(s.to_string(), text);
+
+        text.replace_range(9..10, "x");
+        assert!(matches!(
+            Samurai::from_str(&text),
+            Err(SudokuError::ParseError(ParseError::InvalidCharacter {
+                character: 'x',
+                row: 1,
+                column: 10,
+                ..
+            }))
+        ));
+        let short = lines[..20].join("\n");
+        assert!(Samurai::from_str(&short).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::killer::combinations;
+use crate::{
+    Candidates, Constraint, ConstraintSet, House, ParseError, Pos, Size, SolveError, Sudoku,
+    SudokuError,
+};
+
+/// The values between the 1 and the highest value of a row or column,
+/// the 9 on a 9x9 board, add up to `sum`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SandwichClue {
+    /// A `House::Row` or `House::Column`.
+    pub house: House,
+    pub sum: u8,
+}
+
+impl SandwichClue {
+    pub fn new(house: House, sum: u8) -> Self {
+        Self { house, sum }
+    }
+
+    // The union of the values each cell of the line can take over every
+    // place the two crusts could go, or `None` if they can't go anywhere.
+    fn options(&self, board: &Sudoku) -> Option<Vec<Candidates>> {
+        let size = board.size();
+        let top = size.side();
+        let cells = size.house_positions(self.house).collect::<Vec<_>>();
+        let values = cells.iter().map(|&p| board[p].value()).collect::<Vec<_>>();
+        let fillings = Candidates::up_to(top)
+            .difference([1, top].into_iter().collect())
+            .difference(values.iter().flatten().copied().collect());
+        let mut union = None::<Vec<Candidates>>;
+        for one in 0..cells.len() {
+            for nine in (0..cells.len()).filter(|&j| j != one) {
+                let fits = |i: usize, crust: u8| {
+                    values[i].is_none_or(|v| v == crust)
+                        && values
+                            .iter()
+                            .enumerate()
+                            .all(|(j, &v)| j == i || v != Some(crust))
+                };
+                if !fits(one, 1) || !fits(nine, top) {
+                    continue;
+                }
+                let inside = one.min(nine) + 1..one.max(nine);
+                let placed = inside
+                    .clone()
+                    .filter_map(|i| values[i])
+                    .map(u32::from)
+                    .sum::<u32>();
+                let empty = inside.clone().filter(|&i| values[i].is_none()).count();
+                let Some(filling) = u32::from(self.sum)
+                    .checked_sub(placed)
+                    .and_then(|rest| combinations(rest, empty, 2, fillings))
+                else {
+                    continue;
+                };
+                let mut spots = vec![Candidates::empty(); cells.len()];
+                for (i, spot) in spots.iter_mut().enumerate() {
+                    *spot = match values[i] {
+                        Some(v) => [v].into_iter().collect(),
+                        None if i == one => [1].into_iter().collect(),
+                        None if i == nine => [top].into_iter().collect(),
+                        None if inside.contains(&i) => filling,
+                        None => fillings,
+                    };
+                }
+                union = Some(match union {
+                    Some(u) => u.iter().zip(&spots).map(|(&a, &b)| a | b).collect(),
+                    None => spots,
+                });
+            }
+        }
+        union
+    }
+}
+
+impl Constraint for SandwichClue {
+    fn eliminates(&self, board: &Sudoku, pos: Pos) -> Candidates {
+        let size = board.size();
+        let Some(i) = size.house_positions(self.house).position(|p| p == pos) else {
+            return Candidates::empty();
+        };
+        let allowed = self
+            .options(board)
+            .map_or(Candidates::empty(), |spots| spots[i]);
+        Candidates::up_to(size.side()).difference(allowed)
+    }
+    fn is_satisfied(&self, board: &Sudoku) -> bool {
+        self.options(board).is_some()
+    }
+}
+
+/// A board plus sandwich clues for some of its rows and columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandwich {
+    board: Sudoku,
+    clues: Vec<SandwichClue>,
+}
+
+impl Sandwich {
+    /// Clues must be for rows or columns of the board, at most one each,
+    /// with sums the values between the crusts can reach.
+    pub fn new(board: Sudoku, clues: Vec<SandwichClue>) -> Result<Self, SudokuError> {
+        let side = board.size().side();
+        let most = (2..side).map(u32::from).sum::<u32>();
+        for (i, clue) in clues.iter().enumerate() {
+            let line = match clue.house {
+                House::Row(y) => y,
+                House::Column(x) => x,
+                _ => return Err(SudokuError::InvalidClue),
+            };
+            if line >= side {
+                return Err(SudokuError::OutOfBounds);
+            }
+            if u32::from(clue.sum) > most || clues[..i].iter().any(|c| c.house == clue.house) {
+                return Err(SudokuError::InvalidClue);
+            }
+        }
+        Ok(Self { board, clues })
+    }
+    pub fn board(&self) -> &Sudoku {
+        &self.board
+    }
+    pub fn clues(&self) -> &[SandwichClue] {
+        &self.clues
+    }
+    pub fn clue_for(&self, house: House) -> Option<u8> {
+        self.clues.iter().find(|c| c.house == house).map(|c| c.sum)
+    }
+    /// The cell's candidates, narrowed down by the clues of its row and
+    /// column.
+    pub fn candidates(&self, pos: Pos) -> Candidates {
+        self.constraints().candidates(&self.board, pos)
+    }
+    /// The board's houses plus one constraint per clue.
+    pub fn constraints(&self) -> ConstraintSet {
+        let mut set = ConstraintSet::standard(&self.board);
+        for &clue in &self.clues {
+            set.push(clue);
+        }
+        set
+    }
+    pub fn is_valid(&self) -> bool {
+        self.constraints().is_satisfied(&self.board)
+    }
+    pub fn is_solved(&self) -> bool {
+        self.board.is_solved() && self.is_valid()
+    }
+    pub fn solve(&self) -> Result<Sudoku, SolveError> {
+        self.constraints().solve(&self.board)
+    }
+}
+
+/// One clue per line: a row or column counted from 1, then its sum, e.g.
+/// `r1 12` or `c4 0`. Blank lines and lines starting with `#` are skipped.
+/// The board is an empty 9x9 one; use [`Sandwich::new`] for puzzles with
+/// givens.
+impl core::str::FromStr for Sandwich {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut clues = Vec::new();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let house = words.next().and_then(|w| {
+                let (kind, n) = w.split_at_checked(1)?;
+                let n = n.parse::<u8>().ok()?.checked_sub(1)?;
+                match kind {
+                    "r" | "R" => Some(House::Row(n)),
+                    "c" | "C" => Some(House::Column(n)),
+                    _ => None,
+                }
+            });
+            let sum = words.next().and_then(|w| w.parse::<u8>().ok());
+            match (house, sum, words.next()) {
+                (Some(house), Some(sum), None) => clues.push(SandwichClue::new(house, sum)),
+                _ => {
+                    return Err(SudokuError::ParseError(ParseError::Invalid(
+                        "Invalid sandwich clue.",
+                    )))
+                }
+            }
+        }
+        Sandwich::new(Sudoku::new(Size::CLASSIC), clues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn clues_narrow_candidates() {
+        let s = Sandwich::from_str("# crusts at both ends\nr1 35\n\nc1 0\n").unwrap();
+        assert_eq!(s.clue_for(House::Column(0)), Some(0));
+        assert_eq!(s.clue_for(House::Row(4)), None);
+        let values = |x, y| {
+            s.candidates(Pos::new_unchecked(x, y))
+                .iter()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(8, 0), vec![1, 9]);
+        assert_eq!(values(4, 0), (2..=8).collect::<Vec<_>>());
+        assert_eq!(values(0, 0), vec![1, 9]);
+        assert_eq!(values(0, 2), (1..=9).collect::<Vec<_>>());
+
+        let mut board = Sudoku::new(Size::CLASSIC);
+        board.set(Pos::new_unchecked(0, 0), Some(1)).unwrap();
+        let s = Sandwich::new(board, s.clues().to_vec()).unwrap();
+        let values = |x, y| {
+            s.candidates(Pos::new_unchecked(x, y))
+                .iter()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(8, 0), vec![9]);
+        assert_eq!(values(0, 1), vec![9]);
+        assert_eq!(values(0, 2), (2..=8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn rejects_bad_clues() {
+        assert_eq!(Sandwich::from_str("r1 36"), Err(SudokuError::InvalidClue));
+        assert_eq!(
+            Sandwich::from_str("r1 3\nr1 4"),
+            Err(SudokuError::InvalidClue)
+        );
+        assert_eq!(
+            Sandwich::from_str("b1 3"),
+            Err(SudokuError::ParseError(ParseError::Invalid(
+                "Invalid sandwich clue."
+            )))
+        );
+        let mut board = Sudoku::new(Size::CLASSIC);
+        board.set(Pos::new_unchecked(0, 0), Some(1)).unwrap();
+        board.set(Pos::new_unchecked(2, 0), Some(9)).unwrap();
+        let s = Sandwich::new(board, vec![SandwichClue::new(House::Row(0), 10)]).unwrap();
+        let values = s
+            .candidates(Pos::new_unchecked(1, 0))
+            .iter()
+            .collect::<Vec<_>>();
+        assert!(values.is_empty());
+        assert!(!s.is_valid());
+        assert_eq!(s.solve(), Err(SolveError::InvalidPuzzle));
+    }
+
+    #[test]
+    fn solves_sandwich_puzzle() {
+        let solution = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap()
+        .solve()
+        .unwrap();
+        let line = solution.to_line_string();
+        let clue = |house| {
+            let values = solution
+                .house(house)
+                .map(|c| c.value().unwrap())
+                .collect::<Vec<_>>();
+            let one = values.iter().position(|&v| v == 1).unwrap();
+            let nine = values.iter().position(|&v| v == 9).unwrap();
+            let between = &values[one.min(nine) + 1..one.max(nine)];
+            SandwichClue::new(house, between.iter().sum())
+        };
+        let clues = (0..9)
+            .flat_map(|i| [clue(House::Row(i)), clue(House::Column(i))])
+            .collect::<Vec<_>>();
+        let givens = line
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i % 3 == 0 { c } else { '.' })
+            .collect::<alloc::string::String>();
+        let s = Sandwich::new(Sudoku::from_str(&givens).unwrap(), clues).unwrap();
+        assert!(s.is_valid());
+        let solved = s.solve().unwrap();
+        assert!(solved.is_solved());
+        assert!(s.clues().iter().all(|c| c.is_satisfied(&solved)));
+    }
+}
@@ -0,0 +1,198 @@
+//! A [`ratatui`] widget that draws a [`Sudoku`] board with a selection
+//! cursor, highlighted peers and optional pencil marks.
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Widget;
+use sudoku::{Pos, Sudoku};
+
+pub struct SudokuWidget<'a> {
+    board: &'a Sudoku,
+    cursor: Option<Pos>,
+    highlight_peers: bool,
+    candidates: bool,
+    pub given: Style,
+    pub entry: Style,
+    pub conflict: Style,
+    pub candidate: Style,
+    pub cursor_style: Style,
+    pub peer: Style,
+    pub border: Style,
+}
+
+impl<'a> SudokuWidget<'a> {
+    pub fn new(board: &'a Sudoku) -> Self {
+        Self {
+            board,
+            cursor: None,
+            highlight_peers: true,
+            candidates: false,
+            given: Style::default().add_modifier(Modifier::BOLD),
+            entry: Style::default().fg(Color::Blue),
+            conflict: Style::default().fg(Color::Red),
+            candidate: Style::default().add_modifier(Modifier::DIM),
+            cursor_style: Style::default().bg(Color::Yellow).fg(Color::Black),
+            peer: Style::default().bg(Color::DarkGray),
+            border: Style::default(),
+        }
+    }
+    pub fn cursor(mut self, pos: Option<Pos>) -> Self {
+        self.cursor = pos;
+        self
+    }
+    /// Shades the cells sharing a house with the cursor. On by default.
+    pub fn highlight_peers(mut self, on: bool) -> Self {
+        self.highlight_peers = on;
+        self
+    }
+    /// Draws each cell as a small grid of its pencil marks.
+    pub fn candidates(mut self, on: bool) -> Self {
+        self.candidates = on;
+        self
+    }
+    /// Width and height the board needs, borders included.
+    pub fn size(&self) -> (u16, u16) {
+        let n = self.board.size().box_size() as u16;
+        let (w, gap) = self.cell_shape();
+        let span = n * (n * w + (n - 1) * gap) + n + 1;
+        let lines = n * n * w + n + 1;
+        (span, lines)
+    }
+
+    // Characters per cell side, and the space between cells of a box.
+    fn cell_shape(&self) -> (u16, u16) {
+        if self.candidates {
+            (self.board.size().box_size() as u16, 1)
+        } else {
+            (1, 0)
+        }
+    }
+
+    fn is_peer(&self, pos: Pos) -> bool {
+        match self.cursor {
+            Some(c) if self.highlight_peers && c != pos => self
+                .board
+                .houses_containing(c)
+                .into_iter()
+                .any(|h| self.board.size().house_positions(h).any(|p| p == pos)),
+            _ => false,
+        }
+    }
+}
+
+fn symbol(v: u8) -> char {
+    match v {
+        1..=9 => (b'0' + v) as char,
+        _ => (b'A' + v - 10) as char,
+    }
+}
+
+impl Widget for SudokuWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let n = self.board.size().box_size() as u16;
+        let (w, gap) = self.cell_shape();
+        let (width, height) = self.size();
+        let mut put = |x: u16, y: u16, c: char, style: Style| {
+            if x < area.width && y < area.height {
+                buf.set_string(area.x + x, area.y + y, c.to_string(), style);
+            }
+        };
+
+        let box_width = n * w + (n - 1) * gap + 1;
+        let box_height = n * w + 1;
+        for y in 0..height {
+            for x in 0..width {
+                let (on_column, on_row) = (x % box_width == 0, y % box_height == 0);
+                let c = match (on_column, on_row) {
+                    (true, true) => '+',
+                    (false, true) => '-',
+                    (true, false) => '|',
+                    _ => continue,
+                };
+                put(x, y, c, self.border);
+            }
+        }
+
+        let conflicts = self
+            .board
+            .conflicts()
+            .into_iter()
+            .flat_map(|(a, b)| [a, b])
+            .collect::<Vec<_>>();
+        for cell in self.board.iter() {
+            let pos = cell.position();
+            let (cx, cy) = (pos.x() as u16, pos.y() as u16);
+            let left = 1 + (cx / n) * box_width + (cx % n) * (w + gap);
+            let top = 1 + (cy / n) * box_height + (cy % n) * w;
+            let background = if self.cursor == Some(pos) {
+                self.cursor_style
+            } else if self.is_peer(pos) {
+                self.peer
+            } else {
+                Style::default()
+            };
+            for line in 0..w {
+                for column in 0..w {
+                    let middle = line == w / 2 && column == w / 2;
+                    let (c, style) = match cell.value() {
+                        Some(v) if middle => {
+                            let style = if conflicts.contains(&pos) {
+                                self.conflict
+                            } else if cell.is_given() {
+                                self.given
+                            } else {
+                                self.entry
+                            };
+                            (symbol(v), style)
+                        }
+                        None if self.candidates => {
+                            let v = (line * n + column + 1) as u8;
+                            let c = if cell.pencil_marks().contains(v) {
+                                symbol(v)
+                            } else {
+                                ' '
+                            };
+                            (c, self.candidate)
+                        }
+                        None => ('.', self.candidate),
+                        _ => (' ', Style::default()),
+                    };
+                    put(left + column, top + line, c, background.patch(style));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn draws_values_and_cursor() {
+        let board = Sudoku::from_str(
+            ".5..83.17...1..4..3.4..56.8....3...9.9.8245....6....7...9....5...729..861.36.72.4",
+        )
+        .unwrap();
+        let widget = SudokuWidget::new(&board).cursor(Some(Pos::new_unchecked(0, 0)));
+        assert_eq!(widget.size(), (13, 13));
+        let area = Rect::new(0, 0, 13, 13);
+        let mut buf = Buffer::empty(area);
+        widget.render(area, &mut buf);
+        assert_eq!(buf[(0, 0)].symbol(), "+");
+        assert_eq!(buf[(2, 1)].symbol(), "5");
+        assert_eq!(buf[(1, 1)].bg, Color::Yellow);
+        assert_eq!(buf[(2, 2)].bg, Color::DarkGray);
+        assert_eq!(buf[(5, 5)].bg, Color::Reset);
+    }
+
+    #[test]
+    fn candidates_make_cells_larger() {
+        let board = Sudoku::new(sudoku::Size::CLASSIC);
+        let widget = SudokuWidget::new(&board).candidates(true);
+        assert_eq!(widget.size(), (37, 31));
+    }
+}
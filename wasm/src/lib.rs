@@ -0,0 +1,102 @@
+use std::str::FromStr;
+
+use sudoku::{formats, Technique};
+use wasm_bindgen::prelude::*;
+
+// Boards cross the boundary as 81-character strings with `.` for blanks.
+
+fn board(line: &str) -> Result<sudoku::Sudoku, JsError> {
+    sudoku::Sudoku::from_str(line).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Parses any format the crate understands and returns the line form.
+#[wasm_bindgen]
+pub fn parse(input: &str) -> Result<String, JsError> {
+    formats::parse(input)
+        .map(|s| s.to_line_string())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn solve(line: &str) -> Result<String, JsError> {
+    board(line)?
+        .solve_with_propagation()
+        .map(|s| s.to_line_string())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// A new puzzle for `difficulty` (easy, medium, hard or expert). The same
+/// seed always gives the same puzzle.
+#[wasm_bindgen]
+pub fn generate(seed: u64, difficulty: &str) -> Result<String, JsError> {
+    let difficulty = difficulty
+        .parse()
+        .map_err(|e: sudoku::SudokuError| JsError::new(&e.to_string()))?;
+    Ok(sudoku::Sudoku::generate_with_seed(seed, difficulty).to_line_string())
+}
+
+#[wasm_bindgen]
+pub fn grade(line: &str) -> Result<String, JsError> {
+    Ok(format!("{:?}", board(line)?.grade()))
+}
+
+/// Values in index order, 0 for blanks.
+#[wasm_bindgen(js_name = toFlatArray)]
+pub fn to_flat_array(line: &str) -> Result<Vec<u8>, JsError> {
+    Ok(board(line)?.to_flat_array().to_vec())
+}
+
+#[wasm_bindgen(js_name = fromFlatArray)]
+pub fn from_flat_array(values: &[u8]) -> Result<String, JsError> {
+    let values: [u8; 81] = values
+        .try_into()
+        .map_err(|_| JsError::new("Expected 81 values."))?;
+    sudoku::Sudoku::try_from(values)
+        .map(|s| s.to_line_string())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub struct Hint {
+    technique: Technique,
+    positions: Vec<u8>,
+    eliminations: Vec<u8>,
+    placement: Option<(u8, u8)>,
+}
+
+#[wasm_bindgen]
+impl Hint {
+    #[wasm_bindgen(getter)]
+    pub fn technique(&self) -> String {
+        format!("{:?}", self.technique)
+    }
+    /// Cell indices the deduction is based on.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Vec<u8> {
+        self.positions.clone()
+    }
+    /// Flat `[index, value, index, value, ...]` pairs.
+    #[wasm_bindgen(getter)]
+    pub fn eliminations(&self) -> Vec<u8> {
+        self.eliminations.clone()
+    }
+    /// `[index, value]`, or empty if the hint only eliminates candidates.
+    #[wasm_bindgen(getter)]
+    pub fn placement(&self) -> Vec<u8> {
+        self.placement.map_or_else(Vec::new, |(i, v)| vec![i, v])
+    }
+}
+
+#[wasm_bindgen]
+pub fn hint(line: &str) -> Result<Option<Hint>, JsError> {
+    Ok(board(line)?.hint().map(|h| Hint {
+        technique: h.technique,
+        positions: h.positions.iter().map(|p| p.to_index() as u8).collect(),
+        eliminations: h
+            .eliminations
+            .iter()
+            .flat_map(|&(p, v)| [p.to_index() as u8, v])
+            .collect(),
+        placement: h.placement.map(|(p, v)| (p.to_index() as u8, v)),
+    }))
+}